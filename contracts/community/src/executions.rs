@@ -4,6 +4,7 @@ use cosmwasm_std::{
 use cw20::Cw20ExecuteMsg;
 
 use crate::error::ContractError;
+use messages::pause::{set_paused, set_pausers, PauseInfo, PauseOperation};
 
 use crate::state::{Allowance, ContractConfig, ContractState};
 
@@ -127,6 +128,8 @@ pub fn transfer(
     recipient: String,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Distributing)?;
+
     let config = ContractConfig::load(deps.storage)?;
     let mut state = ContractState::load(deps.storage)?;
 
@@ -182,3 +185,36 @@ pub fn transfer(
 
     Ok(response)
 }
+
+pub fn pause(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    paused: bool,
+    operations: Option<Vec<PauseOperation>>,
+) -> Result<Response, ContractError> {
+    if !PauseInfo::load(deps.storage)?.is_pauser(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(set_paused(deps.storage, paused, operations)?)
+}
+
+pub fn update_pausers(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    guardian: Option<String>,
+    governance: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = ContractConfig::load(deps.storage)?;
+    let pause_info = PauseInfo::load(deps.storage)?;
+    if !config.is_admin(&info.sender) && pause_info.governance.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let guardian = guardian.map(|v| deps.api.addr_validate(&v)).transpose()?;
+    let governance = governance.map(|v| deps.api.addr_validate(&v)).transpose()?;
+
+    Ok(set_pausers(deps.storage, guardian, governance)?)
+}
@@ -1,6 +1,7 @@
 use cosmwasm_std::{Deps, Env};
 
 use crate::error::ContractError;
+use messages::pause::PauseInfo;
 
 use messages::community::query_msgs::{
     AllowanceResponse, AllowancesResponse, BalanceResponse, ContractConfigResponse,
@@ -15,6 +16,7 @@ pub fn get_config(deps: Deps, _env: Env) -> Result<ContractConfigResponse, Contr
     Ok(ContractConfigResponse {
         admins: config.admins.iter().map(|v| v.to_string()).collect(),
         managing_token: config.managing_token.to_string(),
+        pause: PauseInfo::load(deps.storage)?,
     })
 }
 
@@ -1,15 +1,17 @@
 #[cfg(test)]
 mod community_tests {
     use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-    use cosmwasm_std::{from_binary, to_binary, CosmosMsg, DepsMut, Uint128, WasmMsg};
+    use cosmwasm_std::{from_binary, to_binary, Addr, CosmosMsg, DepsMut, Uint128, WasmMsg};
+    use cw2::set_contract_version;
     use cw20::Cw20ExecuteMsg;
 
     use testutils::mock_querier::mock_dependencies;
 
     use crate::entrypoints;
     use crate::error::ContractError;
-    use messages::community::execute_msgs::{ExecuteMsg, InstantiateMsg};
+    use messages::community::execute_msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg};
     use messages::community::query_msgs::{AllowanceResponse, ContractConfigResponse, QueryMsg};
+    use messages::pause::{PauseInfo, PauseOperation};
 
     const POLLTERRA_TOKEN: &str = "pollterra_token";
     const CREATOR: &str = "creator";
@@ -19,11 +21,15 @@ mod community_tests {
     const NON_ADMIN: &str = "non_admin";
     const RECEIVER: &str = "receiver";
     const RECIPIENT: &str = "recipient";
+    const GUARDIAN: &str = "guardian";
+    const GOVERNANCE: &str = "governance";
 
     fn mock_instantiate(deps: DepsMut) {
         let msg = InstantiateMsg {
             admins: vec![ADMIN_0.to_string(), ADMIN_1.to_string()],
             managing_token: POLLTERRA_TOKEN.to_string(),
+            guardian: Some(GUARDIAN.to_string()),
+            governance: Some(GOVERNANCE.to_string()),
         };
 
         let info = mock_info(CREATOR, &[]);
@@ -460,4 +466,115 @@ mod community_tests {
             Err(ContractError::InsufficientRemainAmount(_allowance_amount))
         ));
     }
+
+    #[test]
+    fn pause_transfer() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+
+        deps.querier.with_token_balances(&[(
+            &POLLTERRA_TOKEN.to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(1_000_000u128),
+            )],
+        )]);
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: RECIPIENT.to_string(),
+            amount: Uint128::from(100u128),
+        };
+
+        // only the guardian and governance can pause, not even the admins
+        for sender in [ADMIN_0, NON_ADMIN] {
+            let msg = ExecuteMsg::Pause { operations: None };
+            assert!(matches!(
+                entrypoints::execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg),
+                Err(ContractError::Unauthorized {})
+            ));
+        }
+
+        let msg = ExecuteMsg::Pause {
+            operations: Some(vec![PauseOperation::Distributing]),
+        };
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(GUARDIAN, &[]), msg).unwrap();
+        let info = mock_info(ADMIN_0, &[]);
+        let err = entrypoints::execute(deps.as_mut(), mock_env(), info, transfer_msg.clone())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: distributing is paused");
+
+        let msg = ExecuteMsg::Unpause {
+            operations: Some(vec![PauseOperation::Distributing]),
+        };
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(GUARDIAN, &[]), msg).unwrap();
+        let info = mock_info(ADMIN_0, &[]);
+        entrypoints::execute(deps.as_mut(), mock_env(), info, transfer_msg.clone()).unwrap();
+
+        // a global pause halts every operation
+        let msg = ExecuteMsg::Pause { operations: None };
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(GOVERNANCE, &[]), msg).unwrap();
+        let info = mock_info(ADMIN_0, &[]);
+        assert!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, transfer_msg.clone()).is_err()
+        );
+
+        let msg = ExecuteMsg::Unpause { operations: None };
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(GOVERNANCE, &[]), msg).unwrap();
+        let info = mock_info(ADMIN_0, &[]);
+        entrypoints::execute(deps.as_mut(), mock_env(), info, transfer_msg).unwrap();
+    }
+
+    #[test]
+    fn update_pausers() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+
+        let msg = ExecuteMsg::UpdatePausers {
+            guardian: Some(NEW_ADMIN.to_string()),
+            governance: Some(GOVERNANCE.to_string()),
+        };
+        for sender in [GUARDIAN, NON_ADMIN] {
+            assert!(matches!(
+                entrypoints::execute(
+                    deps.as_mut(),
+                    mock_env(),
+                    mock_info(sender, &[]),
+                    msg.clone()
+                ),
+                Err(ContractError::Unauthorized {})
+            ));
+        }
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(ADMIN_0, &[]), msg).unwrap();
+
+        // the replaced guardian cannot pause anymore
+        let msg = ExecuteMsg::Pause { operations: None };
+        assert!(matches!(
+            entrypoints::execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(GUARDIAN, &[]),
+                msg.clone()
+            ),
+            Err(ContractError::Unauthorized {})
+        ));
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(NEW_ADMIN, &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn migrate_pausers() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+        set_contract_version(&mut deps.storage, "crates.io:community", "0.1.0").unwrap();
+
+        // the unset governance is kept
+        let msg = MigrateMsg {
+            admins: None,
+            guardian: Some(NEW_ADMIN.to_string()),
+            governance: None,
+        };
+        entrypoints::migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+        let pause_info = PauseInfo::load(&deps.storage).unwrap();
+        assert_eq!(pause_info.guardian, Some(Addr::unchecked(NEW_ADMIN)));
+        assert_eq!(pause_info.governance, Some(Addr::unchecked(GOVERNANCE)));
+    }
 }
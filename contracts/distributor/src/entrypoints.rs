@@ -11,6 +11,8 @@ use crate::state::{ContractConfig, ContractState};
 use crate::{executions, queries};
 use messages::distributor::execute_msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg};
 use messages::distributor::query_msgs::QueryMsg;
+use messages::migration::migrate_version;
+use messages::pause::{migrate_pausers, set_pausers};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:distributor";
@@ -40,6 +42,16 @@ pub fn instantiate(
     }
     .save(deps.storage)?;
 
+    let guardian = msg
+        .guardian
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+    let governance = msg
+        .governance
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+    set_pausers(deps.storage, guardian, governance)?;
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new().add_attribute("method", "instantiate"))
@@ -93,12 +105,42 @@ pub fn execute(
         ExecuteMsg::Transfer { recipient, amount } => {
             executions::transfer(deps, env, info, recipient, amount)
         }
+        ExecuteMsg::Pause { operations } => executions::pause(deps, env, info, true, operations),
+        ExecuteMsg::Unpause { operations } => executions::pause(deps, env, info, false, operations),
+        ExecuteMsg::UpdatePausers {
+            guardian,
+            governance,
+        } => executions::update_pausers(deps, env, info, guardian, governance),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let from = migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if let Some(admins) = msg.admins {
+        let mut config = ContractConfig::load(deps.storage)?;
+        config.admins = admins
+            .iter()
+            .map(|v| deps.api.addr_validate(v))
+            .collect::<StdResult<Vec<Addr>>>()?;
+        config.save(deps.storage)?;
+    }
+
+    let guardian = msg
+        .guardian
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+    let governance = msg
+        .governance
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+    migrate_pausers(deps.storage, guardian, governance)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", from.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
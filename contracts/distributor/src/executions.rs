@@ -6,6 +6,7 @@ use cw20::Cw20ExecuteMsg;
 
 use crate::error::ContractError;
 use crate::state::{ContractConfig, ContractState, Distribution};
+use messages::pause::{set_paused, set_pausers, PauseInfo, PauseOperation};
 use messages::utils;
 
 pub fn update_admins(
@@ -184,6 +185,8 @@ pub fn distribute(
     _info: MessageInfo,
     id: Option<u64>,
 ) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Distributing)?;
+
     let mut distributions = if let Some(id) = id {
         vec![Distribution::may_load(deps.storage, id)?.ok_or_else(|| {
             StdError::generic_err("This id is expired distribution or invalid id")
@@ -302,3 +305,36 @@ pub fn transfer(
 
     Ok(response)
 }
+
+pub fn pause(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    paused: bool,
+    operations: Option<Vec<PauseOperation>>,
+) -> Result<Response, ContractError> {
+    if !PauseInfo::load(deps.storage)?.is_pauser(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(set_paused(deps.storage, paused, operations)?)
+}
+
+pub fn update_pausers(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    guardian: Option<String>,
+    governance: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = ContractConfig::load(deps.storage)?;
+    let pause_info = PauseInfo::load(deps.storage)?;
+    if !config.is_admin(&info.sender) && pause_info.governance.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let guardian = guardian.map(|v| deps.api.addr_validate(&v)).transpose()?;
+    let governance = governance.map(|v| deps.api.addr_validate(&v)).transpose()?;
+
+    Ok(set_pausers(deps.storage, guardian, governance)?)
+}
@@ -5,6 +5,7 @@ use crate::state::{ContractConfig, ContractState, Distribution};
 use messages::distributor::query_msgs::{
     ContractConfigResponse, DistributionResponse, DistributionsResponse, StateResponse,
 };
+use messages::pause::PauseInfo;
 use messages::utils::query_cw20_balance;
 
 pub fn get_config(deps: Deps, _env: Env) -> Result<ContractConfigResponse, ContractError> {
@@ -13,6 +14,7 @@ pub fn get_config(deps: Deps, _env: Env) -> Result<ContractConfigResponse, Contr
     Ok(ContractConfigResponse {
         admins: config.admins.iter().map(|v| v.to_string()).collect(),
         managing_token: config.managing_token.to_string(),
+        pause: PauseInfo::load(deps.storage)?,
     })
 }
 
@@ -2,18 +2,20 @@
 mod distributor_tests {
     use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
     use cosmwasm_std::{
-        attr, from_binary, to_binary, Binary, CosmosMsg, DepsMut, SubMsg, Uint128, WasmMsg,
+        attr, from_binary, to_binary, Addr, Binary, CosmosMsg, DepsMut, SubMsg, Uint128, WasmMsg,
     };
+    use cw2::set_contract_version;
     use cw20::Cw20ExecuteMsg;
 
     use testutils::mock_querier::mock_dependencies;
 
     use crate::entrypoints;
     use crate::error::ContractError;
-    use messages::distributor::execute_msgs::{ExecuteMsg, InstantiateMsg};
+    use messages::distributor::execute_msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg};
     use messages::distributor::query_msgs::{
         ContractConfigResponse, DistributionsResponse, QueryMsg,
     };
+    use messages::pause::{PauseInfo, PauseOperation};
 
     const POLLTERRA_TOKEN: &str = "pollterra_token";
     const CREATOR: &str = "creator";
@@ -23,11 +25,15 @@ mod distributor_tests {
     const NEW_ADMIN: &str = "new_admin";
     const RECIPIENT: &str = "recipient";
     const RECIPIENT_2: &str = "recipient2";
+    const GUARDIAN: &str = "guardian";
+    const GOVERNANCE: &str = "governance";
 
     fn mock_instantiate(deps: DepsMut) {
         let msg = InstantiateMsg {
             admins: vec![ADMIN_0.to_string(), ADMIN_1.to_string()],
             managing_token: POLLTERRA_TOKEN.to_string(),
+            guardian: Some(GUARDIAN.to_string()),
+            governance: Some(GOVERNANCE.to_string()),
         };
 
         let info = mock_info(CREATOR, &[]);
@@ -593,4 +599,120 @@ mod distributor_tests {
             Err(ContractError::Unauthorized {})
         ));
     }
+
+    #[test]
+    fn pause_distribute() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+
+        deps.querier.with_token_balances(&[(
+            &POLLTERRA_TOKEN.to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(20_000u128))],
+        )]);
+        let msg = ExecuteMsg::RegisterDistribution {
+            start_height: 10000,
+            end_height: 30000,
+            recipient: RECIPIENT.to_string(),
+            amount: Uint128::new(10_000),
+            message: None,
+        };
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(ADMIN_0, &[]), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.height = 20000;
+        let distribute_msg = ExecuteMsg::Distribute { id: None };
+
+        // only the guardian and governance can pause, not even the admins
+        for sender in [ADMIN_0, NOT_ADMIN] {
+            let msg = ExecuteMsg::Pause { operations: None };
+            assert!(matches!(
+                entrypoints::execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg),
+                Err(ContractError::Unauthorized {})
+            ));
+        }
+
+        let msg = ExecuteMsg::Pause {
+            operations: Some(vec![PauseOperation::Distributing]),
+        };
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(GUARDIAN, &[]), msg).unwrap();
+        let info = mock_info(ADMIN_0, &[]);
+        let err = entrypoints::execute(deps.as_mut(), env.clone(), info, distribute_msg.clone())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: distributing is paused");
+
+        let msg = ExecuteMsg::Unpause {
+            operations: Some(vec![PauseOperation::Distributing]),
+        };
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(GUARDIAN, &[]), msg).unwrap();
+
+        // a global pause halts every operation
+        let msg = ExecuteMsg::Pause { operations: None };
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(GOVERNANCE, &[]), msg).unwrap();
+        let info = mock_info(ADMIN_0, &[]);
+        assert!(
+            entrypoints::execute(deps.as_mut(), env.clone(), info, distribute_msg.clone()).is_err()
+        );
+
+        let msg = ExecuteMsg::Unpause { operations: None };
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(GOVERNANCE, &[]), msg).unwrap();
+        let info = mock_info(ADMIN_0, &[]);
+        let res = entrypoints::execute(deps.as_mut(), env, info, distribute_msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
+
+    #[test]
+    fn update_pausers() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+
+        let msg = ExecuteMsg::UpdatePausers {
+            guardian: Some(NEW_ADMIN.to_string()),
+            governance: Some(GOVERNANCE.to_string()),
+        };
+        for sender in [GUARDIAN, NOT_ADMIN] {
+            assert!(matches!(
+                entrypoints::execute(
+                    deps.as_mut(),
+                    mock_env(),
+                    mock_info(sender, &[]),
+                    msg.clone()
+                ),
+                Err(ContractError::Unauthorized {})
+            ));
+        }
+        // governance replaces the pausers as well as the admins
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(GOVERNANCE, &[]), msg).unwrap();
+
+        // the replaced guardian cannot pause anymore
+        let msg = ExecuteMsg::Pause { operations: None };
+        assert!(matches!(
+            entrypoints::execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(GUARDIAN, &[]),
+                msg.clone()
+            ),
+            Err(ContractError::Unauthorized {})
+        ));
+        entrypoints::execute(deps.as_mut(), mock_env(), mock_info(NEW_ADMIN, &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn migrate_pausers() {
+        let mut deps = mock_dependencies(&[]);
+        mock_instantiate(deps.as_mut());
+        set_contract_version(&mut deps.storage, "crates.io:distributor", "0.1.0").unwrap();
+
+        // the unset guardian is kept
+        let msg = MigrateMsg {
+            admins: None,
+            guardian: None,
+            governance: Some(NEW_ADMIN.to_string()),
+        };
+        entrypoints::migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+        let pause_info = PauseInfo::load(&deps.storage).unwrap();
+        assert_eq!(pause_info.guardian, Some(Addr::unchecked(GUARDIAN)));
+        assert_eq!(pause_info.governance, Some(Addr::unchecked(NEW_ADMIN)));
+    }
 }
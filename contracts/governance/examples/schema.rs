@@ -6,7 +6,9 @@ use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use messages::governance::execute_msgs::*;
 use messages::governance::query_msgs::*;
 use messages::governance::state::{
-    ConfigResponse, PollResponse, PollsResponse, StakerResponse, StateResponse, VotersResponse,
+    ConfigResponse, DelegationResponse, LockedPollsResponse, PollResponse, PollsResponse,
+    StakerResponse, StakerRewardsResponse, StateResponse, TallyResponse, UnbondingsResponse,
+    VoterHistoryResponse, VotersResponse,
 };
 
 fn main() {
@@ -22,9 +24,15 @@ fn main() {
     export_schema(&schema_for!(QueryMsg), &out_dir);
 
     export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(DelegationResponse), &out_dir);
+    export_schema(&schema_for!(LockedPollsResponse), &out_dir);
     export_schema(&schema_for!(PollResponse), &out_dir);
     export_schema(&schema_for!(PollsResponse), &out_dir);
     export_schema(&schema_for!(StakerResponse), &out_dir);
+    export_schema(&schema_for!(StakerRewardsResponse), &out_dir);
     export_schema(&schema_for!(StateResponse), &out_dir);
+    export_schema(&schema_for!(TallyResponse), &out_dir);
+    export_schema(&schema_for!(UnbondingsResponse), &out_dir);
+    export_schema(&schema_for!(VoterHistoryResponse), &out_dir);
     export_schema(&schema_for!(VotersResponse), &out_dir);
 }
@@ -1,24 +1,50 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, CanonicalAddr, Decimal, Deps, DepsMut, Env, MessageInfo, Response, Uint128,
+    to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Reply, Response, Uint128,
 };
+use cw2::set_contract_version;
 
 use crate::error::ContractError;
-use crate::staking::{query_staker, withdraw_voting_tokens};
-use crate::validators::{validate_poll_period, validate_quorum, validate_threshold};
+use crate::migrations;
+use crate::replies;
+use crate::staking::{
+    claim_rewards, claim_unbonded, compound_rewards, deposit_native_reward, extend_lock,
+    query_staker, query_staker_rewards, query_total_ve_power, query_unbondings, query_ve_lock,
+    withdraw_asset_tokens, withdraw_expired, withdraw_voting_tokens,
+};
+use crate::validators::{
+    validate_poll_period, validate_quorum, validate_threshold, validate_veto_threshold,
+};
 use messages::governance::execute_msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg};
 use messages::governance::query_msgs::QueryMsg;
-use messages::governance::state::{config_store, state_store, Config, State};
+use messages::governance::state::{Config, State, CONFIG, STATE};
+use messages::migration::{migrate_version, Version};
+use messages::pause::set_pausers;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:governance";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub(crate) const MAX_QUORUM: Decimal = Decimal::one();
 pub(crate) const MAX_THRESHOLD: Decimal = Decimal::one();
+pub(crate) const MAX_VETO_THRESHOLD: Decimal = Decimal::one();
 pub(crate) const MIN_TITLE_LENGTH: usize = 4;
 pub(crate) const MAX_TITLE_LENGTH: usize = 64;
 pub(crate) const MIN_DESC_LENGTH: usize = 4;
 pub(crate) const MAX_DESC_LENGTH: usize = 1024;
 pub(crate) const MIN_LINK_LENGTH: usize = 12;
 pub(crate) const MAX_LINK_LENGTH: usize = 128;
+pub(crate) const MAX_DELEGATORS: usize = 100;
+pub(crate) const MAX_UNBONDINGS: usize = 30;
+pub(crate) const MAX_LOCK_WEEKS: u64 = 208;
+pub(crate) const MAX_DEPOSITORS: usize = 20;
+// shares of the proposal deposit, every depositor is refunded when the poll ends
+pub(crate) const MIN_INITIAL_DEPOSIT_PERCENT: u64 = 10;
+pub(crate) const MIN_ADDED_DEPOSIT_PERCENT: u64 = 1;
+
+// every poll execution replies with its own id, offset from this base
+pub(crate) const EXECUTE_POLL_REPLY_ID_BASE: u64 = 1 << 32;
 
 use crate::executions;
 use crate::queries;
@@ -32,28 +58,53 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     validate_quorum(msg.quorum)?;
     validate_threshold(msg.threshold)?;
-    validate_poll_period(msg.timelock_period, msg.expiration_period)?;
-
+    validate_veto_threshold(msg.veto_threshold)?;
     let config = Config {
-        pollterra_token: CanonicalAddr::from(vec![]),
-        owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+        pollterra_token: Addr::unchecked(""),
+        owner: info.sender,
         quorum: msg.quorum,
         threshold: msg.threshold,
+        veto_threshold: msg.veto_threshold,
         voting_period: msg.voting_period,
         timelock_period: msg.timelock_period,
         expiration_period: msg.expiration_period,
         proposal_deposit: msg.proposal_deposit,
         snapshot_period: msg.snapshot_period,
+        unbonding_period: msg.unbonding_period,
+        text_poll_params: None,
+        target_poll_params: vec![],
+        deposit_period: None,
+        stake_assets: vec![],
+        max_lock_weeks: 0,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
     };
+    validate_poll_period(&config)?;
+    let guardian = msg
+        .guardian
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
 
     let state = State {
-        contract_addr: deps.api.addr_canonicalize(env.contract.address.as_str())?,
+        contract_addr: env.contract.address.clone(),
         poll_count: 0,
         total_share: Uint128::zero(),
         total_deposit: Uint128::zero(),
+        reward_index: Decimal::zero(),
+        pending_reward: Uint128::zero(),
+        native_reward_index: Decimal::zero(),
+        native_reward_balance: Uint128::zero(),
+        total_unbonding: Uint128::zero(),
+        total_locked: Uint128::zero(),
     };
-    config_store(deps.storage).save(&config)?;
-    state_store(deps.storage).save(&state)?;
+    CONFIG.save(deps.storage, &config)?;
+    STATE.save(deps.storage, &state)?;
+
+    // governance pauses itself through the messages of its polls
+    set_pausers(deps.storage, guardian, Some(env.contract.address))?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::default())
 }
@@ -77,37 +128,84 @@ pub fn execute(
             vote,
             amount,
         } => executions::cast_vote(deps, _env, info, poll_id, vote, amount),
+        ExecuteMsg::Delegate { delegate } => executions::delegate(deps, _env, info, delegate),
+        ExecuteMsg::Undelegate {} => executions::undelegate(deps, info),
         // Mark a poll as ended
         ExecuteMsg::EndPoll { poll_id } => executions::end_poll(deps, _env, poll_id),
+        ExecuteMsg::CancelPoll { poll_id } => executions::cancel_poll(deps, _env, info, poll_id),
+        ExecuteMsg::VetoPoll { poll_id } => executions::veto_poll(deps, _env, info, poll_id),
         // Execute the associated messages of a passed poll
         ExecuteMsg::ExecutePoll { poll_id } => executions::execute_poll(deps, _env, poll_id),
+        ExecuteMsg::ExecutePollMsgs { poll_id } => {
+            executions::execute_poll_msgs(deps, _env, info, poll_id)
+        }
         ExecuteMsg::ExpirePoll { poll_id } => executions::expire_poll(deps, _env, poll_id),
         ExecuteMsg::RegisterContracts { pollterra_token } => {
             executions::register_contracts(deps, pollterra_token)
         }
         ExecuteMsg::SnapshotPoll { poll_id } => executions::snapshot_poll(deps, _env, poll_id),
-        ExecuteMsg::WithdrawVotingTokens { amount } => withdraw_voting_tokens(deps, info, amount),
+        ExecuteMsg::WithdrawVotingTokens { amount } => {
+            withdraw_voting_tokens(deps, _env, info, amount)
+        }
+        ExecuteMsg::WithdrawAssetTokens { token, amount } => {
+            withdraw_asset_tokens(deps, _env, info, token, amount)
+        }
+        ExecuteMsg::ExtendLock { duration } => extend_lock(deps, _env, info, duration),
+        ExecuteMsg::WithdrawExpired {} => withdraw_expired(deps, _env, info),
+        ExecuteMsg::ClaimUnbonded {} => claim_unbonded(deps, _env, info),
+        ExecuteMsg::ClaimRewards {} => claim_rewards(deps, info),
+        ExecuteMsg::CompoundRewards {} => compound_rewards(deps, _env, info),
+        ExecuteMsg::DepositReward {} => deposit_native_reward(deps, _env),
         ExecuteMsg::UpdateConfig {
             owner,
             quorum,
             threshold,
+            veto_threshold,
             voting_period,
             timelock_period,
             expiration_period,
             proposal_deposit,
             snapshot_period,
+            unbonding_period,
+            text_poll_params,
+            target_poll_params,
+            deposit_period,
+            stake_assets,
+            max_lock_weeks,
+            meta_contract,
+            community_contract,
+            distributor_contract,
         } => executions::update_config(
             deps,
+            _env,
             info,
             owner,
             quorum,
             threshold,
+            veto_threshold,
             voting_period,
             timelock_period,
             expiration_period,
             proposal_deposit,
             snapshot_period,
+            unbonding_period,
+            text_poll_params,
+            target_poll_params,
+            deposit_period,
+            stake_assets,
+            max_lock_weeks,
+            meta_contract,
+            community_contract,
+            distributor_contract,
         ),
+        ExecuteMsg::Pause { operations } => executions::pause(deps, _env, info, true, operations),
+        ExecuteMsg::Unpause { operations } => {
+            executions::pause(deps, _env, info, false, operations)
+        }
+        ExecuteMsg::UpdatePausers {
+            guardian,
+            governance,
+        } => executions::update_pausers(deps, _env, info, guardian, governance),
     }
 }
 
@@ -116,16 +214,22 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
     match msg {
         QueryMsg::Config {} => Ok(to_binary(&queries::query_config(deps)?)?),
         QueryMsg::State {} => Ok(to_binary(&queries::query_state(deps)?)?),
-        QueryMsg::Staker { address } => Ok(to_binary(&query_staker(deps, address)?)?),
+        QueryMsg::Staker { address } => Ok(to_binary(&query_staker(deps, _env, address)?)?),
+        QueryMsg::Unbondings { address } => Ok(to_binary(&query_unbondings(deps, address)?)?),
+        QueryMsg::StakerRewards { address } => {
+            Ok(to_binary(&query_staker_rewards(deps, address)?)?)
+        }
         QueryMsg::Poll { poll_id } => Ok(to_binary(&queries::query_poll(deps, poll_id)?)?),
         QueryMsg::Polls {
             filter,
+            creator,
             start_after,
             limit,
             order_by,
         } => Ok(to_binary(&queries::query_polls(
             deps,
             filter,
+            creator,
             start_after,
             limit,
             order_by,
@@ -142,10 +246,54 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             limit,
             order_by,
         )?)?),
+        QueryMsg::Delegation { address } => {
+            Ok(to_binary(&queries::query_delegation(deps, address)?)?)
+        }
+        QueryMsg::VoterHistory {
+            address,
+            start_after,
+            limit,
+        } => Ok(to_binary(&queries::query_voter_history(
+            deps,
+            address,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::Tally { poll_id } => Ok(to_binary(&queries::query_tally(deps, poll_id)?)?),
+        QueryMsg::LockedPolls { address } => Ok(to_binary(&queries::query_locked_polls(
+            deps, _env, address,
+        )?)?),
+        QueryMsg::VeLock { address, height } => {
+            Ok(to_binary(&query_ve_lock(deps, _env, address, height)?)?)
+        }
+        QueryMsg::TotalVePower { height } => {
+            Ok(to_binary(&query_total_ve_power(deps, _env, height)?)?)
+        }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        id if id > EXECUTE_POLL_REPLY_ID_BASE => replies::after_poll_execute(deps, msg),
+        _ => Err(ContractError::InvalidReplyId {}),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    migrations::set_legacy_version(deps.storage, CONTRACT_NAME)?;
+    let from = migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if from < Version::new(0, 2, 0) {
+        migrations::v0_2(deps.storage, deps.api, &env)?;
+    }
+
+    migrations::apply_config(deps.storage, deps.api, msg)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("from_version", &from.to_string()),
+        ("to_version", CONTRACT_VERSION),
+    ]))
 }
@@ -12,11 +12,23 @@ pub enum ContractError {
     #[error("Must deposit more than {0} token")]
     InsufficientProposalDeposit(u128),
 
+    #[error("Must add a deposit of at least {0} token")]
+    InsufficientAddedDeposit(u128),
+
+    #[error("Poll cannot have more than {0} depositors")]
+    TooManyDepositors(usize),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
-    #[error("User has already voted")]
-    AlreadyVoted {},
+    #[error("Cannot delegate to oneself")]
+    CannotDelegateToSelf {},
+
+    #[error("Delegate cannot have more than {0} delegators")]
+    TooManyDelegators(usize),
+
+    #[error("No delegation")]
+    NoDelegation {},
 
     #[error("Data should be given")]
     DataShouldBeGiven {},
@@ -30,15 +42,54 @@ pub enum ContractError {
     #[error("Nothing staked")]
     NothingStaked {},
 
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
     #[error("User is trying to withdraw too many tokens")]
     InvalidWithdrawAmount {},
 
+    #[error("Stake is too small for the current share price")]
+    StakeTooSmall {},
+
+    #[error("Cannot have more than {0} withdrawals unbonding")]
+    TooManyUnbondings(usize),
+
+    #[error("Stake assets must be distinct tokens other than POL with a positive weight")]
+    InvalidStakeAsset {},
+
+    #[error("Lock duration must be between 1 and {0} weeks")]
+    InvalidLockDuration(u64),
+
+    #[error("Locking is disabled")]
+    LockingDisabled {},
+
+    #[error("Lock already exists")]
+    LockExists {},
+
+    #[error("No lock")]
+    NoLock {},
+
+    #[error("Lock has ended")]
+    LockExpired {},
+
+    #[error("Lock has not ended yet")]
+    LockNotExpired {},
+
     #[error("Cannot make a text proposal to expired state")]
     NoExecuteData {},
 
     #[error("Expire height has not been reached")]
     PollNotExpired {},
 
+    #[error("Poll has expired")]
+    PollExpired {},
+
+    #[error("Polls can only execute bank sends and wasm executions, migrations and admin updates")]
+    UnsupportedPollMsg {},
+
+    #[error("Poll messages cannot send zero coins")]
+    InvalidPollMsgFunds {},
+
     #[error("Poll does not exist")]
     PollNotFound {},
 
@@ -57,6 +108,9 @@ pub enum ContractError {
     #[error("Threshold must be between [0 and {0}]")]
     PollThresholdInvalidValue(String),
 
+    #[error("Veto threshold must be between [0 and {0}]")]
+    PollVetoThresholdInvalidValue(String),
+
     #[error("Poll link too short, must be at least {0} characters")]
     PollLinkInvalidShort(usize),
 
@@ -84,6 +138,39 @@ pub enum ContractError {
     #[error("Timelock period has not expired")]
     TimelockNotExpired {},
 
+    #[error("Timelock period has expired")]
+    TimelockExpired {},
+
+    #[error("Poll already has votes")]
+    PollHasVotes {},
+
+    #[error("Invalid param change target")]
+    InvalidParamChangeTarget {},
+
+    #[error("Community contract is not the one known to governance")]
+    UnknownCommunityContract {},
+
+    #[error("Deposit split must be within the deposit and only send to the community contract")]
+    InvalidDepositSplit {},
+
+    #[error("Poll is not in its deposit period")]
+    PollNotInDepositPeriod {},
+
+    #[error("Deposit period has not expired")]
+    PollDepositPeriod {},
+
     #[error("Invalid poll period. Expiration period is earlier than the timelock period.")]
     InvalidPollPeriod {},
+
+    #[error("Invalid poll period. The periods must all be measured in blocks or all in seconds.")]
+    InvalidPollPeriodUnit {},
+
+    #[error("The unit of the poll periods cannot change while polls are live")]
+    PollPeriodUnitInUse {},
+
+    #[error("Cannot send the uusd reserved for the rewards of the stakers")]
+    NativeRewardsReserved {},
+
+    #[error("Invalid reply id")]
+    InvalidReplyId {},
 }
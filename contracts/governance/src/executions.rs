@@ -1,34 +1,51 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::{
-    attr, from_binary, to_binary, CanonicalAddr, CosmosMsg, Decimal, DepsMut, Env, MessageInfo,
-    Response, Uint128, WasmMsg,
+    attr, from_binary, to_binary, Addr, Api, BankMsg, BlockInfo, CosmosMsg, Decimal, Deps, DepsMut,
+    Env, MessageInfo, Order, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
+use cw0::{Duration, Expiration};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::U64Key;
+use std::cmp::Ordering;
 use terraswap::querier::query_token_balance;
 
+use crate::entrypoints::{
+    EXECUTE_POLL_REPLY_ID_BASE, MAX_DELEGATORS, MAX_DEPOSITORS, MIN_ADDED_DEPOSIT_PERCENT,
+    MIN_INITIAL_DEPOSIT_PERCENT,
+};
 use crate::error::ContractError;
-use crate::staking::stake_voting_tokens;
+use crate::shares;
+use crate::staking::{
+    deposit_reward, increase_lock, lock_tokens, stake_asset_tokens, stake_voting_tokens, DENOM,
+};
 use crate::validators::{
-    validate_poll_description, validate_poll_link, validate_poll_period, validate_poll_title,
-    validate_quorum, validate_threshold,
+    validate_max_lock_weeks, validate_param_change, validate_poll_description, validate_poll_link,
+    validate_poll_msg, validate_poll_params, validate_poll_period, validate_poll_period_unit,
+    validate_poll_title, validate_quorum, validate_stake_assets, validate_threshold,
+    validate_veto_threshold,
 };
+use messages::governance::execute_msgs::ExecuteMsg;
+use messages::governance::param_change::ParamChangeMsg;
 use messages::governance::state::{
-    bank_read, bank_store, config_read, config_store, poll_indexer_store, poll_store,
-    poll_voter_read, poll_voter_store, state_read, state_store, Config, Cw20HookMsg, ExecuteData,
-    Poll, PollExecuteMsg, PollStatus, State, VoteOption, VoterInfo,
+    has_live_polls, polls, staked_asset_at, staked_share_at, total_asset_at, total_share_at,
+    total_ve_power_at, ve_power_at, Config, Cw20HookMsg, ExecuteData, Poll, PollExecuteMsg,
+    PollParams, PollStatus, StakeAsset, StakeAssetMsg, State, TargetPollParams,
+    TargetPollParamsMsg, VoteOption, VoterInfo, CONFIG, DELEGATIONS, DELEGATORS, POLL_VOTERS,
+    STAKERS, STATE, VOTER_HISTORY,
 };
+use messages::pause::{set_paused, set_pausers, PauseInfo, PauseOperation};
 
 pub fn register_contracts(
     deps: DepsMut,
     pollterra_token: String,
 ) -> Result<Response, ContractError> {
-    let mut config: Config = config_read(deps.storage).load()?;
-    if config.pollterra_token != CanonicalAddr::from(vec![]) {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+    if config.pollterra_token != "" {
         return Err(ContractError::Unauthorized {});
     }
 
-    config.pollterra_token = deps.api.addr_canonicalize(&pollterra_token)?;
-    config_store(deps.storage).save(&config)?;
+    config.pollterra_token = deps.api.addr_validate(&pollterra_token)?;
+    CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::default())
 }
@@ -42,21 +59,66 @@ pub fn receive_cw20(
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     // only asset contract can execute this message
-    let config: Config = config_read(deps.storage).load()?;
-    if config.pollterra_token != deps.api.addr_canonicalize(info.sender.as_str())? {
-        return Err(ContractError::Unauthorized {});
+    let config: Config = CONFIG.load(deps.storage)?;
+    if config.pollterra_token != info.sender {
+        // the other stake assets can only be staked
+        return match (
+            config.asset_weight(&info.sender),
+            from_binary(&cw20_msg.msg),
+        ) {
+            (Some(_), Ok(Cw20HookMsg::StakeVotingTokens {})) => {
+                let api = deps.api;
+                stake_asset_tokens(
+                    deps,
+                    env,
+                    info.sender,
+                    api.addr_validate(&cw20_msg.sender)?,
+                    cw20_msg.amount,
+                )
+            }
+            _ => Err(ContractError::Unauthorized {}),
+        };
     }
 
     match from_binary(&cw20_msg.msg) {
         Ok(Cw20HookMsg::StakeVotingTokens {}) => {
             let api = deps.api;
-            stake_voting_tokens(deps, api.addr_validate(&cw20_msg.sender)?, cw20_msg.amount)
+            stake_voting_tokens(
+                deps,
+                env,
+                api.addr_validate(&cw20_msg.sender)?,
+                cw20_msg.amount,
+            )
+        }
+        Ok(Cw20HookMsg::DepositReward {}) => deposit_reward(deps, cw20_msg.amount),
+        Ok(Cw20HookMsg::Lock { duration }) => {
+            let api = deps.api;
+            lock_tokens(
+                deps,
+                env,
+                api.addr_validate(&cw20_msg.sender)?,
+                cw20_msg.amount,
+                duration,
+            )
+        }
+        Ok(Cw20HookMsg::IncreaseLock {}) => {
+            let api = deps.api;
+            increase_lock(
+                deps,
+                env,
+                api.addr_validate(&cw20_msg.sender)?,
+                cw20_msg.amount,
+            )
+        }
+        Ok(Cw20HookMsg::AddDeposit { poll_id }) => {
+            add_deposit(deps, env, cw20_msg.sender, cw20_msg.amount, poll_id)
         }
         Ok(Cw20HookMsg::CreatePoll {
             title,
             description,
             link,
             execute_msgs,
+            param_changes,
         }) => create_poll(
             deps,
             env,
@@ -66,6 +128,7 @@ pub fn receive_cw20(
             description,
             link,
             execute_msgs,
+            param_changes,
         ),
         _ => Err(ContractError::DataShouldBeGiven {}),
     }
@@ -82,31 +145,34 @@ pub fn create_poll(
     description: String,
     link: Option<String>,
     execute_msgs: Option<Vec<PollExecuteMsg>>,
+    param_changes: Option<Vec<ParamChangeMsg>>,
 ) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Creating)?;
+
     validate_poll_title(&title)?;
     validate_poll_description(&description)?;
     validate_poll_link(&link)?;
 
-    let config: Config = config_store(deps.storage).load()?;
-    if deposit_amount < config.proposal_deposit {
-        return Err(ContractError::InsufficientProposalDeposit(
-            config.proposal_deposit.u128(),
-        ));
-    }
-
-    let mut state: State = state_store(deps.storage).load()?;
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
     let poll_id = state.poll_count + 1;
 
     // Increase poll count & total deposit amount
     state.poll_count += 1;
     state.total_deposit += deposit_amount;
 
+    let mut exe_msgs: Vec<PollExecuteMsg> = execute_msgs.unwrap_or_default();
+    for change in param_changes.unwrap_or_default() {
+        validate_param_change(deps.api, &config, &env.contract.address, &change)?;
+        exe_msgs.push(change.to_poll_execute_msg()?);
+    }
+
     let mut data_list: Vec<ExecuteData> = vec![];
-    let all_execute_data = if let Some(exe_msgs) = execute_msgs {
+    let all_execute_data = if !exe_msgs.is_empty() {
         for msgs in exe_msgs {
+            validate_poll_msg(deps.api, &msgs.msg)?;
             let execute_data = ExecuteData {
                 order: msgs.order,
-                contract: deps.api.addr_canonicalize(&msgs.contract)?,
                 msg: msgs.msg,
             };
             data_list.push(execute_data)
@@ -116,14 +182,38 @@ pub fn create_poll(
         None
     };
 
-    let sender_address_raw = deps.api.addr_canonicalize(&proposer)?;
+    // a partial deposit makes a draft poll when there is a deposit period
+    let params = config.poll_params(poll_targets(deps.api, &all_execute_data)?);
+    let (status, end) = if deposit_amount >= params.proposal_deposit {
+        (
+            PollStatus::InProgress,
+            config.voting_period.after(&env.block),
+        )
+    } else if let Some(deposit_period) = config.deposit_period {
+        let min_deposit = params.proposal_deposit * Decimal::percent(MIN_INITIAL_DEPOSIT_PERCENT);
+        if deposit_amount < min_deposit {
+            return Err(ContractError::InsufficientProposalDeposit(
+                min_deposit.u128(),
+            ));
+        }
+        (PollStatus::Deposit, deposit_period.after(&env.block))
+    } else {
+        return Err(ContractError::InsufficientProposalDeposit(
+            params.proposal_deposit.u128(),
+        ));
+    };
+
+    let creator = deps.api.addr_validate(&proposer)?;
     let new_poll = Poll {
         id: poll_id,
-        creator: sender_address_raw,
-        status: PollStatus::InProgress,
+        creator: creator.clone(),
+        status: status.clone(),
         yes_votes: Uint128::zero(),
         no_votes: Uint128::zero(),
-        end_height: env.block.height + config.voting_period,
+        abstain_votes: Uint128::zero(),
+        veto_votes: Uint128::zero(),
+        start_height: env.block.height,
+        end,
         title,
         description,
         link,
@@ -131,118 +221,173 @@ pub fn create_poll(
         deposit_amount,
         total_balance_at_end_poll: None,
         staked_amount: None,
+        error: None,
+        deposits: vec![(creator, deposit_amount)],
     };
 
-    poll_store(deps.storage).save(&poll_id.to_be_bytes(), &new_poll)?;
-    poll_indexer_store(deps.storage, &PollStatus::InProgress)
-        .save(&poll_id.to_be_bytes(), &true)?;
+    polls().save(deps.storage, U64Key::new(poll_id), &new_poll)?;
+
+    STATE.save(deps.storage, &state)?;
 
-    state_store(deps.storage).save(&state)?;
+    let (end_key, end_value) = end_attribute(&new_poll.end);
 
     Ok(Response::new().add_attributes(vec![
         ("action", "create_poll"),
-        (
-            "creator",
-            deps.api
-                .addr_humanize(&new_poll.creator)?
-                .to_string()
-                .as_str(),
-        ),
+        ("creator", new_poll.creator.as_str()),
         ("poll_id", &poll_id.to_string()),
-        ("end_height", new_poll.end_height.to_string().as_str()),
+        (end_key, end_value.as_str()),
     ]))
 }
 
+/// AddDeposit adds a contribution to the deposit of a draft poll,
+/// the voting starts once the deposit required by the poll is reached
+pub fn add_deposit(
+    deps: DepsMut,
+    env: Env,
+    depositor: String,
+    amount: Uint128,
+    poll_id: u64,
+) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Creating)?;
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut a_poll: Poll = polls()
+        .may_load(deps.storage, U64Key::new(poll_id))?
+        .ok_or(ContractError::PollNotFound {})?;
+    if a_poll.status != PollStatus::Deposit || a_poll.end.is_expired(&env.block) {
+        return Err(ContractError::PollNotInDepositPeriod {});
+    }
+
+    // every depositor is refunded one by one, so contributions are kept few and large enough,
+    // the last one only needs to complete the deposit
+    let params = config.poll_params(poll_targets(deps.api, &a_poll.execute_data)?);
+    let min_deposit = std::cmp::min(
+        params.proposal_deposit * Decimal::percent(MIN_ADDED_DEPOSIT_PERCENT),
+        params
+            .proposal_deposit
+            .checked_sub(a_poll.deposit_amount)
+            .unwrap_or_default(),
+    );
+    if amount.is_zero() || amount < min_deposit {
+        return Err(ContractError::InsufficientAddedDeposit(min_deposit.u128()));
+    }
+
+    let depositor = deps.api.addr_validate(&depositor)?;
+    match a_poll
+        .deposits
+        .iter_mut()
+        .find(|(address, _)| address == &depositor)
+    {
+        Some((_, deposit)) => *deposit += amount,
+        None => a_poll.deposits.push((depositor.clone(), amount)),
+    }
+    if a_poll.deposits.len() > MAX_DEPOSITORS {
+        return Err(ContractError::TooManyDepositors(MAX_DEPOSITORS));
+    }
+    a_poll.deposit_amount += amount;
+
+    let mut state: State = STATE.load(deps.storage)?;
+    state.total_deposit += amount;
+    STATE.save(deps.storage, &state)?;
+
+    let mut attributes = vec![
+        attr("action", "add_deposit"),
+        attr("poll_id", poll_id.to_string()),
+        attr("depositor", depositor.as_str()),
+        attr("amount", amount.to_string()),
+    ];
+
+    if a_poll.deposit_amount >= params.proposal_deposit {
+        a_poll.status = PollStatus::InProgress;
+        a_poll.start_height = env.block.height;
+        a_poll.end = config.voting_period.after(&env.block);
+
+        let (end_key, end_value) = end_attribute(&a_poll.end);
+        attributes.push(attr(end_key, end_value));
+    }
+
+    polls().save(deps.storage, U64Key::new(poll_id), &a_poll)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
 /// end a poll
 ///
 /// By default a Poll is considered rejected when ending. The weight of votes and the quorum of the vote is considered before declaring a Poll as passed.
 /// Before the function completes, state is saved any leftover deposit amount is sent back to the poll creator and a response is returned.
 pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
-    let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
+    let mut a_poll: Poll = polls().load(deps.storage, U64Key::new(poll_id))?;
+
+    if a_poll.status == PollStatus::Deposit {
+        return end_deposit_period(deps, env, a_poll);
+    }
 
     if a_poll.status != PollStatus::InProgress {
         return Err(ContractError::PollNotInProgress {});
     }
 
-    if a_poll.end_height > env.block.height {
+    if !a_poll.end.is_expired(&env.block) {
         return Err(ContractError::PollVotingPeriod {});
     }
 
-    let no = a_poll.no_votes.u128();
-    let yes = a_poll.yes_votes.u128();
-
-    let tallied_weight = yes + no;
-
     let mut poll_status = PollStatus::Rejected;
-    #[allow(unused_mut)]
-    let mut rejected_reason: &str;
+    let rejected_reason: &str;
     let mut passed = false;
 
     let mut messages: Vec<CosmosMsg> = vec![];
-    let config: Config = config_read(deps.storage).load()?;
-    let mut state: State = state_read(deps.storage).load()?;
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    let params = config.poll_params(poll_targets(deps.api, &a_poll.execute_data)?);
 
-    let (quorum, staked_amount) = if state.total_share.u128() == 0 {
-        (Decimal::zero(), Uint128::zero())
-    } else if let Some(staked_amount) = a_poll.staked_amount {
-        (
-            Decimal::from_ratio(tallied_weight, staked_amount),
-            staked_amount,
-        )
-    } else {
-        let staked_amount = query_token_balance(
-            &deps.querier,
-            deps.api.addr_humanize(&config.pollterra_token)?,
-            deps.api.addr_humanize(&state.contract_addr)?,
-        )?
-        .checked_sub(state.total_deposit)?;
-
-        (
-            Decimal::from_ratio(tallied_weight, staked_amount),
-            staked_amount,
-        )
+    let staked_amount = match a_poll.staked_amount {
+        Some(staked_amount) => staked_amount,
+        None => current_voting_stake(deps.as_ref(), &config, &a_poll, &state)?,
     };
 
-    if tallied_weight == 0 || quorum < config.quorum {
-        // Quorum: More than quorum of the total staked tokens at the end of the voting
-        // period need to have participated in the vote.
-        rejected_reason = "Quorum not reached";
-    } else {
-        if Decimal::from_ratio(yes, tallied_weight) > config.threshold {
-            //Threshold: More than 50% of the tokens that participated in the vote
-            // (after excluding “Abstain” votes) need to have voted in favor of the proposal (“Yes”).
-            poll_status = PollStatus::Passed;
-            rejected_reason = "Poll Passed";
-            passed = true;
-        } else {
-            rejected_reason = "Threshold not reached";
+    match tally(&a_poll, &config, &params, staked_amount).outcome {
+        TallyOutcome::QuorumNotReached => {
+            // Quorum: More than quorum of the total staked tokens at the end of the voting
+            // period need to have participated in the vote.
+            rejected_reason = "Quorum not reached";
         }
-
-        // Refunds deposit only when quorum is reached
-        if !a_poll.deposit_amount.is_zero() {
-            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: deps.api.addr_humanize(&config.pollterra_token)?.to_string(),
-                funds: vec![],
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: deps.api.addr_humanize(&a_poll.creator)?.to_string(),
-                    amount: a_poll.deposit_amount,
-                })?,
-            }))
+        TallyOutcome::Vetoed => {
+            // Veto: the deposit of a vetoed poll is burnt instead of refunded
+            rejected_reason = "Vetoed";
+
+            if !a_poll.deposit_amount.is_zero() {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: config.pollterra_token.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::Burn {
+                        amount: a_poll.deposit_amount,
+                    })?,
+                }))
+            }
+        }
+        outcome => {
+            if outcome == TallyOutcome::Passed {
+                //Threshold: More than 50% of the tokens that participated in the vote
+                // (after excluding “Abstain” votes) need to have voted in favor of the proposal (“Yes”).
+                poll_status = PollStatus::Passed;
+                rejected_reason = "Poll Passed";
+                passed = true;
+            } else {
+                rejected_reason = "Threshold not reached";
+            }
+
+            // Refunds deposit only when quorum is reached
+            messages.extend(refund_deposit(&config, &a_poll)?);
         }
     }
 
     // Decrease total deposit amount
     state.total_deposit = state.total_deposit.checked_sub(a_poll.deposit_amount)?;
-    state_store(deps.storage).save(&state)?;
-
-    // Update poll indexer
-    poll_indexer_store(deps.storage, &PollStatus::InProgress).remove(&a_poll.id.to_be_bytes());
-    poll_indexer_store(deps.storage, &poll_status).save(&a_poll.id.to_be_bytes(), &true)?;
+    STATE.save(deps.storage, &state)?;
 
     // Update poll status
     a_poll.status = poll_status;
     a_poll.total_balance_at_end_poll = Some(staked_amount);
-    poll_store(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
+    polls().save(deps.storage, U64Key::new(poll_id), &a_poll)?;
 
     Ok(Response::new().add_messages(messages).add_attributes(vec![
         ("action", "end_poll"),
@@ -263,54 +408,120 @@ pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, Contr
 /// which are associated with a Passed poll. This ensures the actions taken by a successful Poll are
 /// well known and predefined.
 pub fn execute_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
-    let config: Config = config_read(deps.storage).load()?;
-    let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut a_poll: Poll = polls().load(deps.storage, U64Key::new(poll_id))?;
 
-    if a_poll.status != PollStatus::Passed {
+    // a failed poll can be re-attempted until it expires
+    let status = a_poll.status.clone();
+    if status != PollStatus::Passed && status != PollStatus::Failed {
         return Err(ContractError::PollNotPassed {});
     }
 
-    if a_poll.end_height + config.timelock_period > env.block.height {
+    if !(a_poll.end + config.timelock_period)?.is_expired(&env.block) {
         return Err(ContractError::TimelockNotExpired {});
     }
 
-    poll_indexer_store(deps.storage, &PollStatus::Passed).remove(&poll_id.to_be_bytes());
-    poll_indexer_store(deps.storage, &PollStatus::Executed).save(&poll_id.to_be_bytes(), &true)?;
+    // an expired poll is left to ExpirePoll
+    if (a_poll.end + config.expiration_period)?.is_expired(&env.block) {
+        return Err(ContractError::PollExpired {});
+    }
+
+    if a_poll.execute_data.is_none() {
+        return Err(ContractError::NoExecuteData {});
+    }
 
     a_poll.status = PollStatus::Executed;
-    poll_store(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
+    a_poll.error = None;
+    polls().save(deps.storage, U64Key::new(poll_id), &a_poll)?;
+
+    // the messages are run by the contract itself in a submessage,
+    // so that a failure is recorded in the reply instead of reverting the execution
+    let execute_msgs = SubMsg::reply_on_error(
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_binary(&ExecuteMsg::ExecutePollMsgs { poll_id })?,
+            funds: vec![],
+        }),
+        EXECUTE_POLL_REPLY_ID_BASE + poll_id,
+    );
+
+    Ok(Response::new()
+        .add_submessage(execute_msgs)
+        .add_attributes(vec![
+            ("action", "execute_poll"),
+            ("poll_id", poll_id.to_string().as_str()),
+        ]))
+}
+
+/// ExecutePollMsgs sends the messages of an executed poll in order,
+/// it can only be called by the contract itself from execute_poll
+pub fn execute_poll_msgs(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let a_poll: Poll = polls().load(deps.storage, U64Key::new(poll_id))?;
+    if a_poll.status != PollStatus::Executed {
+        return Err(ContractError::PollNotPassed {});
+    }
 
     let mut messages: Vec<CosmosMsg> = vec![];
     if let Some(all_msgs) = a_poll.execute_data {
         let mut msgs = all_msgs;
         msgs.sort();
         for msg in msgs {
-            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: deps.api.addr_humanize(&msg.contract)?.to_string(),
-                msg: msg.msg,
-                funds: vec![],
-            }))
+            messages.push(msg.msg)
         }
     } else {
         return Err(ContractError::NoExecuteData {});
     }
 
+    // the uusd of unclaimed rewards belongs to the stakers, polls can only send the rest
+    let mut native_sent = Uint128::zero();
+    for msg in messages.iter() {
+        let funds = match msg {
+            CosmosMsg::Bank(BankMsg::Send { amount, .. }) => amount,
+            CosmosMsg::Wasm(WasmMsg::Execute { funds, .. })
+            | CosmosMsg::Wasm(WasmMsg::Instantiate { funds, .. }) => funds,
+            _ => continue,
+        };
+        for coin in funds.iter().filter(|coin| coin.denom == DENOM) {
+            native_sent = native_sent.checked_add(coin.amount)?;
+        }
+    }
+    if !native_sent.is_zero() {
+        let balance = deps
+            .querier
+            .query_balance(env.contract.address, DENOM)?
+            .amount;
+        let native_reward_balance = STATE.load(deps.storage)?.native_reward_balance;
+        if native_sent + native_reward_balance > balance {
+            return Err(ContractError::NativeRewardsReserved {});
+        }
+    }
+
     Ok(Response::new().add_messages(messages).add_attributes(vec![
-        ("action", "execute_poll"),
+        ("action", "execute_poll_msgs"),
         ("poll_id", poll_id.to_string().as_str()),
     ]))
 }
 
 // Voting
 /// cast_vote exposes the end user side of a poll. Once a poll and its proposal is created,
-/// any account which has some staked governance tokens can cast 1 vote for a given proposal.
+/// any account which has some staked governance tokens can cast a vote for a given proposal.
 ///
 /// Before a Vote is registered from a user a number of checks are performed; firstly that
-/// the Poll exists and that it is currently in Progress. Accounts may only vote once
-/// and the Account must have some staked governance tokens.
-/// With all these conditions met, the account's casted vote is evaluated and both the vote and
-/// a collection of info related to the Voter is stored in state. This registers both the actors
-/// desired vote and also their information to prevent a second vote.
+/// the Poll exists and that it is currently in Progress, and that the Account has enough
+/// staked governance tokens. With all these conditions met, the account's casted vote is
+/// evaluated and both the vote and a collection of info related to the Voter is stored in state.
+/// A second vote replaces the first one while the poll is in progress.
+/// The vote is also cast with the whole stake of the delegators of the account,
+/// except those who have voted themselves.
 pub fn cast_vote(
     deps: DepsMut,
     env: Env,
@@ -319,89 +530,377 @@ pub fn cast_vote(
     vote: VoteOption,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
-    let sender_address_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
-    let config = config_read(deps.storage).load()?;
-    let state = state_read(deps.storage).load()?;
+    PauseInfo::assert_active(deps.storage, PauseOperation::Voting)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
     if poll_id == 0 || state.poll_count < poll_id {
         return Err(ContractError::PollNotFound {});
     }
 
-    let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
-    if a_poll.status != PollStatus::InProgress || env.block.height > a_poll.end_height {
+    let mut a_poll: Poll = polls().load(deps.storage, U64Key::new(poll_id))?;
+    if a_poll.status != PollStatus::InProgress || a_poll.end.is_expired(&env.block) {
         return Err(ContractError::PollNotInProgress {});
     }
 
-    // Check the voter already has a vote on the poll
-    if poll_voter_read(deps.storage, poll_id)
-        .load(sender_address_raw.as_slice())
-        .is_ok()
-    {
-        return Err(ContractError::AlreadyVoted {});
-    }
-
-    let key = &sender_address_raw.as_slice();
-    let mut token_manager = bank_read(deps.storage).may_load(key)?.unwrap_or_default();
-
     // convert share to amount
     let total_share = state.total_share;
     let total_balance = query_token_balance(
         &deps.querier,
-        deps.api.addr_humanize(&config.pollterra_token)?,
-        deps.api.addr_humanize(&state.contract_addr)?,
+        config.pollterra_token.clone(),
+        state.contract_addr.clone(),
     )?
-    .checked_sub(state.total_deposit)?;
+    .checked_sub(state.unstaked_balance())?;
+
+    // the voting power is the stake when the poll started, so moved stake cannot vote twice
+    let start_height = a_poll.start_height;
+    let staked_amount = |storage: &dyn Storage, voter: &Addr| -> StdResult<Uint128> {
+        voting_power(
+            storage,
+            &config,
+            voter,
+            start_height,
+            total_share,
+            total_balance,
+        )
+    };
 
-    if token_manager
-        .share
-        .multiply_ratio(total_balance, total_share)
-        < amount
-    {
+    if staked_amount(deps.storage, &info.sender)? < amount {
         return Err(ContractError::InsufficientStaked {});
     }
 
-    // update tally info
-    if VoteOption::Yes == vote {
-        a_poll.yes_votes += amount;
-    } else {
-        a_poll.no_votes += amount;
-    }
-
     let vote_info = VoterInfo {
         vote,
         balance: amount,
+        delegated: false,
     };
-    token_manager
-        .locked_balance
-        .push((poll_id, vote_info.clone()));
-    bank_store(deps.storage).save(key, &token_manager)?;
+    record_vote(deps.storage, &mut a_poll, &info.sender, &vote_info)?;
+
+    let delegators = DELEGATORS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let mut num_delegated: u64 = 0;
+    for delegator in delegators.iter() {
+        // delegators who have voted themselves override their delegate
+        let voted = POLL_VOTERS.may_load(deps.storage, (U64Key::new(poll_id), delegator))?;
+        if matches!(
+            voted,
+            Some(VoterInfo {
+                delegated: false,
+                ..
+            })
+        ) {
+            continue;
+        }
 
-    // store poll voter && and update poll data
-    poll_voter_store(deps.storage, poll_id).save(sender_address_raw.as_slice(), &vote_info)?;
+        let balance = staked_amount(deps.storage, delegator)?;
+        if balance.is_zero() {
+            continue;
+        }
 
-    // processing snapshot
-    let time_to_end = a_poll.end_height - env.block.height;
+        let delegated_info = VoterInfo {
+            vote: vote_info.vote.clone(),
+            balance,
+            delegated: true,
+        };
+        record_vote(deps.storage, &mut a_poll, delegator, &delegated_info)?;
+        num_delegated += 1;
+    }
 
-    if time_to_end < config.snapshot_period && a_poll.staked_amount.is_none() {
-        a_poll.staked_amount = Some(total_balance);
+    // processing snapshot
+    if a_poll.staked_amount.is_none()
+        && cmp_end_to(&a_poll, config.snapshot_period, &env.block)? == Ordering::Less
+    {
+        a_poll.staked_amount = Some(voting_stake(
+            deps.storage,
+            &config,
+            &a_poll,
+            &state,
+            total_balance,
+        )?);
     }
 
-    poll_store(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
+    polls().save(deps.storage, U64Key::new(poll_id), &a_poll)?;
 
-    Ok(Response::new().add_attributes(vec![
+    let mut response = Response::new().add_attributes(vec![
         ("action", "cast_vote"),
         ("poll_id", poll_id.to_string().as_str()),
         ("amount", amount.to_string().as_str()),
         ("voter", info.sender.as_str()),
         ("vote_option", vote_info.vote.to_string().as_str()),
+    ]);
+    if num_delegated > 0 {
+        response = response.add_attribute("num_delegators", num_delegated.to_string());
+    }
+
+    Ok(response)
+}
+
+// stores the vote and locks its balance, a previous vote of the voter is taken back from the tally
+fn record_vote(
+    storage: &mut dyn Storage,
+    a_poll: &mut Poll,
+    voter: &Addr,
+    vote_info: &VoterInfo,
+) -> StdResult<()> {
+    if let Some(previous) = POLL_VOTERS.may_load(storage, (U64Key::new(a_poll.id), voter))? {
+        let tally = tally_of(a_poll, &previous.vote);
+        *tally = tally.checked_sub(previous.balance)?;
+    }
+    let tally = tally_of(a_poll, &vote_info.vote);
+    *tally += vote_info.balance;
+
+    let mut token_manager = STAKERS.may_load(storage, voter)?.unwrap_or_default();
+    token_manager
+        .locked_balance
+        .retain(|(poll_id, _)| *poll_id != a_poll.id);
+    token_manager
+        .locked_balance
+        .push((a_poll.id, vote_info.clone()));
+    STAKERS.save(storage, voter, &token_manager)?;
+
+    VOTER_HISTORY.save(storage, (voter, U64Key::new(a_poll.id)), vote_info)?;
+    POLL_VOTERS.save(storage, (U64Key::new(a_poll.id), voter), vote_info)
+}
+
+fn tally_of<'a>(a_poll: &'a mut Poll, vote: &VoteOption) -> &'a mut Uint128 {
+    match vote {
+        VoteOption::Yes => &mut a_poll.yes_votes,
+        VoteOption::No => &mut a_poll.no_votes,
+        VoteOption::Abstain => &mut a_poll.abstain_votes,
+        VoteOption::NoWithVeto => &mut a_poll.veto_votes,
+    }
+}
+
+/// delegate lets the delegate vote with the whole stake of the sender, replacing its previous delegate
+pub fn delegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delegate: String,
+) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Voting)?;
+
+    let delegate = deps.api.addr_validate(&delegate)?;
+    if info.sender == delegate {
+        return Err(ContractError::CannotDelegateToSelf {});
+    }
+
+    // the delegators of a delegate are limited, so only stakers may take a place
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+    let total_balance = query_token_balance(
+        &deps.querier,
+        config.pollterra_token.clone(),
+        state.contract_addr.clone(),
+    )?
+    .checked_sub(state.unstaked_balance())?;
+    if voting_power(
+        deps.storage,
+        &config,
+        &info.sender,
+        env.block.height + 1,
+        state.total_share,
+        total_balance,
+    )?
+    .is_zero()
+    {
+        return Err(ContractError::NothingStaked {});
+    }
+
+    remove_delegation(deps.storage, &info.sender)?;
+
+    let mut delegators = DELEGATORS
+        .may_load(deps.storage, &delegate)?
+        .unwrap_or_default();
+    if delegators.len() >= MAX_DELEGATORS {
+        return Err(ContractError::TooManyDelegators(MAX_DELEGATORS));
+    }
+    delegators.push(info.sender.clone());
+    DELEGATORS.save(deps.storage, &delegate, &delegators)?;
+    DELEGATIONS.save(deps.storage, &info.sender, &delegate)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "delegate"),
+        ("delegator", info.sender.as_str()),
+        ("delegate", delegate.as_str()),
+    ]))
+}
+
+/// undelegate stops the delegate from voting for the sender, votes already cast are kept
+pub fn undelegate(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    if !remove_delegation(deps.storage, &info.sender)? {
+        return Err(ContractError::NoDelegation {});
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "undelegate"),
+        ("delegator", info.sender.as_str()),
+    ]))
+}
+
+// returns whether the delegator had a delegate
+fn remove_delegation(storage: &mut dyn Storage, delegator: &Addr) -> StdResult<bool> {
+    let delegate = match DELEGATIONS.may_load(storage, delegator)? {
+        Some(delegate) => delegate,
+        None => return Ok(false),
+    };
+
+    let mut delegators = DELEGATORS.may_load(storage, &delegate)?.unwrap_or_default();
+    delegators.retain(|v| v != delegator);
+    if delegators.is_empty() {
+        DELEGATORS.remove(storage, &delegate);
+    } else {
+        DELEGATORS.save(storage, &delegate, &delegators)?;
+    }
+    DELEGATIONS.remove(storage, delegator);
+
+    Ok(true)
+}
+
+/// Rejects a draft poll whose deposit has not been reached in the deposit period,
+/// the contributions are refunded
+fn end_deposit_period(
+    deps: DepsMut,
+    env: Env,
+    mut a_poll: Poll,
+) -> Result<Response, ContractError> {
+    if !a_poll.end.is_expired(&env.block) {
+        return Err(ContractError::PollDepositPeriod {});
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let messages = refund_deposit(&config, &a_poll)?;
+
+    let mut state: State = STATE.load(deps.storage)?;
+    state.total_deposit = state.total_deposit.checked_sub(a_poll.deposit_amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    a_poll.status = PollStatus::Rejected;
+    polls().save(deps.storage, U64Key::new(a_poll.id), &a_poll)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "end_poll"),
+        ("poll_id", &a_poll.id.to_string()),
+        ("rejected_reason", "Deposit not reached"),
+        ("passed", "false"),
+    ]))
+}
+
+/// Refunds the deposit of a poll to its contributors, pro rata to their contributions
+fn refund_deposit(config: &Config, a_poll: &Poll) -> StdResult<Vec<CosmosMsg>> {
+    // polls created before the contributions were recorded were deposited by their creator
+    let deposits = if a_poll.deposits.is_empty() {
+        vec![(a_poll.creator.clone(), a_poll.deposit_amount)]
+    } else {
+        a_poll.deposits.clone()
+    };
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for (depositor, amount) in deposits {
+        if amount.is_zero() {
+            continue;
+        }
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: config.pollterra_token.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: depositor.to_string(),
+                amount,
+            })?,
+        }))
+    }
+
+    Ok(messages)
+}
+
+/// CancelPoll lets the creator withdraw a poll which has not been voted on yet,
+/// the deposit is refunded
+pub fn cancel_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut a_poll: Poll = polls().load(deps.storage, U64Key::new(poll_id))?;
+
+    if a_poll.creator != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // draft polls can be cancelled too, but only until their end
+    let status = a_poll.status.clone();
+    if (status != PollStatus::InProgress && status != PollStatus::Deposit)
+        || a_poll.end.is_expired(&env.block)
+    {
+        return Err(ContractError::PollNotInProgress {});
+    }
+
+    if POLL_VOTERS
+        .prefix(U64Key::new(poll_id))
+        .range(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some()
+    {
+        return Err(ContractError::PollHasVotes {});
+    }
+
+    let messages = refund_deposit(&config, &a_poll)?;
+
+    let mut state: State = STATE.load(deps.storage)?;
+    state.total_deposit = state.total_deposit.checked_sub(a_poll.deposit_amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    a_poll.status = PollStatus::Cancelled;
+    polls().save(deps.storage, U64Key::new(poll_id), &a_poll)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "cancel_poll"),
+        ("poll_id", poll_id.to_string().as_str()),
+    ]))
+}
+
+/// VetoPoll lets the pause guardian stop a passed poll from being executed
+/// while its timelock period is not over
+pub fn veto_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+) -> Result<Response, ContractError> {
+    if PauseInfo::load(deps.storage)?.guardian.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    let mut a_poll: Poll = polls().load(deps.storage, U64Key::new(poll_id))?;
+    if a_poll.status != PollStatus::Passed {
+        return Err(ContractError::PollNotPassed {});
+    }
+
+    if (a_poll.end + config.timelock_period)?.is_expired(&env.block) {
+        return Err(ContractError::TimelockExpired {});
+    }
+
+    a_poll.status = PollStatus::Vetoed;
+    polls().save(deps.storage, U64Key::new(poll_id), &a_poll)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "veto_poll"),
+        ("poll_id", poll_id.to_string().as_str()),
     ]))
 }
 
 /// ExpirePoll is used to make the poll as expired state for querying purpose
 pub fn expire_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
-    let config: Config = config_read(deps.storage).load()?;
-    let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut a_poll: Poll = polls().load(deps.storage, U64Key::new(poll_id))?;
 
-    if a_poll.status != PollStatus::Passed {
+    // failed polls expire as the passed ones do
+    let status = a_poll.status.clone();
+    if status != PollStatus::Passed && status != PollStatus::Failed {
         return Err(ContractError::PollNotPassed {});
     }
 
@@ -409,15 +908,12 @@ pub fn expire_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, Co
         return Err(ContractError::NoExecuteData {});
     }
 
-    if a_poll.end_height + config.expiration_period > env.block.height {
+    if !(a_poll.end + config.expiration_period)?.is_expired(&env.block) {
         return Err(ContractError::PollNotExpired {});
     }
 
-    poll_indexer_store(deps.storage, &PollStatus::Passed).remove(&poll_id.to_be_bytes());
-    poll_indexer_store(deps.storage, &PollStatus::Expired).save(&poll_id.to_be_bytes(), &true)?;
-
     a_poll.status = PollStatus::Expired;
-    poll_store(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
+    polls().save(deps.storage, U64Key::new(poll_id), &a_poll)?;
 
     Ok(Response::new().add_attributes(vec![
         ("action", "expire_poll"),
@@ -427,16 +923,14 @@ pub fn expire_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, Co
 
 /// SnapshotPoll is used to take a snapshot of the staked amount for quorum calculation
 pub fn snapshot_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
-    let config: Config = config_read(deps.storage).load()?;
-    let mut a_poll: Poll = poll_store(deps.storage).load(&poll_id.to_be_bytes())?;
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut a_poll: Poll = polls().load(deps.storage, U64Key::new(poll_id))?;
 
     if a_poll.status != PollStatus::InProgress {
         return Err(ContractError::PollNotInProgress {});
     }
 
-    let time_to_end = a_poll.end_height - env.block.height;
-
-    if time_to_end > config.snapshot_period {
+    if cmp_end_to(&a_poll, config.snapshot_period, &env.block)? == Ordering::Greater {
         return Err(ContractError::SnapshotHeight {});
     }
 
@@ -445,18 +939,19 @@ pub fn snapshot_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response,
     }
 
     // store the current staked amount for quorum calculation
-    let state: State = state_store(deps.storage).load()?;
+    let state: State = STATE.load(deps.storage)?;
 
-    let staked_amount = query_token_balance(
+    let total_balance = query_token_balance(
         &deps.querier,
-        deps.api.addr_humanize(&config.pollterra_token)?,
-        deps.api.addr_humanize(&state.contract_addr)?,
+        config.pollterra_token.clone(),
+        state.contract_addr.clone(),
     )?
-    .checked_sub(state.total_deposit)?;
+    .checked_sub(state.unstaked_balance())?;
+    let staked_amount = voting_stake(deps.storage, &config, &a_poll, &state, total_balance)?;
 
     a_poll.staked_amount = Some(staked_amount);
 
-    poll_store(deps.storage).save(&poll_id.to_be_bytes(), &a_poll)?;
+    polls().save(deps.storage, U64Key::new(poll_id), &a_poll)?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "snapshot_poll"),
@@ -465,27 +960,199 @@ pub fn snapshot_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response,
     ]))
 }
 
+// compares the end of the poll with the given period from the current block
+fn end_attribute(end: &Expiration) -> (&'static str, String) {
+    match end {
+        Expiration::AtHeight(height) => ("end_height", height.to_string()),
+        Expiration::AtTime(time) => ("end_time", time.seconds().to_string()),
+        Expiration::Never {} => ("end", "never".to_string()),
+    }
+}
+
+/// Contracts the messages of a poll are sent to, none for a text poll
+pub(crate) fn poll_targets(
+    api: &dyn Api,
+    execute_data: &Option<Vec<ExecuteData>>,
+) -> StdResult<Option<Vec<Addr>>> {
+    let execute_data = match execute_data {
+        Some(execute_data) => execute_data,
+        None => return Ok(None),
+    };
+
+    let mut targets: Vec<Addr> = vec![];
+    for data in execute_data {
+        let contract_addr = match &data.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. })
+            | CosmosMsg::Wasm(WasmMsg::Migrate { contract_addr, .. })
+            | CosmosMsg::Wasm(WasmMsg::UpdateAdmin { contract_addr, .. })
+            | CosmosMsg::Wasm(WasmMsg::ClearAdmin { contract_addr }) => contract_addr,
+            _ => continue,
+        };
+        targets.push(api.addr_validate(contract_addr)?);
+    }
+
+    Ok(Some(targets))
+}
+
+fn cmp_end_to(a_poll: &Poll, period: Duration, block: &BlockInfo) -> StdResult<Ordering> {
+    a_poll
+        .end
+        .partial_cmp(&period.after(block))
+        .ok_or_else(|| StdError::generic_err("Cannot compare height and time"))
+}
+
+// the amount of the stake which could vote on the poll, valued at the current share price,
+// the other stake assets by their current weight and the locks by their power at the start
+pub(crate) fn voting_stake(
+    storage: &dyn Storage,
+    config: &Config,
+    a_poll: &Poll,
+    state: &State,
+    total_balance: Uint128,
+) -> StdResult<Uint128> {
+    let mut staked_amount = shares::share_amount(
+        total_share_at(storage, a_poll.start_height)?,
+        state.total_share,
+        total_balance,
+    )?;
+    for asset in config.stake_assets.iter() {
+        let total = total_asset_at(storage, &asset.token, a_poll.start_height)?;
+        staked_amount = staked_amount.checked_add(total * asset.weight)?;
+    }
+    staked_amount = staked_amount.checked_add(total_ve_power_at(storage, a_poll.start_height)?)?;
+    Ok(staked_amount)
+}
+
+// the voting stake of a poll which has no snapshot, at the current share price
+pub(crate) fn current_voting_stake(
+    deps: Deps,
+    config: &Config,
+    a_poll: &Poll,
+    state: &State,
+) -> StdResult<Uint128> {
+    // only the other stake assets can vote when no POL is staked
+    let total_balance = if state.total_share.is_zero() {
+        Uint128::zero()
+    } else {
+        query_token_balance(
+            &deps.querier,
+            config.pollterra_token.clone(),
+            state.contract_addr.clone(),
+        )?
+        .checked_sub(state.unstaked_balance())?
+    };
+    voting_stake(deps.storage, config, a_poll, state, total_balance)
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum TallyOutcome {
+    Passed,
+    QuorumNotReached,
+    Vetoed,
+    ThresholdNotReached,
+}
+
+// the vote ratios of a poll against its voting stake and the outcome of ending it with them
+pub(crate) struct Tally {
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub veto: Decimal,
+    pub outcome: TallyOutcome,
+}
+
+pub(crate) fn tally(
+    a_poll: &Poll,
+    config: &Config,
+    params: &PollParams,
+    staked_amount: Uint128,
+) -> Tally {
+    // abstain votes count toward the quorum, but not toward the threshold
+    let tallied_weight =
+        a_poll.yes_votes + a_poll.no_votes + a_poll.abstain_votes + a_poll.veto_votes;
+    let voted_weight = a_poll.yes_votes + a_poll.no_votes + a_poll.veto_votes;
+    let ratio = |votes: Uint128, total: Uint128| match total.is_zero() {
+        true => Decimal::zero(),
+        false => Decimal::from_ratio(votes, total),
+    };
+    let quorum = ratio(tallied_weight, staked_amount);
+    let threshold = ratio(a_poll.yes_votes, voted_weight);
+    let veto = ratio(a_poll.veto_votes, tallied_weight);
+
+    let outcome = if tallied_weight.is_zero() || quorum < params.quorum {
+        TallyOutcome::QuorumNotReached
+    } else if veto > config.veto_threshold {
+        TallyOutcome::Vetoed
+    } else if threshold > params.threshold {
+        TallyOutcome::Passed
+    } else {
+        TallyOutcome::ThresholdNotReached
+    };
+
+    Tally {
+        quorum,
+        threshold,
+        veto,
+        outcome,
+    }
+}
+
+// the voting power of the stake of the voter at the height, valued like the voting stake
+pub(crate) fn voting_power(
+    storage: &dyn Storage,
+    config: &Config,
+    voter: &Addr,
+    height: u64,
+    total_share: Uint128,
+    total_balance: Uint128,
+) -> StdResult<Uint128> {
+    let mut power = shares::share_amount(
+        staked_share_at(storage, voter, height)?,
+        total_share,
+        total_balance,
+    )?;
+    for asset in config.stake_assets.iter() {
+        let amount = staked_asset_at(storage, voter, &asset.token, height)?;
+        power = power.checked_add(amount * asset.weight)?;
+    }
+    power = power.checked_add(ve_power_at(storage, voter, height)?)?;
+    Ok(power)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     owner: Option<String>,
     quorum: Option<Decimal>,
     threshold: Option<Decimal>,
-    voting_period: Option<u64>,
-    timelock_period: Option<u64>,
-    expiration_period: Option<u64>,
+    veto_threshold: Option<Decimal>,
+    voting_period: Option<Duration>,
+    timelock_period: Option<Duration>,
+    expiration_period: Option<Duration>,
     proposal_deposit: Option<Uint128>,
-    snapshot_period: Option<u64>,
+    snapshot_period: Option<Duration>,
+    unbonding_period: Option<u64>,
+    text_poll_params: Option<PollParams>,
+    target_poll_params: Option<Vec<TargetPollParamsMsg>>,
+    deposit_period: Option<Duration>,
+    stake_assets: Option<Vec<StakeAssetMsg>>,
+    max_lock_weeks: Option<u64>,
+    meta_contract: Option<String>,
+    community_contract: Option<String>,
+    distributor_contract: Option<String>,
 ) -> Result<Response, ContractError> {
     let api = deps.api;
-    config_store(deps.storage).update(|mut config| {
-        if config.owner != api.addr_canonicalize(info.sender.as_str())? {
+    let live_polls = has_live_polls(deps.storage)?;
+    CONFIG.update(deps.storage, |mut config| {
+        // governance updates its own config through the param changes of polls
+        if info.sender != env.contract.address && config.owner != info.sender {
             return Err(ContractError::Unauthorized {});
         }
+        let previous = config.clone();
 
         if let Some(owner) = owner {
-            config.owner = api.addr_canonicalize(&owner)?;
+            config.owner = api.addr_validate(&owner)?;
         }
 
         if let Some(quorum) = quorum {
@@ -498,6 +1165,11 @@ pub fn update_config(
             config.threshold = threshold;
         }
 
+        if let Some(veto_threshold) = veto_threshold {
+            validate_veto_threshold(veto_threshold)?;
+            config.veto_threshold = veto_threshold;
+        }
+
         if let Some(voting_period) = voting_period {
             config.voting_period = voting_period;
         }
@@ -510,8 +1182,6 @@ pub fn update_config(
             config.expiration_period = expiration_period;
         }
 
-        validate_poll_period(config.timelock_period, config.expiration_period)?;
-
         if let Some(proposal_deposit) = proposal_deposit {
             config.proposal_deposit = proposal_deposit;
         }
@@ -520,8 +1190,102 @@ pub fn update_config(
             config.snapshot_period = period;
         }
 
+        if let Some(period) = unbonding_period {
+            config.unbonding_period = period;
+        }
+
+        if let Some(params) = text_poll_params {
+            validate_poll_params(&params)?;
+            config.text_poll_params = Some(params);
+        }
+
+        if let Some(target_params) = target_poll_params {
+            let mut all_params: Vec<TargetPollParams> = vec![];
+            for target in target_params {
+                validate_poll_params(&target.params)?;
+                all_params.push(TargetPollParams {
+                    contract: api.addr_validate(&target.contract)?,
+                    params: target.params,
+                });
+            }
+            config.target_poll_params = all_params;
+        }
+
+        if let Some(period) = deposit_period {
+            config.deposit_period = Some(period);
+        }
+
+        if let Some(stake_assets) = stake_assets {
+            config.stake_assets = stake_assets
+                .into_iter()
+                .map(|asset| {
+                    Ok(StakeAsset {
+                        token: api.addr_validate(&asset.token)?,
+                        weight: asset.weight,
+                    })
+                })
+                .collect::<StdResult<Vec<StakeAsset>>>()?;
+            validate_stake_assets(&config)?;
+        }
+
+        if let Some(max_lock_weeks) = max_lock_weeks {
+            validate_max_lock_weeks(max_lock_weeks)?;
+            config.max_lock_weeks = max_lock_weeks;
+        }
+
+        if let Some(meta_contract) = meta_contract {
+            config.meta_contract = Some(api.addr_validate(&meta_contract)?);
+        }
+
+        if let Some(community_contract) = community_contract {
+            config.community_contract = Some(api.addr_validate(&community_contract)?);
+        }
+
+        if let Some(distributor_contract) = distributor_contract {
+            config.distributor_contract = Some(api.addr_validate(&distributor_contract)?);
+        }
+
+        validate_poll_period(&config)?;
+        validate_poll_period_unit(&previous, &config, live_polls)?;
+
         Ok(config)
     })?;
 
     Ok(Response::new().add_attributes(vec![("action", "update_config")]))
 }
+
+/// pause allows the guardian or governance itself to halt the contract or some of its operations
+pub fn pause(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    paused: bool,
+    operations: Option<Vec<PauseOperation>>,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address
+        && !PauseInfo::load(deps.storage)?.is_pauser(&info.sender)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(set_paused(deps.storage, paused, operations)?)
+}
+
+/// update_pausers replaces the guardian and governance, only by the owner or governance itself
+pub fn update_pausers(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    guardian: Option<String>,
+    governance: Option<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != env.contract.address && config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let guardian = guardian.map(|v| deps.api.addr_validate(&v)).transpose()?;
+    let governance = governance.map(|v| deps.api.addr_validate(&v)).transpose()?;
+
+    Ok(set_pausers(deps.storage, guardian, governance)?)
+}
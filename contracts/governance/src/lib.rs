@@ -2,7 +2,10 @@ pub mod entrypoints;
 
 mod error;
 mod executions;
+mod migrations;
 mod queries;
+mod replies;
+mod shares;
 mod staking;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -0,0 +1,298 @@
+use cosmwasm_std::{
+    Api, Binary, CanonicalAddr, CosmosMsg, Decimal, Env, Order, StdResult, Storage, Uint128,
+    WasmMsg,
+};
+use cosmwasm_storage::{bucket, singleton, Bucket};
+use cw0::{Duration, Expiration};
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::U64Key;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+use crate::validators::{
+    validate_poll_period, validate_poll_period_unit, validate_quorum, validate_threshold,
+    validate_veto_threshold,
+};
+use messages::governance::execute_msgs::MigrateMsg;
+use messages::governance::state::{
+    has_live_polls, polls, save_staked_share, Config, ExecuteData, Poll, PollStatus, State,
+    TokenManager, VoterInfo, CONFIG, POLL_VOTERS, STAKERS, STATE, VOTER_HISTORY,
+};
+use messages::pause::{set_pausers, PAUSE_INFO};
+
+// the storage layout before 0.2.0, keyed by canonical addresses
+static KEY_CONFIG: &[u8] = b"config";
+static KEY_STATE: &[u8] = b"state";
+static PREFIX_POLL_INDEXER: &[u8] = b"poll_indexer";
+static PREFIX_POLL_VOTER: &[u8] = b"poll_voter";
+static PREFIX_POLL: &[u8] = b"poll";
+static PREFIX_BANK: &[u8] = b"bank";
+
+/// Governance was deployed without a contract version before 0.2.0
+pub fn set_legacy_version(storage: &mut dyn Storage, contract_name: &str) -> StdResult<()> {
+    if get_contract_version(storage).is_err() {
+        set_contract_version(storage, contract_name, "0.1.0")?;
+    }
+    Ok(())
+}
+
+// Config before 0.2.0, without the veto threshold
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct ConfigV0_1 {
+    owner: CanonicalAddr,
+    pollterra_token: CanonicalAddr,
+    quorum: Decimal,
+    threshold: Decimal,
+    voting_period: u64,
+    timelock_period: u64,
+    expiration_period: u64,
+    proposal_deposit: Uint128,
+    snapshot_period: u64,
+}
+
+// State before 0.2.0, without rewards and unbondings
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct StateV0_1 {
+    contract_addr: CanonicalAddr,
+    poll_count: u64,
+    total_share: Uint128,
+    total_deposit: Uint128,
+}
+
+// ExecuteData before 0.2.0, only executing contracts without funds
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct ExecuteDataV0_1 {
+    order: u64,
+    contract: CanonicalAddr,
+    msg: Binary,
+}
+
+// Poll before 0.2.0, ending at a block height
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct PollV0_1 {
+    id: u64,
+    creator: CanonicalAddr,
+    status: PollStatus,
+    yes_votes: Uint128,
+    no_votes: Uint128,
+    end_height: u64,
+    title: String,
+    description: String,
+    link: Option<String>,
+    execute_data: Option<Vec<ExecuteDataV0_1>>,
+    deposit_amount: Uint128,
+    total_balance_at_end_poll: Option<Uint128>,
+    staked_amount: Option<Uint128>,
+}
+
+/// Governance becomes the pauser of itself, as it is on instantiation,
+/// vetoes need more than a third of the votes and withdrawals are not unbonded
+/// unless configured otherwise.
+/// The current shares are checkpointed as the voting power of the polls in progress.
+/// The periods and the ends of the polls stay measured in blocks
+/// and their messages become wasm executions.
+/// The stores move to cw-storage-plus and are keyed by addresses,
+/// the polls are indexed by status and creator.
+pub fn v0_2(storage: &mut dyn Storage, api: &dyn Api, env: &Env) -> StdResult<()> {
+    if PAUSE_INFO.may_load(storage)?.is_none() {
+        set_pausers(storage, None, Some(env.contract.address.clone()))?;
+    }
+
+    let legacy: StateV0_1 = singleton(storage, KEY_STATE).load()?;
+    singleton::<StateV0_1>(storage, KEY_STATE).remove();
+    STATE.save(
+        storage,
+        &State {
+            contract_addr: api.addr_humanize(&legacy.contract_addr)?,
+            poll_count: legacy.poll_count,
+            total_share: legacy.total_share,
+            total_deposit: legacy.total_deposit,
+            reward_index: Decimal::zero(),
+            pending_reward: Uint128::zero(),
+            native_reward_index: Decimal::zero(),
+            native_reward_balance: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            total_locked: Uint128::zero(),
+        },
+    )?;
+
+    let stakers = bucket::<TokenManager>(storage, PREFIX_BANK)
+        .range(None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Vec<u8>, TokenManager)>>>()?;
+    for (key, token_manager) in stakers {
+        let staker = api.addr_humanize(&CanonicalAddr::from(key.clone()))?;
+        save_staked_share(storage, &staker, token_manager.share, legacy.total_share, 0)?;
+        STAKERS.save(storage, &staker, &token_manager)?;
+        bucket::<TokenManager>(storage, PREFIX_BANK).remove(&key);
+    }
+
+    let legacy_polls = bucket::<PollV0_1>(storage, PREFIX_POLL)
+        .range(None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Vec<u8>, PollV0_1)>>>()?;
+    for (key, legacy) in legacy_polls {
+        bucket::<PollV0_1>(storage, PREFIX_POLL).remove(&key);
+        Bucket::<bool>::multilevel(
+            storage,
+            &[PREFIX_POLL_INDEXER, legacy.status.to_string().as_bytes()],
+        )
+        .remove(&key);
+
+        let voters = poll_voters_v0_1(storage, legacy.id)
+            .range(None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(Vec<u8>, VoterInfo)>>>()?;
+        for (voter, voter_info) in voters {
+            poll_voters_v0_1(storage, legacy.id).remove(&voter);
+            let voter = api.addr_humanize(&CanonicalAddr::from(voter))?;
+            VOTER_HISTORY.save(storage, (&voter, U64Key::new(legacy.id)), &voter_info)?;
+            POLL_VOTERS.save(storage, (U64Key::new(legacy.id), &voter), &voter_info)?;
+        }
+
+        let start_height = if legacy.status == PollStatus::InProgress {
+            env.block.height
+        } else {
+            0
+        };
+        let execute_data = legacy
+            .execute_data
+            .map(|msgs| {
+                msgs.into_iter()
+                    .map(|data| {
+                        Ok(ExecuteData {
+                            order: data.order,
+                            msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                                contract_addr: api.addr_humanize(&data.contract)?.to_string(),
+                                msg: data.msg,
+                                funds: vec![],
+                            }),
+                        })
+                    })
+                    .collect::<StdResult<Vec<ExecuteData>>>()
+            })
+            .transpose()?;
+        polls().save(
+            storage,
+            U64Key::new(legacy.id),
+            &Poll {
+                id: legacy.id,
+                creator: api.addr_humanize(&legacy.creator)?,
+                status: legacy.status,
+                yes_votes: legacy.yes_votes,
+                no_votes: legacy.no_votes,
+                abstain_votes: Uint128::zero(),
+                veto_votes: Uint128::zero(),
+                start_height,
+                end: Expiration::AtHeight(legacy.end_height),
+                title: legacy.title,
+                description: legacy.description,
+                link: legacy.link,
+                execute_data,
+                deposit_amount: legacy.deposit_amount,
+                total_balance_at_end_poll: legacy.total_balance_at_end_poll,
+                staked_amount: legacy.staked_amount,
+                error: None,
+                deposits: vec![],
+            },
+        )?;
+    }
+
+    let legacy: ConfigV0_1 = singleton(storage, KEY_CONFIG).load()?;
+    singleton::<ConfigV0_1>(storage, KEY_CONFIG).remove();
+    CONFIG.save(
+        storage,
+        &Config {
+            owner: api.addr_humanize(&legacy.owner)?,
+            pollterra_token: api.addr_humanize(&legacy.pollterra_token)?,
+            quorum: legacy.quorum,
+            threshold: legacy.threshold,
+            veto_threshold: Decimal::permille(334),
+            voting_period: Duration::Height(legacy.voting_period),
+            timelock_period: Duration::Height(legacy.timelock_period),
+            expiration_period: Duration::Height(legacy.expiration_period),
+            proposal_deposit: legacy.proposal_deposit,
+            snapshot_period: Duration::Height(legacy.snapshot_period),
+            unbonding_period: 0,
+            text_poll_params: None,
+            target_poll_params: vec![],
+            deposit_period: None,
+            stake_assets: vec![],
+            max_lock_weeks: 0,
+            meta_contract: None,
+            community_contract: None,
+            distributor_contract: None,
+        },
+    )
+}
+
+fn poll_voters_v0_1(storage: &mut dyn Storage, poll_id: u64) -> Bucket<'_, VoterInfo> {
+    Bucket::multilevel(storage, &[PREFIX_POLL_VOTER, &poll_id.to_be_bytes()])
+}
+
+pub fn apply_config(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    msg: MigrateMsg,
+) -> Result<(), ContractError> {
+    let live_polls = has_live_polls(storage)?;
+    CONFIG.update(storage, |mut config| {
+        let previous = config.clone();
+
+        if let Some(quorum) = msg.quorum {
+            validate_quorum(quorum)?;
+            config.quorum = quorum;
+        }
+
+        if let Some(threshold) = msg.threshold {
+            validate_threshold(threshold)?;
+            config.threshold = threshold;
+        }
+
+        if let Some(veto_threshold) = msg.veto_threshold {
+            validate_veto_threshold(veto_threshold)?;
+            config.veto_threshold = veto_threshold;
+        }
+
+        if let Some(voting_period) = msg.voting_period {
+            config.voting_period = voting_period;
+        }
+
+        if let Some(timelock_period) = msg.timelock_period {
+            config.timelock_period = timelock_period;
+        }
+
+        if let Some(expiration_period) = msg.expiration_period {
+            config.expiration_period = expiration_period;
+        }
+
+        if let Some(proposal_deposit) = msg.proposal_deposit {
+            config.proposal_deposit = proposal_deposit;
+        }
+
+        if let Some(period) = msg.snapshot_period {
+            config.snapshot_period = period;
+        }
+
+        if let Some(period) = msg.unbonding_period {
+            config.unbonding_period = period;
+        }
+
+        if let Some(meta_contract) = msg.meta_contract {
+            config.meta_contract = Some(api.addr_validate(&meta_contract)?);
+        }
+
+        if let Some(community_contract) = msg.community_contract {
+            config.community_contract = Some(api.addr_validate(&community_contract)?);
+        }
+
+        if let Some(distributor_contract) = msg.distributor_contract {
+            config.distributor_contract = Some(api.addr_validate(&distributor_contract)?);
+        }
+
+        validate_poll_period(&config)?;
+        validate_poll_period_unit(&previous, &config, live_polls)?;
+
+        Ok::<_, ContractError>(config)
+    })?;
+
+    Ok(())
+}
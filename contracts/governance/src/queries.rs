@@ -1,43 +1,77 @@
 #[cfg(not(feature = "library"))]
-use cosmwasm_std::{Deps, StdResult};
+use cosmwasm_std::{Deps, Env, StdResult};
+use cw_storage_plus::U64Key;
 
 use crate::error::ContractError;
+use crate::executions::{current_voting_stake, poll_targets, tally, TallyOutcome};
+use crate::staking::locked_voting_balance;
 use messages::governance::state::{
-    config_read, poll_read, read_poll_voters, read_polls, state_read, Config, ConfigResponse,
-    OrderBy, Poll, PollExecuteMsg, PollResponse, PollStatus, PollsResponse, State, StateResponse,
-    VotersResponse, VotersResponseItem,
+    polls, read_poll_voters, read_polls, read_voter_history, Config, ConfigResponse,
+    DelegationResponse, LockedPoll, LockedPollsResponse, OrderBy, Poll, PollExecuteMsg,
+    PollResponse, PollStatus, PollsResponse, StakeAssetMsg, State, StateResponse, TallyResponse,
+    TargetPollParamsMsg, VoterHistoryResponse, VoterHistoryResponseItem, VotersResponse,
+    VotersResponseItem, CONFIG, DELEGATIONS, DELEGATORS, STAKERS, STATE,
 };
+use messages::pause::PauseInfo;
 
 /// query_config allows for the query of the currently set configuration values
 /// which influence Polls such as the quorum needed and the minimum voting peroid before a poll can be ended
 pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
-    let config: Config = config_read(deps.storage).load()?;
+    let config: Config = CONFIG.load(deps.storage)?;
     Ok(ConfigResponse {
-        owner: deps.api.addr_humanize(&config.owner)?.to_string(),
-        pollterra_token: deps.api.addr_humanize(&config.pollterra_token)?.to_string(),
+        owner: config.owner.to_string(),
+        pollterra_token: config.pollterra_token.to_string(),
         quorum: config.quorum,
         threshold: config.threshold,
+        veto_threshold: config.veto_threshold,
         voting_period: config.voting_period,
         timelock_period: config.timelock_period,
         expiration_period: config.expiration_period,
         proposal_deposit: config.proposal_deposit,
         snapshot_period: config.snapshot_period,
+        unbonding_period: config.unbonding_period,
+        text_poll_params: config.text_poll_params,
+        target_poll_params: config
+            .target_poll_params
+            .into_iter()
+            .map(|v| {
+                Ok(TargetPollParamsMsg {
+                    contract: v.contract.to_string(),
+                    params: v.params,
+                })
+            })
+            .collect::<StdResult<Vec<TargetPollParamsMsg>>>()?,
+        deposit_period: config.deposit_period,
+        stake_assets: config
+            .stake_assets
+            .into_iter()
+            .map(|v| StakeAssetMsg {
+                token: v.token.to_string(),
+                weight: v.weight,
+            })
+            .collect(),
+        max_lock_weeks: config.max_lock_weeks,
+        meta_contract: config.meta_contract.map(|v| v.to_string()),
+        community_contract: config.community_contract.map(|v| v.to_string()),
+        distributor_contract: config.distributor_contract.map(|v| v.to_string()),
+        pause: PauseInfo::load(deps.storage)?,
     })
 }
 
 /// query_state allows for the query of dynamic state values such as the poll count and how much has been deposited
 pub fn query_state(deps: Deps) -> Result<StateResponse, ContractError> {
-    let state: State = state_read(deps.storage).load()?;
+    let state: State = STATE.load(deps.storage)?;
     Ok(StateResponse {
         poll_count: state.poll_count,
         total_share: state.total_share,
         total_deposit: state.total_deposit,
+        pending_reward: state.pending_reward,
     })
 }
 
 /// query_poll allows for the query of a given poll by supplying its poll_id
 pub fn query_poll(deps: Deps, poll_id: u64) -> Result<PollResponse, ContractError> {
-    let poll = match poll_read(deps.storage).may_load(&poll_id.to_be_bytes())? {
+    let poll = match polls().may_load(deps.storage, U64Key::new(poll_id))? {
         Some(poll) => Some(poll),
         None => return Err(ContractError::PollNotFound {}),
     }
@@ -47,9 +81,10 @@ pub fn query_poll(deps: Deps, poll_id: u64) -> Result<PollResponse, ContractErro
 
     Ok(PollResponse {
         id: poll.id,
-        creator: deps.api.addr_humanize(&poll.creator)?.to_string(),
+        creator: poll.creator.to_string(),
         status: poll.status,
-        end_height: poll.end_height,
+        start_height: poll.start_height,
+        end: poll.end,
         title: poll.title,
         description: poll.description,
         link: poll.link,
@@ -58,7 +93,6 @@ pub fn query_poll(deps: Deps, poll_id: u64) -> Result<PollResponse, ContractErro
             for msg in exe_msgs {
                 let execute_data = PollExecuteMsg {
                     order: msg.order,
-                    contract: deps.api.addr_humanize(&msg.contract)?.to_string(),
                     msg: msg.msg,
                 };
                 data_list.push(execute_data)
@@ -69,7 +103,10 @@ pub fn query_poll(deps: Deps, poll_id: u64) -> Result<PollResponse, ContractErro
         },
         yes_votes: poll.yes_votes,
         no_votes: poll.no_votes,
+        abstain_votes: poll.abstain_votes,
+        veto_votes: poll.veto_votes,
         staked_amount: poll.staked_amount,
+        error: poll.error,
         total_balance_at_end_poll: poll.total_balance_at_end_poll,
     })
 }
@@ -77,20 +114,23 @@ pub fn query_poll(deps: Deps, poll_id: u64) -> Result<PollResponse, ContractErro
 pub fn query_polls(
     deps: Deps,
     filter: Option<PollStatus>,
+    creator: Option<String>,
     start_after: Option<u64>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
 ) -> Result<PollsResponse, ContractError> {
-    let polls = read_polls(deps.storage, filter, start_after, limit, order_by)?;
+    let creator = creator.map(|v| deps.api.addr_validate(&v)).transpose()?;
+    let poll_list = read_polls(deps.storage, filter, creator, start_after, limit, order_by)?;
 
-    let poll_responses: StdResult<Vec<PollResponse>> = polls
+    let poll_responses: StdResult<Vec<PollResponse>> = poll_list
         .iter()
         .map(|poll| {
             Ok(PollResponse {
                 id: poll.id,
-                creator: deps.api.addr_humanize(&poll.creator)?.to_string(),
+                creator: poll.creator.to_string(),
                 status: poll.status.clone(),
-                end_height: poll.end_height,
+                start_height: poll.start_height,
+                end: poll.end,
                 title: poll.title.to_string(),
                 description: poll.description.to_string(),
                 link: poll.link.clone(),
@@ -101,7 +141,6 @@ pub fn query_polls(
                     for msg in exe_msgs {
                         let execute_data = PollExecuteMsg {
                             order: msg.order,
-                            contract: deps.api.addr_humanize(&msg.contract)?.to_string(),
                             msg: msg.msg,
                         };
                         data_list.push(execute_data)
@@ -112,7 +151,10 @@ pub fn query_polls(
                 },
                 yes_votes: poll.yes_votes,
                 no_votes: poll.no_votes,
+                abstain_votes: poll.abstain_votes,
+                veto_votes: poll.veto_votes,
                 staked_amount: poll.staked_amount,
+                error: poll.error.clone(),
                 total_balance_at_end_poll: poll.total_balance_at_end_poll,
             })
         })
@@ -130,7 +172,7 @@ pub fn query_voters(
     limit: Option<u32>,
     order_by: Option<OrderBy>,
 ) -> Result<VotersResponse, ContractError> {
-    let poll: Poll = match poll_read(deps.storage).may_load(&poll_id.to_be_bytes())? {
+    let poll: Poll = match polls().may_load(deps.storage, U64Key::new(poll_id))? {
         Some(poll) => Some(poll),
         None => return Err(ContractError::PollNotFound {}),
     }
@@ -138,25 +180,18 @@ pub fn query_voters(
 
     let voters = if poll.status != PollStatus::InProgress {
         vec![]
-    } else if let Some(start_after) = start_after {
-        read_poll_voters(
-            deps.storage,
-            poll_id,
-            Some(deps.api.addr_canonicalize(&start_after)?),
-            limit,
-            order_by,
-        )?
     } else {
-        read_poll_voters(deps.storage, poll_id, None, limit, order_by)?
+        read_poll_voters(deps.storage, poll_id, start_after, limit, order_by)?
     };
 
     let voters_response: StdResult<Vec<VotersResponseItem>> = voters
         .iter()
         .map(|voter_info| {
             Ok(VotersResponseItem {
-                voter: deps.api.addr_humanize(&voter_info.0)?.to_string(),
+                voter: voter_info.0.to_string(),
                 vote: voter_info.1.vote.clone(),
                 balance: voter_info.1.balance,
+                delegated: voter_info.1.delegated,
             })
         })
         .collect();
@@ -165,3 +200,118 @@ pub fn query_voters(
         voters: voters_response?,
     })
 }
+
+pub fn query_delegation(deps: Deps, address: String) -> Result<DelegationResponse, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+
+    let delegate = DELEGATIONS
+        .may_load(deps.storage, &address)?
+        .map(|v| v.to_string());
+    let delegators = DELEGATORS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default()
+        .iter()
+        .map(|v| v.to_string())
+        .collect();
+
+    Ok(DelegationResponse {
+        delegate,
+        delegators,
+    })
+}
+
+pub fn query_voter_history(
+    deps: Deps,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<VoterHistoryResponse, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+
+    let votes = read_voter_history(deps.storage, &address, start_after, limit)?
+        .into_iter()
+        .map(|(poll_id, voter_info)| {
+            let poll: Poll = polls().load(deps.storage, U64Key::new(poll_id))?;
+            Ok(VoterHistoryResponseItem {
+                poll_id,
+                status: poll.status,
+                vote: voter_info.vote,
+                balance: voter_info.balance,
+                delegated: voter_info.delegated,
+            })
+        })
+        .collect::<StdResult<Vec<VoterHistoryResponseItem>>>()?;
+
+    Ok(VoterHistoryResponse { votes })
+}
+
+/// query_tally computes the progress of a poll the way end_poll does,
+/// with the voting stake at the end of the poll once it has ended
+pub fn query_tally(deps: Deps, poll_id: u64) -> Result<TallyResponse, ContractError> {
+    let poll: Poll = polls()
+        .may_load(deps.storage, U64Key::new(poll_id))?
+        .ok_or(ContractError::PollNotFound {})?;
+    let config: Config = CONFIG.load(deps.storage)?;
+    let state: State = STATE.load(deps.storage)?;
+    let params = config.poll_params(poll_targets(deps.api, &poll.execute_data)?);
+
+    let staked_amount = match (poll.total_balance_at_end_poll, poll.staked_amount) {
+        (Some(staked_amount), _) | (None, Some(staked_amount)) => staked_amount,
+        (None, None) => current_voting_stake(deps, &config, &poll, &state)?,
+    };
+    let tally = tally(&poll, &config, &params, staked_amount);
+
+    Ok(TallyResponse {
+        poll_id,
+        status: poll.status,
+        yes_votes: poll.yes_votes,
+        no_votes: poll.no_votes,
+        abstain_votes: poll.abstain_votes,
+        veto_votes: poll.veto_votes,
+        staked_amount,
+        quorum: tally.quorum,
+        threshold: tally.threshold,
+        veto: tally.veto,
+        required_quorum: params.quorum,
+        required_threshold: params.threshold,
+        veto_threshold: config.veto_threshold,
+        passing: tally.outcome == TallyOutcome::Passed,
+    })
+}
+
+pub fn query_locked_polls(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> Result<LockedPollsResponse, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+    let config: Config = CONFIG.load(deps.storage)?;
+    let token_manager = STAKERS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+
+    // the votes on polls which are not in progress anymore are unlocked
+    let mut locked_polls: Vec<LockedPoll> = vec![];
+    for (poll_id, voter_info) in token_manager.locked_balance.iter() {
+        let poll: Poll = polls().load(deps.storage, U64Key::new(*poll_id))?;
+        if poll.status == PollStatus::InProgress {
+            locked_polls.push(LockedPoll {
+                poll_id: *poll_id,
+                end: poll.end,
+                vote: voter_info.vote.clone(),
+                balance: voter_info.balance,
+            });
+        }
+    }
+
+    Ok(LockedPollsResponse {
+        locked_balance: locked_voting_balance(
+            deps.storage,
+            &config,
+            &token_manager,
+            &address,
+            env.block.height,
+        )?,
+        polls: locked_polls,
+    })
+}
@@ -0,0 +1,33 @@
+use cosmwasm_std::{ContractResult, DepsMut, Reply, Response};
+use cw_storage_plus::U64Key;
+
+use crate::entrypoints::EXECUTE_POLL_REPLY_ID_BASE;
+use crate::error::ContractError;
+use messages::governance::state::{polls, Poll, PollStatus};
+
+/// AfterPollExecute records the failure of the messages of an executed poll,
+/// the poll can then be executed again until it expires
+pub fn after_poll_execute(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let error = match msg.result {
+        ContractResult::Err(error) => error,
+        ContractResult::Ok(_) => return Err(ContractError::InvalidReplyId {}),
+    };
+
+    let poll_id = msg.id - EXECUTE_POLL_REPLY_ID_BASE;
+    let mut a_poll: Poll = polls()
+        .may_load(deps.storage, U64Key::new(poll_id))?
+        .ok_or(ContractError::InvalidReplyId {})?;
+    if a_poll.status != PollStatus::Executed {
+        return Err(ContractError::InvalidReplyId {});
+    }
+
+    a_poll.status = PollStatus::Failed;
+    a_poll.error = Some(error.clone());
+    polls().save(deps.storage, U64Key::new(poll_id), &a_poll)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "execute_poll_failed"),
+        ("poll_id", poll_id.to_string().as_str()),
+        ("error", error.as_str()),
+    ]))
+}
@@ -0,0 +1,81 @@
+use std::convert::TryFrom;
+
+use cosmwasm_std::{StdResult, Uint128, Uint256};
+
+use crate::error::ContractError;
+
+// the largest part of a stake, in permille, which may be lost to rounding down its share
+const MAX_ROUNDING_LOSS_PERMILLE: u128 = 1;
+
+/// stake_share is the share minted for staking the amount into the pool,
+/// rounded down in favor of the pool.
+/// Tokens sent to the contract raise the share price, so a staker owning the
+/// whole pool could inflate it until the next stake rounds down to nothing.
+/// Such stakes fail once they lose more than a token and the permille limit.
+pub fn stake_share(
+    amount: Uint128,
+    total_share: Uint128,
+    total_balance: Uint128,
+) -> Result<Uint128, ContractError> {
+    // the first staker gets a share per token
+    if total_share.is_zero() || total_balance.is_zero() {
+        return Ok(amount);
+    }
+
+    let (share, remainder) = mul_div(amount, total_share, total_balance)?;
+
+    // the dropped fraction of a share is worth remainder / total_share tokens
+    let total_share = Uint256::from(total_share);
+    let loss_limit = Uint256::from(amount)
+        .checked_mul(total_share)?
+        .checked_mul(Uint256::from(MAX_ROUNDING_LOSS_PERMILLE))?;
+    if share.is_zero()
+        || (remainder > total_share && remainder.checked_mul(Uint256::from(1000u32))? > loss_limit)
+    {
+        return Err(ContractError::StakeTooSmall {});
+    }
+
+    Ok(share)
+}
+
+/// withdraw_share is the share burnt for withdrawing the amount from the pool,
+/// rounded up in favor of the pool
+pub fn withdraw_share(
+    amount: Uint128,
+    total_share: Uint128,
+    total_balance: Uint128,
+) -> StdResult<Uint128> {
+    let (share, remainder) = mul_div(amount, total_share, total_balance)?;
+    if remainder.is_zero() {
+        Ok(share)
+    } else {
+        Ok(share.checked_add(Uint128::new(1))?)
+    }
+}
+
+/// share_amount is the amount of tokens the share is worth, rounded down
+pub fn share_amount(
+    share: Uint128,
+    total_share: Uint128,
+    total_balance: Uint128,
+) -> StdResult<Uint128> {
+    if total_share.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    Ok(mul_div(share, total_balance, total_share)?.0)
+}
+
+// value * numerator / denominator through a 256 bit product, with the remainder of the division
+fn mul_div(
+    value: Uint128,
+    numerator: Uint128,
+    denominator: Uint128,
+) -> StdResult<(Uint128, Uint256)> {
+    let product = Uint256::from(value).checked_mul(Uint256::from(numerator))?;
+    let denominator = Uint256::from(denominator);
+    let quotient = product.checked_div(denominator)?;
+    let remainder = product.checked_rem(denominator)?;
+
+    Ok((Uint128::try_from(quotient)?, remainder))
+}
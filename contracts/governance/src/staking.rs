@@ -1,51 +1,64 @@
+use crate::entrypoints::MAX_UNBONDINGS;
 use crate::error::ContractError;
+use crate::shares;
 use messages::governance::state::{
-    bank_read, bank_store, config_read, config_store, poll_read, poll_voter_store, state_read,
-    state_store, Config, Poll, PollStatus, StakerResponse, State, TokenManager,
+    load_ve_lock, polls, save_staked_asset, save_staked_share, save_ve_lock, total_asset,
+    total_ve_power_at, ve_lock_at, Config, Poll, PollStatus, StakerAssetResponse, StakerResponse,
+    StakerRewardsResponse, State, TokenManager, TotalVePowerResponse, Unbonding,
+    UnbondingsResponse, VeLock, VeLockResponse, CONFIG, LOCK_WEEK_BLOCKS, POLL_VOTERS, STAKERS,
+    STATE, UNBONDINGS,
 };
+use messages::pause::{PauseInfo, PauseOperation};
 
 use cosmwasm_std::{
-    to_binary, Addr, CanonicalAddr, CosmosMsg, Deps, DepsMut, MessageInfo, Response, StdResult,
-    Storage, Uint128, WasmMsg,
+    to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Storage, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::U64Key;
 use terraswap::querier::query_token_balance;
 
+pub(crate) const DENOM: &str = "uusd";
+
 pub fn stake_voting_tokens(
     deps: DepsMut,
+    env: Env,
     sender: Addr,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Staking)?;
+
     if amount.is_zero() {
         return Err(ContractError::InsufficientFunds {});
     }
 
-    let sender_address_raw = deps.api.addr_canonicalize(sender.as_str())?;
-    let key = &sender_address_raw.as_slice();
-
-    let mut token_manager = bank_read(deps.storage).may_load(key)?.unwrap_or_default();
-    let config: Config = config_store(deps.storage).load()?;
-    let mut state: State = state_store(deps.storage).load()?;
+    let mut token_manager = STAKERS.may_load(deps.storage, &sender)?.unwrap_or_default();
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
 
     // balance already increased, so subtract deposit amount
     let total_balance = query_token_balance(
         &deps.querier,
-        deps.api.addr_humanize(&config.pollterra_token)?,
-        deps.api.addr_humanize(&state.contract_addr)?,
+        config.pollterra_token.clone(),
+        state.contract_addr.clone(),
     )?
-    .checked_sub(state.total_deposit + amount)?;
+    .checked_sub(state.unstaked_balance() + amount)?;
 
-    let share = if total_balance.is_zero() || state.total_share.is_zero() {
-        amount
-    } else {
-        amount.multiply_ratio(state.total_share, total_balance)
-    };
+    let share = shares::stake_share(amount, state.total_share, total_balance)?;
 
+    accrue_rewards(&state, &mut token_manager);
     token_manager.share += share;
     state.total_share += share;
 
-    state_store(deps.storage).save(&state)?;
-    bank_store(deps.storage).save(key, &token_manager)?;
+    STATE.save(deps.storage, &state)?;
+    STAKERS.save(deps.storage, &sender, &token_manager)?;
+    save_staked_share(
+        deps.storage,
+        &sender,
+        token_manager.share,
+        state.total_share,
+        env.block.height,
+    )?;
 
     Ok(Response::new().add_attributes(vec![
         ("action", "staking"),
@@ -55,150 +68,833 @@ pub fn stake_voting_tokens(
     ]))
 }
 
+/// stake_asset_tokens stakes a token other than POL, which votes by its weight.
+/// The assets are held as they are staked, they earn no rewards so they need no shares.
+pub fn stake_asset_tokens(
+    deps: DepsMut,
+    env: Env,
+    token: Addr,
+    sender: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Staking)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::InsufficientFunds {});
+    }
+
+    let mut token_manager = STAKERS.may_load(deps.storage, &sender)?.unwrap_or_default();
+    let staked = match token_manager.assets.iter_mut().find(|(t, _)| t == &token) {
+        Some((_, staked)) => {
+            *staked = staked.checked_add(amount)?;
+            *staked
+        }
+        None => {
+            token_manager.assets.push((token.clone(), amount));
+            amount
+        }
+    };
+    let total = total_asset(deps.storage, &token)?.checked_add(amount)?;
+
+    STAKERS.save(deps.storage, &sender, &token_manager)?;
+    save_staked_asset(
+        deps.storage,
+        &sender,
+        &token,
+        staked,
+        total,
+        env.block.height,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "staking"),
+        ("sender", sender.as_str()),
+        ("token", token.as_str()),
+        ("amount", amount.to_string().as_str()),
+    ]))
+}
+
 // Withdraw amount if not staked. By default all funds will be withdrawn.
 pub fn withdraw_voting_tokens(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     amount: Option<Uint128>,
 ) -> Result<Response, ContractError> {
-    let sender_address_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
-    let key = sender_address_raw.as_slice();
+    PauseInfo::assert_active(deps.storage, PauseOperation::Staking)?;
 
-    if let Some(mut token_manager) = bank_read(deps.storage).may_load(key)? {
-        let config: Config = config_store(deps.storage).load()?;
-        let mut state: State = state_store(deps.storage).load()?;
+    if let Some(mut token_manager) = STAKERS.may_load(deps.storage, &info.sender)? {
+        let config: Config = CONFIG.load(deps.storage)?;
+        let mut state: State = STATE.load(deps.storage)?;
 
-        // Load total share & total balance except proposal deposit amount
-        let total_share = state.total_share.u128();
+        // Load total share & total balance except the tokens which are not staked
+        let total_share = state.total_share;
         let total_balance = query_token_balance(
             &deps.querier,
-            deps.api.addr_humanize(&config.pollterra_token)?,
-            deps.api.addr_humanize(&state.contract_addr)?,
+            config.pollterra_token.clone(),
+            state.contract_addr.clone(),
         )?
-        .checked_sub(state.total_deposit)?
-        .u128();
+        .checked_sub(state.unstaked_balance())?;
 
-        let locked_balance =
-            compute_locked_balance(deps.storage, &mut token_manager, &sender_address_raw);
-        let locked_share = locked_balance * total_share / total_balance;
-        let user_share = token_manager.share.u128();
-
-        let withdraw_share = amount
-            .map(|v| std::cmp::max(v.multiply_ratio(total_share, total_balance).u128(), 1u128))
-            .unwrap_or_else(|| user_share - locked_share);
-        let withdraw_amount = amount
-            .map(|v| v.u128())
-            .unwrap_or_else(|| withdraw_share * total_balance / total_share);
-
-        if locked_share + withdraw_share > user_share {
+        accrue_rewards(&state, &mut token_manager);
+
+        compute_locked_balance(deps.storage, &mut token_manager, &info.sender)?;
+        let locked_balance = locked_voting_balance(
+            deps.storage,
+            &config,
+            &token_manager,
+            &info.sender,
+            env.block.height,
+        )?;
+        let locked_share = shares::withdraw_share(locked_balance, total_share, total_balance)?;
+        let user_share = token_manager.share;
+
+        let (withdraw_share, withdraw_amount) = match amount {
+            Some(amount) => (
+                shares::withdraw_share(amount, total_share, total_balance)?,
+                amount,
+            ),
+            None => {
+                let share = user_share
+                    .checked_sub(locked_share)
+                    .map_err(|_| ContractError::InvalidWithdrawAmount {})?;
+                (
+                    share,
+                    shares::share_amount(share, total_share, total_balance)?,
+                )
+            }
+        };
+
+        if withdraw_amount.is_zero() || locked_share.checked_add(withdraw_share)? > user_share {
             Err(ContractError::InvalidWithdrawAmount {})
         } else {
-            let share = user_share - withdraw_share;
-            token_manager.share = Uint128::from(share);
+            token_manager.share = user_share.checked_sub(withdraw_share)?;
+
+            STAKERS.save(deps.storage, &info.sender, &token_manager)?;
 
-            bank_store(deps.storage).save(key, &token_manager)?;
+            state.total_share = total_share.checked_sub(withdraw_share)?;
+            save_staked_share(
+                deps.storage,
+                &info.sender,
+                token_manager.share,
+                state.total_share,
+                env.block.height,
+            )?;
 
-            state.total_share = Uint128::from(total_share - withdraw_share);
-            state_store(deps.storage).save(&state)?;
+            if config.unbonding_period == 0 {
+                STATE.save(deps.storage, &state)?;
 
-            send_tokens(
-                deps,
-                &config.pollterra_token,
-                &sender_address_raw,
+                return send_tokens(
+                    &config.pollterra_token,
+                    &info.sender,
+                    withdraw_amount,
+                    "withdraw",
+                );
+            }
+
+            // the tokens stop voting and earning rewards, but are sent after the unbonding period
+            let release_height = env.block.height + config.unbonding_period;
+            unbond(
+                deps.storage,
+                &info.sender,
+                None,
                 withdraw_amount,
-                "withdraw",
-            )
+                release_height,
+            )?;
+
+            state.total_unbonding = state.total_unbonding.checked_add(withdraw_amount)?;
+            STATE.save(deps.storage, &state)?;
+
+            Ok(Response::new().add_attributes(vec![
+                ("action", "withdraw"),
+                ("recipient", info.sender.as_str()),
+                ("amount", withdraw_amount.to_string().as_str()),
+                ("release_height", release_height.to_string().as_str()),
+            ]))
         }
     } else {
         Err(ContractError::NothingStaked {})
     }
 }
 
+/// withdraw_asset_tokens withdraws a stake asset other than POL, by default the whole stake of it.
+/// The stake left must still cover the balance locked in the polls in progress.
+pub fn withdraw_asset_tokens(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token: String,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Staking)?;
+
+    let token = deps.api.addr_validate(&token)?;
+    let mut token_manager = STAKERS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let index = token_manager
+        .assets
+        .iter()
+        .position(|(t, _)| t == &token)
+        .ok_or(ContractError::NothingStaked {})?;
+    let staked = token_manager.assets[index].1;
+    let withdraw_amount = amount.unwrap_or(staked);
+    if withdraw_amount.is_zero() || withdraw_amount > staked {
+        return Err(ContractError::InvalidWithdrawAmount {});
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let locked_balance = compute_locked_balance(deps.storage, &mut token_manager, &info.sender)?;
+    let remaining = staked.checked_sub(withdraw_amount)?;
+    if remaining.is_zero() {
+        token_manager.assets.remove(index);
+    } else {
+        token_manager.assets[index].1 = remaining;
+    }
+
+    if !locked_balance.is_zero() {
+        let state: State = STATE.load(deps.storage)?;
+        let total_balance = query_token_balance(
+            &deps.querier,
+            config.pollterra_token.clone(),
+            state.contract_addr.clone(),
+        )?
+        .checked_sub(state.unstaked_balance())?;
+        let voting_power =
+            shares::share_amount(token_manager.share, state.total_share, total_balance)?
+                .checked_add(asset_voting_power(&config, &token_manager)?)?
+                .checked_add(ve_power(deps.storage, &info.sender, env.block.height)?)?;
+        if voting_power < locked_balance {
+            return Err(ContractError::InvalidWithdrawAmount {});
+        }
+    }
+
+    let total = total_asset(deps.storage, &token)?.checked_sub(withdraw_amount)?;
+
+    STAKERS.save(deps.storage, &info.sender, &token_manager)?;
+    save_staked_asset(
+        deps.storage,
+        &info.sender,
+        &token,
+        remaining,
+        total,
+        env.block.height,
+    )?;
+
+    if config.unbonding_period == 0 {
+        return send_tokens(&token, &info.sender, withdraw_amount, "withdraw");
+    }
+
+    // the assets stop voting, but are sent after the unbonding period
+    let release_height = env.block.height + config.unbonding_period;
+    unbond(
+        deps.storage,
+        &info.sender,
+        Some(token.clone()),
+        withdraw_amount,
+        release_height,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "withdraw"),
+        ("recipient", info.sender.as_str()),
+        ("token", token.as_str()),
+        ("amount", withdraw_amount.to_string().as_str()),
+        ("release_height", release_height.to_string().as_str()),
+    ]))
+}
+
+/// lock_tokens locks the sent POL for the weeks, rounded down to the start of a week.
+/// The lock votes with its amount scaled by the time left, so its power decays until it ends.
+/// Locked POL earns no rewards and is not part of the stake shares.
+pub fn lock_tokens(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    amount: Uint128,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Staking)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::InsufficientFunds {});
+    }
+    if load_ve_lock(deps.storage, &sender)?.is_some() {
+        return Err(ContractError::LockExists {});
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let end_height = lock_end(&config, env.block.height, env.block.height, duration)?;
+    let lock = VeLock { amount, end_height };
+    save_ve_lock(deps.storage, &sender, Some(&lock), env.block.height)?;
+
+    let mut state: State = STATE.load(deps.storage)?;
+    state.total_locked = state.total_locked.checked_add(amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "lock"),
+        ("sender", sender.as_str()),
+        ("amount", amount.to_string().as_str()),
+        ("end_height", end_height.to_string().as_str()),
+    ]))
+}
+
+/// increase_lock adds the sent POL to the lock of the sender, ending with it
+pub fn increase_lock(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Staking)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::InsufficientFunds {});
+    }
+    let mut lock = active_lock(deps.storage, &sender, env.block.height)?;
+    lock.amount = lock.amount.checked_add(amount)?;
+    save_ve_lock(deps.storage, &sender, Some(&lock), env.block.height)?;
+
+    let mut state: State = STATE.load(deps.storage)?;
+    state.total_locked = state.total_locked.checked_add(amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "increase_lock"),
+        ("sender", sender.as_str()),
+        ("amount", amount.to_string().as_str()),
+        ("locked_amount", lock.amount.to_string().as_str()),
+    ]))
+}
+
+/// extend_lock moves the end of the lock of the sender the weeks later,
+/// still no later than the longest duration from now
+pub fn extend_lock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Staking)?;
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut lock = active_lock(deps.storage, &info.sender, env.block.height)?;
+    lock.end_height = lock_end(&config, env.block.height, lock.end_height, duration)?;
+    save_ve_lock(deps.storage, &info.sender, Some(&lock), env.block.height)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "extend_lock"),
+        ("sender", info.sender.as_str()),
+        ("end_height", lock.end_height.to_string().as_str()),
+    ]))
+}
+
+/// withdraw_expired sends the POL of the lock of the sender once it has ended.
+/// The stake left must still cover the balance locked in the polls in progress.
+pub fn withdraw_expired(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Staking)?;
+
+    let lock = load_ve_lock(deps.storage, &info.sender)?.ok_or(ContractError::NoLock {})?;
+    if lock.end_height > env.block.height {
+        return Err(ContractError::LockNotExpired {});
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    if let Some(mut token_manager) = STAKERS.may_load(deps.storage, &info.sender)? {
+        let locked_balance =
+            compute_locked_balance(deps.storage, &mut token_manager, &info.sender)?;
+        if !locked_balance.is_zero() {
+            let total_balance = query_token_balance(
+                &deps.querier,
+                config.pollterra_token.clone(),
+                state.contract_addr.clone(),
+            )?
+            .checked_sub(state.unstaked_balance())?;
+            let voting_power =
+                shares::share_amount(token_manager.share, state.total_share, total_balance)?
+                    .checked_add(asset_voting_power(&config, &token_manager)?)?;
+            if voting_power < locked_balance {
+                return Err(ContractError::InvalidWithdrawAmount {});
+            }
+        }
+        STAKERS.save(deps.storage, &info.sender, &token_manager)?;
+    }
+
+    save_ve_lock(deps.storage, &info.sender, None, env.block.height)?;
+    state.total_locked = state.total_locked.checked_sub(lock.amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    send_tokens(
+        &config.pollterra_token,
+        &info.sender,
+        lock.amount,
+        "withdraw_expired",
+    )
+}
+
+// the lock of the staker, which cannot be changed once it has ended
+fn active_lock(storage: &dyn Storage, staker: &Addr, height: u64) -> Result<VeLock, ContractError> {
+    match load_ve_lock(storage, staker)? {
+        Some(lock) if lock.end_height > height => Ok(lock),
+        Some(_) => Err(ContractError::LockExpired {}),
+        None => Err(ContractError::NoLock {}),
+    }
+}
+
+// the end of a lock the weeks after the given end, at the start of a week
+// and within the longest duration from the current height
+fn lock_end(config: &Config, height: u64, end: u64, duration: u64) -> Result<u64, ContractError> {
+    if config.max_lock_weeks == 0 {
+        return Err(ContractError::LockingDisabled {});
+    }
+    let max_end = (height / LOCK_WEEK_BLOCKS + config.max_lock_weeks) * LOCK_WEEK_BLOCKS;
+    let end_height = duration
+        .checked_add(end / LOCK_WEEK_BLOCKS)
+        .and_then(|weeks| weeks.checked_mul(LOCK_WEEK_BLOCKS))
+        .unwrap_or(u64::MAX);
+    if duration == 0 || end_height > max_end {
+        return Err(ContractError::InvalidLockDuration(config.max_lock_weeks));
+    }
+    Ok(end_height)
+}
+
+// adds the withdrawn amount of the token to the unbondings of the staker,
+// merged with the last one of the token released at the same height
+fn unbond(
+    storage: &mut dyn Storage,
+    staker: &Addr,
+    token: Option<Addr>,
+    amount: Uint128,
+    release_height: u64,
+) -> Result<(), ContractError> {
+    let mut unbondings = UNBONDINGS.may_load(storage, staker)?.unwrap_or_default();
+    match unbondings.last_mut() {
+        Some(last) if last.release_height == release_height && last.token == token => {
+            last.amount = last.amount.checked_add(amount)?;
+        }
+        _ => {
+            if unbondings.len() >= MAX_UNBONDINGS {
+                return Err(ContractError::TooManyUnbondings(MAX_UNBONDINGS));
+            }
+            unbondings.push(Unbonding {
+                amount,
+                release_height,
+                token,
+            });
+        }
+    }
+    UNBONDINGS.save(storage, staker, &unbondings)?;
+
+    Ok(())
+}
+
+/// claim_unbonded sends the withdrawn tokens of the sender whose unbonding period is over
+pub fn claim_unbonded(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Staking)?;
+
+    let (released, unbondings): (Vec<Unbonding>, Vec<Unbonding>) = UNBONDINGS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|v| v.release_height <= env.block.height);
+    if released.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    // POL first, then the other stake assets in the order they were withdrawn
+    let mut amounts: Vec<(Option<Addr>, Uint128)> = vec![(None, Uint128::zero())];
+    for unbonding in released {
+        match amounts.iter_mut().find(|(t, _)| t == &unbonding.token) {
+            Some((_, amount)) => *amount = amount.checked_add(unbonding.amount)?,
+            None => amounts.push((unbonding.token, unbonding.amount)),
+        }
+    }
+    let amount = amounts[0].1;
+
+    if unbondings.is_empty() {
+        UNBONDINGS.remove(deps.storage, &info.sender);
+    } else {
+        UNBONDINGS.save(deps.storage, &info.sender, &unbondings)?;
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    state.total_unbonding = state.total_unbonding.checked_sub(amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for (token, amount) in amounts {
+        if !amount.is_zero() {
+            let token = token.unwrap_or_else(|| config.pollterra_token.clone());
+            messages.push(transfer_msg(&token, &info.sender, amount)?);
+        }
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "claim_unbonded"),
+        ("recipient", info.sender.as_str()),
+        ("amount", amount.to_string().as_str()),
+    ]))
+}
+
 // removes not in-progress poll voter info & unlock tokens
 // and returns the largest locked amount in participated polls.
 fn compute_locked_balance(
     storage: &mut dyn Storage,
     token_manager: &mut TokenManager,
-    voter: &CanonicalAddr,
-) -> u128 {
-    token_manager.locked_balance.retain(|(poll_id, _)| {
-        let poll: Poll = poll_read(storage).load(&poll_id.to_be_bytes()).unwrap();
+    voter: &Addr,
+) -> StdResult<Uint128> {
+    let mut locked_balance = vec![];
+    for (poll_id, voter_info) in token_manager.locked_balance.drain(..) {
+        let poll: Poll = polls().load(storage, U64Key::new(poll_id))?;
 
-        if poll.status != PollStatus::InProgress {
+        if poll.status == PollStatus::InProgress {
+            locked_balance.push((poll_id, voter_info));
+        } else {
             // remove voter info from the poll
-            poll_voter_store(storage, *poll_id).remove(voter.as_slice());
+            POLL_VOTERS.remove(storage, (U64Key::new(poll_id), voter));
         }
+    }
+    token_manager.locked_balance = locked_balance;
 
-        poll.status == PollStatus::InProgress
-    });
-
-    token_manager
+    Ok(token_manager
         .locked_balance
         .iter()
-        .map(|(_, v)| v.balance.u128())
+        .map(|(_, v)| v.balance)
         .max()
-        .unwrap_or_default()
+        .unwrap_or_default())
 }
 
-fn send_tokens(
-    deps: DepsMut,
-    asset_token: &CanonicalAddr,
-    recipient: &CanonicalAddr,
-    amount: u128,
-    action: &str,
-) -> Result<Response, ContractError> {
-    let contract_human = deps.api.addr_humanize(asset_token)?.to_string();
-    let recipient_human = deps.api.addr_humanize(recipient)?.to_string();
+/// The staked POL locked by the votes on polls in progress.
+/// The staked assets and the lock cover the largest vote before the POL.
+pub fn locked_voting_balance(
+    storage: &dyn Storage,
+    config: &Config,
+    token_manager: &TokenManager,
+    staker: &Addr,
+    height: u64,
+) -> StdResult<Uint128> {
+    let mut largest_vote = Uint128::zero();
+    for (poll_id, voter_info) in token_manager.locked_balance.iter() {
+        let poll: Poll = polls().load(storage, U64Key::new(*poll_id))?;
+        if poll.status == PollStatus::InProgress {
+            largest_vote = largest_vote.max(voter_info.balance);
+        }
+    }
 
-    Ok(Response::new()
-        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: contract_human,
+    Ok(largest_vote
+        .saturating_sub(asset_voting_power(config, token_manager)?)
+        .saturating_sub(ve_power(storage, staker, height)?))
+}
+
+// the voting power of the staked assets by their current weight, none for the removed assets
+fn asset_voting_power(config: &Config, token_manager: &TokenManager) -> StdResult<Uint128> {
+    let mut power = Uint128::zero();
+    for (token, amount) in token_manager.assets.iter() {
+        let weight = config.asset_weight(token).unwrap_or_default();
+        power = power.checked_add(*amount * weight)?;
+    }
+    Ok(power)
+}
+
+// the current voting power of the lock of the staker
+fn ve_power(storage: &dyn Storage, staker: &Addr, height: u64) -> StdResult<Uint128> {
+    Ok(load_ve_lock(storage, staker)?
+        .map(|lock| lock.power_at(height))
+        .unwrap_or_default())
+}
+
+// adds the rewards of the current share since the last accrual, to be called before the share changes
+fn accrue_rewards(state: &State, token_manager: &mut TokenManager) {
+    token_manager.pending_reward +=
+        token_manager.share * (state.reward_index - token_manager.reward_index);
+    token_manager.reward_index = state.reward_index;
+
+    token_manager.pending_native_reward +=
+        token_manager.share * (state.native_reward_index - token_manager.native_reward_index);
+    token_manager.native_reward_index = state.native_reward_index;
+}
+
+/// deposit_reward distributes the received POL to the current stakers by their share
+pub fn deposit_reward(deps: DepsMut, amount: Uint128) -> Result<Response, ContractError> {
+    let mut state: State = STATE.load(deps.storage)?;
+    if state.total_share.is_zero() {
+        return Err(ContractError::NothingStaked {});
+    }
+
+    state.reward_index = state.reward_index + Decimal::from_ratio(amount, state.total_share);
+    state.pending_reward += amount;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "deposit_reward"),
+        ("amount", amount.to_string().as_str()),
+    ]))
+}
+
+/// deposit_native_reward distributes the uusd received since the last deposit
+pub fn deposit_native_reward(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut state: State = STATE.load(deps.storage)?;
+    if state.total_share.is_zero() {
+        return Err(ContractError::NothingStaked {});
+    }
+
+    let balance = deps
+        .querier
+        .query_balance(env.contract.address, DENOM)?
+        .amount;
+    let amount = balance
+        .checked_sub(state.native_reward_balance)
+        .unwrap_or_else(|_| Uint128::zero());
+    if amount.is_zero() {
+        return Err(ContractError::InsufficientFunds {});
+    }
+
+    state.native_reward_index =
+        state.native_reward_index + Decimal::from_ratio(amount, state.total_share);
+    state.native_reward_balance = balance;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "deposit_native_reward"),
+        ("amount", amount.to_string().as_str()),
+    ]))
+}
+
+pub fn claim_rewards(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Claiming)?;
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    let mut token_manager = STAKERS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+
+    accrue_rewards(&state, &mut token_manager);
+    let reward = token_manager.pending_reward;
+    let native_reward = token_manager.pending_native_reward;
+    if reward.is_zero() && native_reward.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    token_manager.pending_reward = Uint128::zero();
+    token_manager.pending_native_reward = Uint128::zero();
+    state.pending_reward = state.pending_reward.checked_sub(reward)?;
+    state.native_reward_balance = state.native_reward_balance.checked_sub(native_reward)?;
+
+    STAKERS.save(deps.storage, &info.sender, &token_manager)?;
+    STATE.save(deps.storage, &state)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !reward.is_zero() {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: config.pollterra_token.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: recipient_human.clone(),
-                amount: Uint128::from(amount),
+                recipient: info.sender.to_string(),
+                amount: reward,
             })?,
             funds: vec![],
-        })])
+        }));
+    }
+    if !native_reward.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: DENOM.to_string(),
+                amount: native_reward,
+            }],
+        }));
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "claim_rewards"),
+        ("recipient", info.sender.as_str()),
+        ("amount", reward.to_string().as_str()),
+        ("native_amount", native_reward.to_string().as_str()),
+    ]))
+}
+
+// stakes the POL rewards of the sender as if they were sent to be staked
+pub fn compound_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Staking)?;
+
+    let mut token_manager = match STAKERS.may_load(deps.storage, &info.sender)? {
+        Some(token_manager) => token_manager,
+        None => return Err(ContractError::NothingStaked {}),
+    };
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+
+    accrue_rewards(&state, &mut token_manager);
+    let amount = token_manager.pending_reward;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    let total_balance = query_token_balance(
+        &deps.querier,
+        config.pollterra_token.clone(),
+        state.contract_addr.clone(),
+    )?
+    .checked_sub(state.unstaked_balance())?;
+
+    let share = shares::stake_share(amount, state.total_share, total_balance)?;
+
+    token_manager.pending_reward = Uint128::zero();
+    token_manager.share += share;
+    state.pending_reward = state.pending_reward.checked_sub(amount)?;
+    state.total_share += share;
+
+    STATE.save(deps.storage, &state)?;
+    STAKERS.save(deps.storage, &info.sender, &token_manager)?;
+    save_staked_share(
+        deps.storage,
+        &info.sender,
+        token_manager.share,
+        state.total_share,
+        env.block.height,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "compound_rewards"),
+        ("sender", info.sender.as_str()),
+        ("share", share.to_string().as_str()),
+        ("amount", amount.to_string().as_str()),
+    ]))
+}
+
+fn send_tokens(
+    asset_token: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+    action: &str,
+) -> Result<Response, ContractError> {
+    Ok(Response::new()
+        .add_message(transfer_msg(asset_token, recipient, amount)?)
         .add_attributes(vec![
             ("action", action),
-            ("recipient", recipient_human.as_str()),
+            ("recipient", recipient.as_str()),
             ("amount", amount.to_string().as_str()),
         ]))
 }
 
-pub fn query_staker(deps: Deps, address: String) -> StdResult<StakerResponse> {
-    let addr_raw = deps.api.addr_canonicalize(&address).unwrap();
-    let config: Config = config_read(deps.storage).load()?;
-    let state: State = state_read(deps.storage).load()?;
-    let mut token_manager = bank_read(deps.storage)
-        .may_load(addr_raw.as_slice())?
+fn transfer_msg(token: &Addr, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }))
+}
+
+pub fn query_staker(deps: Deps, env: Env, address: String) -> StdResult<StakerResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let config: Config = CONFIG.load(deps.storage)?;
+    let state: State = STATE.load(deps.storage)?;
+    let mut token_manager = STAKERS
+        .may_load(deps.storage, &address)?
         .unwrap_or_default();
 
     // filter out not in-progress polls
-    token_manager.locked_balance.retain(|(poll_id, _)| {
-        let poll: Poll = poll_read(deps.storage)
-            .load(&poll_id.to_be_bytes())
-            .unwrap();
-
-        poll.status == PollStatus::InProgress
-    });
+    let mut locked_balance = vec![];
+    for (poll_id, voter_info) in token_manager.locked_balance.drain(..) {
+        let poll: Poll = polls().load(deps.storage, U64Key::new(poll_id))?;
+        if poll.status == PollStatus::InProgress {
+            locked_balance.push((poll_id, voter_info));
+        }
+    }
 
     let total_balance = query_token_balance(
         &deps.querier,
-        deps.api.addr_humanize(&config.pollterra_token)?,
-        deps.api.addr_humanize(&state.contract_addr)?,
+        config.pollterra_token.clone(),
+        state.contract_addr.clone(),
     )?
-    .checked_sub(state.total_deposit)?;
+    .checked_sub(state.unstaked_balance())?;
 
+    let balance = shares::share_amount(token_manager.share, state.total_share, total_balance)?;
     Ok(StakerResponse {
-        balance: if !state.total_share.is_zero() {
-            token_manager
-                .share
-                .multiply_ratio(total_balance, state.total_share)
-        } else {
-            Uint128::zero()
-        },
+        balance,
         share: token_manager.share,
-        locked_balance: token_manager.locked_balance,
+        locked_balance,
+        voting_power: balance
+            .checked_add(asset_voting_power(&config, &token_manager)?)?
+            .checked_add(ve_power(deps.storage, &address, env.block.height)?)?,
+        assets: token_manager
+            .assets
+            .into_iter()
+            .map(|(token, amount)| StakerAssetResponse {
+                weight: config.asset_weight(&token).unwrap_or_default(),
+                token: token.to_string(),
+                amount,
+            })
+            .collect(),
+    })
+}
+
+pub fn query_staker_rewards(deps: Deps, address: String) -> StdResult<StakerRewardsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let state: State = STATE.load(deps.storage)?;
+    let mut token_manager = STAKERS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+
+    accrue_rewards(&state, &mut token_manager);
+
+    Ok(StakerRewardsResponse {
+        pending_reward: token_manager.pending_reward,
+        pending_native_reward: token_manager.pending_native_reward,
+    })
+}
+
+pub fn query_ve_lock(
+    deps: Deps,
+    env: Env,
+    address: String,
+    height: Option<u64>,
+) -> StdResult<VeLockResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let height = height.unwrap_or(env.block.height);
+    let lock = ve_lock_at(deps.storage, &address, height)?.unwrap_or_default();
+
+    Ok(VeLockResponse {
+        amount: lock.amount,
+        end_height: lock.end_height,
+        voting_power: lock.power_at(height),
+    })
+}
+
+pub fn query_total_ve_power(
+    deps: Deps,
+    env: Env,
+    height: Option<u64>,
+) -> StdResult<TotalVePowerResponse> {
+    Ok(TotalVePowerResponse {
+        voting_power: total_ve_power_at(deps.storage, height.unwrap_or(env.block.height))?,
+    })
+}
+
+pub fn query_unbondings(deps: Deps, address: String) -> StdResult<UnbondingsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+
+    Ok(UnbondingsResponse {
+        unbondings: UNBONDINGS
+            .may_load(deps.storage, &address)?
+            .unwrap_or_default(),
     })
 }
@@ -2,14 +2,17 @@ use cosmwasm_std::testing::mock_env;
 use cosmwasm_std::{Env, Timestamp};
 
 pub(crate) const VOTING_TOKEN: &str = "voting_token";
+pub(crate) const LP_TOKEN: &str = "lp_token";
 pub(crate) const TEST_CREATOR: &str = "creator";
 pub(crate) const TEST_VOTER: &str = "voter1";
 pub(crate) const TEST_VOTER_2: &str = "voter2";
 pub(crate) const TEST_VOTER_3: &str = "voter3";
 pub(crate) const DEFAULT_QUORUM: u64 = 30u64;
 pub(crate) const DEFAULT_THRESHOLD: u64 = 50u64;
+pub(crate) const DEFAULT_VETO_THRESHOLD: u64 = 33u64;
 pub(crate) const DEFAULT_VOTING_PERIOD: u64 = 10000u64;
 pub(crate) const DEFAULT_FIX_PERIOD: u64 = 10u64;
+pub(crate) const DEFAULT_UNBONDING_PERIOD: u64 = 0u64;
 pub(crate) const DEFAULT_TIMELOCK_PERIOD: u64 = 10000u64;
 pub(crate) const DEFAULT_EXPIRATION_PERIOD: u64 = 20000u64;
 pub(crate) const DEFAULT_PROPOSAL_DEPOSIT: u128 = 10000000000u128;
@@ -23,3 +26,13 @@ pub fn mock_env_height(height: u64, time: u64) -> Env {
     env.block.time = Timestamp::from_seconds(time);
     env
 }
+
+/**
+ * Mocks the environment of the block before the given one,
+ * tokens staked then carry voting power in polls started in the given block.
+ */
+pub fn mock_env_before(env: &Env) -> Env {
+    let mut env = env.clone();
+    env.block.height -= 1;
+    env
+}
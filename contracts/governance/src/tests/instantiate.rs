@@ -1,29 +1,41 @@
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{coins, from_binary, DepsMut};
-use cosmwasm_std::{Api, CanonicalAddr, Decimal, Uint128};
+use cosmwasm_std::{attr, coins, from_binary, to_vec, Addr, DepsMut, Order, Storage};
+use cosmwasm_std::{Api, Binary, CosmosMsg, Decimal, Uint128, WasmMsg};
+use cosmwasm_storage::{to_length_prefixed, to_length_prefixed_nested};
+use cw0::{Duration, Expiration};
+use cw2::{get_contract_version, set_contract_version};
 
 use testutils::mock_querier::mock_dependencies;
 
-use crate::entrypoints::{execute, instantiate, query};
+use crate::entrypoints::{execute, instantiate, migrate, query};
 use crate::error::ContractError;
 use crate::tests::common::{
     DEFAULT_EXPIRATION_PERIOD, DEFAULT_FIX_PERIOD, DEFAULT_PROPOSAL_DEPOSIT, DEFAULT_QUORUM,
-    DEFAULT_THRESHOLD, DEFAULT_TIMELOCK_PERIOD, DEFAULT_VOTING_PERIOD, TEST_CREATOR, VOTING_TOKEN,
+    DEFAULT_THRESHOLD, DEFAULT_TIMELOCK_PERIOD, DEFAULT_UNBONDING_PERIOD, DEFAULT_VETO_THRESHOLD,
+    DEFAULT_VOTING_PERIOD, TEST_CREATOR, TEST_VOTER, VOTING_TOKEN,
 };
-use crate::tests::poll::mock_register_voting_token;
-use messages::governance::execute_msgs::{ExecuteMsg, InstantiateMsg};
+use crate::tests::poll::{create_poll_msg, mock_register_voting_token};
+use messages::governance::execute_msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg};
 use messages::governance::query_msgs::QueryMsg;
-use messages::governance::state::{config_read, state_read, Config, ConfigResponse, State};
+use messages::governance::state::{
+    Config, ConfigResponse, PollExecuteMsg, PollResponse, PollStatus, PollsResponse, State,
+    VoteOption, VoterHistoryResponse, VoterHistoryResponseItem, VotersResponse, VotersResponseItem,
+    CONFIG, STAKERS, STATE,
+};
+use messages::pause::PauseInfo;
 
 pub(crate) fn instantiate_msg() -> InstantiateMsg {
     InstantiateMsg {
         quorum: Decimal::percent(DEFAULT_QUORUM),
         threshold: Decimal::percent(DEFAULT_THRESHOLD),
-        voting_period: DEFAULT_VOTING_PERIOD,
-        timelock_period: DEFAULT_TIMELOCK_PERIOD,
-        expiration_period: DEFAULT_EXPIRATION_PERIOD,
+        veto_threshold: Decimal::percent(DEFAULT_VETO_THRESHOLD),
+        voting_period: Duration::Height(DEFAULT_VOTING_PERIOD),
+        timelock_period: Duration::Height(DEFAULT_TIMELOCK_PERIOD),
+        expiration_period: Duration::Height(DEFAULT_EXPIRATION_PERIOD),
         proposal_deposit: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
-        snapshot_period: DEFAULT_FIX_PERIOD,
+        snapshot_period: Duration::Height(DEFAULT_FIX_PERIOD),
+        unbonding_period: DEFAULT_UNBONDING_PERIOD,
+        guardian: None,
     }
 }
 
@@ -34,11 +46,14 @@ pub fn mock_instantiate(deps: DepsMut) {
     let msg = InstantiateMsg {
         quorum: Decimal::percent(DEFAULT_QUORUM),
         threshold: Decimal::percent(DEFAULT_THRESHOLD),
-        voting_period: DEFAULT_VOTING_PERIOD,
-        timelock_period: DEFAULT_TIMELOCK_PERIOD,
-        expiration_period: DEFAULT_EXPIRATION_PERIOD,
+        veto_threshold: Decimal::percent(DEFAULT_VETO_THRESHOLD),
+        voting_period: Duration::Height(DEFAULT_VOTING_PERIOD),
+        timelock_period: Duration::Height(DEFAULT_TIMELOCK_PERIOD),
+        expiration_period: Duration::Height(DEFAULT_EXPIRATION_PERIOD),
         proposal_deposit: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
-        snapshot_period: DEFAULT_FIX_PERIOD,
+        snapshot_period: Duration::Height(DEFAULT_FIX_PERIOD),
+        unbonding_period: DEFAULT_UNBONDING_PERIOD,
+        guardian: None,
     };
 
     let info = mock_info(TEST_CREATOR, &[]);
@@ -53,24 +68,43 @@ pub fn mock_instantiate(deps: DepsMut) {
 fn successful_initialization() {
     let mut deps = mock_dependencies(&[]);
 
-    let msg = instantiate_msg();
+    let mut msg = instantiate_msg();
+    msg.guardian = Some(TEST_VOTER.to_string());
     let info = mock_info(TEST_CREATOR, &coins(2, VOTING_TOKEN));
     let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     assert_eq!(0, res.messages.len());
 
-    let config: Config = config_read(deps.as_ref().storage).load().unwrap();
+    // the guardian pauses the contract and vetoes polls
+    let pause_info = PauseInfo::load(deps.as_ref().storage).unwrap();
+    assert_eq!(pause_info.guardian, Some(Addr::unchecked(TEST_VOTER)));
+    assert_eq!(
+        pause_info.governance,
+        Some(Addr::unchecked(MOCK_CONTRACT_ADDR))
+    );
+
+    let config: Config = CONFIG.load(deps.as_ref().storage).unwrap();
     assert_eq!(
         config,
         Config {
-            pollterra_token: CanonicalAddr::from(vec![]),
-            owner: deps.api.addr_canonicalize(TEST_CREATOR).unwrap(),
+            pollterra_token: Addr::unchecked(""),
+            owner: Addr::unchecked(TEST_CREATOR),
             quorum: Decimal::percent(DEFAULT_QUORUM),
             threshold: Decimal::percent(DEFAULT_THRESHOLD),
-            voting_period: DEFAULT_VOTING_PERIOD,
-            timelock_period: DEFAULT_TIMELOCK_PERIOD,
-            expiration_period: DEFAULT_EXPIRATION_PERIOD,
+            veto_threshold: Decimal::percent(DEFAULT_VETO_THRESHOLD),
+            voting_period: Duration::Height(DEFAULT_VOTING_PERIOD),
+            timelock_period: Duration::Height(DEFAULT_TIMELOCK_PERIOD),
+            expiration_period: Duration::Height(DEFAULT_EXPIRATION_PERIOD),
             proposal_deposit: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
-            snapshot_period: DEFAULT_FIX_PERIOD
+            snapshot_period: Duration::Height(DEFAULT_FIX_PERIOD),
+            unbonding_period: DEFAULT_UNBONDING_PERIOD,
+            text_poll_params: None,
+            target_poll_params: vec![],
+            deposit_period: None,
+            stake_assets: vec![],
+            max_lock_weeks: 0,
+            meta_contract: None,
+            community_contract: None,
+            distributor_contract: None,
         }
     );
 
@@ -78,20 +112,23 @@ fn successful_initialization() {
         pollterra_token: VOTING_TOKEN.to_string(),
     };
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    let config: Config = config_read(deps.as_ref().storage).load().unwrap();
-    assert_eq!(
-        config.pollterra_token,
-        deps.api.addr_canonicalize(VOTING_TOKEN).unwrap()
-    );
+    let config: Config = CONFIG.load(deps.as_ref().storage).unwrap();
+    assert_eq!(config.pollterra_token, Addr::unchecked(VOTING_TOKEN));
 
-    let state: State = state_read(deps.as_ref().storage).load().unwrap();
+    let state: State = STATE.load(deps.as_ref().storage).unwrap();
     assert_eq!(
         state,
         State {
-            contract_addr: deps.api.addr_canonicalize(MOCK_CONTRACT_ADDR).unwrap(),
+            contract_addr: Addr::unchecked(MOCK_CONTRACT_ADDR),
             poll_count: 0,
             total_share: Uint128::zero(),
             total_deposit: Uint128::zero(),
+            reward_index: Decimal::zero(),
+            pending_reward: Uint128::zero(),
+            native_reward_index: Decimal::zero(),
+            native_reward_balance: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            total_locked: Uint128::zero(),
         }
     );
 }
@@ -128,8 +165,8 @@ fn invalid_poll_period_fails_initialization() {
     let mut deps = mock_dependencies(&[]);
 
     let mut msg = instantiate_msg();
-    msg.expiration_period = 10000u64;
-    msg.timelock_period = 20000u64;
+    msg.expiration_period = Duration::Height(10000);
+    msg.timelock_period = Duration::Height(20000);
 
     let info = mock_info(TEST_CREATOR, &coins(2, VOTING_TOKEN));
     let res = instantiate(deps.as_mut(), mock_env(), info, msg);
@@ -155,11 +192,21 @@ fn successful_update_config() {
         owner: Some("addr0001".to_string()),
         quorum: None,
         threshold: None,
+        veto_threshold: None,
         voting_period: None,
         timelock_period: None,
         expiration_period: None,
         proposal_deposit: None,
         snapshot_period: None,
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -171,8 +218,14 @@ fn successful_update_config() {
     assert_eq!("addr0001", config.owner.as_str());
     assert_eq!(Decimal::percent(DEFAULT_QUORUM), config.quorum);
     assert_eq!(Decimal::percent(DEFAULT_THRESHOLD), config.threshold);
-    assert_eq!(DEFAULT_VOTING_PERIOD, config.voting_period);
-    assert_eq!(DEFAULT_TIMELOCK_PERIOD, config.timelock_period);
+    assert_eq!(
+        Duration::Height(DEFAULT_VOTING_PERIOD),
+        config.voting_period
+    );
+    assert_eq!(
+        Duration::Height(DEFAULT_TIMELOCK_PERIOD),
+        config.timelock_period
+    );
     assert_eq!(DEFAULT_PROPOSAL_DEPOSIT, config.proposal_deposit.u128());
 
     // update left items with the new owner
@@ -181,11 +234,21 @@ fn successful_update_config() {
         owner: None,
         quorum: Some(Decimal::percent(20)),
         threshold: Some(Decimal::percent(75)),
-        voting_period: Some(20000u64),
-        timelock_period: Some(20000u64),
-        expiration_period: Some(30000u64),
+        veto_threshold: None,
+        voting_period: Some(Duration::Height(20000)),
+        timelock_period: Some(Duration::Height(20000)),
+        expiration_period: Some(Duration::Height(30000)),
         proposal_deposit: Some(Uint128::from(123u128)),
-        snapshot_period: Some(11),
+        snapshot_period: Some(Duration::Height(11)),
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -197,11 +260,11 @@ fn successful_update_config() {
     assert_eq!("addr0001", config.owner.as_str());
     assert_eq!(Decimal::percent(20), config.quorum);
     assert_eq!(Decimal::percent(75), config.threshold);
-    assert_eq!(20000u64, config.voting_period);
-    assert_eq!(20000u64, config.timelock_period);
-    assert_eq!(30000u64, config.expiration_period);
+    assert_eq!(Duration::Height(20000), config.voting_period);
+    assert_eq!(Duration::Height(20000), config.timelock_period);
+    assert_eq!(Duration::Height(30000), config.expiration_period);
     assert_eq!(123u128, config.proposal_deposit.u128());
-    assert_eq!(11u64, config.snapshot_period);
+    assert_eq!(Duration::Height(11), config.snapshot_period);
 }
 
 #[test]
@@ -216,11 +279,21 @@ fn unsuccessful_update_config() {
         owner: None,
         quorum: None,
         threshold: None,
+        veto_threshold: None,
         voting_period: None,
         timelock_period: None,
         expiration_period: None,
         proposal_deposit: None,
         snapshot_period: None,
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
@@ -242,11 +315,21 @@ fn unsuccessful_update_config_invalid_poll_period() {
         owner: None,
         quorum: None,
         threshold: None,
+        veto_threshold: None,
         voting_period: None,
-        timelock_period: Some(20000u64),
-        expiration_period: Some(10000u64),
+        timelock_period: Some(Duration::Height(20000)),
+        expiration_period: Some(Duration::Height(10000)),
         proposal_deposit: None,
         snapshot_period: None,
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
@@ -257,6 +340,80 @@ fn unsuccessful_update_config_invalid_poll_period() {
     }
 }
 
+#[test]
+fn unsuccessful_update_config_period_unit_with_live_polls() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+
+    let update_periods = |period: fn(u64) -> Duration| ExecuteMsg::UpdateConfig {
+        owner: None,
+        quorum: None,
+        threshold: None,
+        veto_threshold: None,
+        voting_period: Some(period(DEFAULT_VOTING_PERIOD)),
+        timelock_period: Some(period(DEFAULT_TIMELOCK_PERIOD)),
+        expiration_period: Some(period(DEFAULT_EXPIRATION_PERIOD)),
+        proposal_deposit: None,
+        snapshot_period: Some(period(DEFAULT_FIX_PERIOD)),
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
+    };
+
+    // the unit is free to change without polls
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        update_periods(Duration::Time),
+    )
+    .unwrap();
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        update_periods(Duration::Height),
+    )
+    .unwrap();
+
+    let msg = create_poll_msg("test".to_string(), "test".to_string(), None, None);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the end of the poll in progress is measured in blocks
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        update_periods(Duration::Time),
+    );
+    match res {
+        Ok(_) => panic!("Must return error"),
+        Err(ContractError::PollPeriodUnitInUse {}) => (),
+        Err(e) => panic!("Unexpected error: {:?}", e),
+    }
+
+    // the periods can still change in the same unit
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        update_periods(Duration::Height),
+    )
+    .unwrap();
+}
+
 #[test]
 fn unsuccessful_update_config_invalid_quorum() {
     let mut deps = mock_dependencies(&[]);
@@ -272,11 +429,21 @@ fn unsuccessful_update_config_invalid_quorum() {
             Uint128::from(1u128),
         )),
         threshold: None,
+        veto_threshold: None,
         voting_period: None,
         timelock_period: None,
         expiration_period: None,
         proposal_deposit: None,
         snapshot_period: None,
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
@@ -302,11 +469,21 @@ fn unsuccessful_update_config_invalid_threshold() {
             Uint128::from(2u128),
             Uint128::from(1u128),
         )),
+        veto_threshold: None,
         voting_period: None,
         timelock_period: None,
         expiration_period: None,
         proposal_deposit: None,
         snapshot_period: None,
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
@@ -316,3 +493,242 @@ fn unsuccessful_update_config_invalid_threshold() {
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
 }
+
+/**
+ * Tests migrating a contract deployed before contract versions and pausing.
+ */
+#[test]
+fn migrate_unversioned_state() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+    deps.storage.remove(b"contract_info");
+    deps.storage.remove(b"pause_info");
+    deps.storage.remove(b"config");
+    deps.storage.remove(b"state");
+
+    // the periods and the polls of 0.1.0 are measured in blocks,
+    // its stores are keyed by canonical addresses
+    let creator = to_vec(&deps.api.addr_canonicalize(TEST_CREATOR).unwrap()).unwrap();
+    let token = to_vec(&deps.api.addr_canonicalize(VOTING_TOKEN).unwrap()).unwrap();
+    let contract = to_vec(&deps.api.addr_canonicalize(MOCK_CONTRACT_ADDR).unwrap()).unwrap();
+    let legacy_state = format!(
+        r#"{{"contract_addr":{},"poll_count":1,"total_share":"10","total_deposit":"{}"}}"#,
+        String::from_utf8(contract).unwrap(),
+        DEFAULT_PROPOSAL_DEPOSIT,
+    );
+    deps.storage
+        .set(&to_length_prefixed(b"state"), legacy_state.as_bytes());
+    let legacy_config = format!(
+        r#"{{"owner":{},"pollterra_token":{},"quorum":"0.3","threshold":"0.5","voting_period":{},"timelock_period":{},"expiration_period":{},"proposal_deposit":"{}","snapshot_period":{}}}"#,
+        String::from_utf8(creator.clone()).unwrap(),
+        String::from_utf8(token.clone()).unwrap(),
+        DEFAULT_VOTING_PERIOD,
+        DEFAULT_TIMELOCK_PERIOD,
+        DEFAULT_EXPIRATION_PERIOD,
+        DEFAULT_PROPOSAL_DEPOSIT,
+        DEFAULT_FIX_PERIOD,
+    );
+    deps.storage
+        .set(&to_length_prefixed(b"config"), legacy_config.as_bytes());
+    let legacy_poll = format!(
+        r#"{{"id":1,"creator":{},"status":"in_progress","yes_votes":"0","no_votes":"0","end_height":22345,"title":"test","description":"test","link":null,"execute_data":[{{"order":1,"contract":{},"msg":"e30="}}],"deposit_amount":"{}","total_balance_at_end_poll":null,"staked_amount":null}}"#,
+        String::from_utf8(creator).unwrap(),
+        String::from_utf8(token).unwrap(),
+        DEFAULT_PROPOSAL_DEPOSIT,
+    );
+    deps.storage.set(
+        &[to_length_prefixed(b"poll").as_slice(), &1u64.to_be_bytes()].concat(),
+        legacy_poll.as_bytes(),
+    );
+    let voter = deps.api.addr_canonicalize(TEST_VOTER).unwrap();
+    let legacy_vote = r#"{"vote":"yes","balance":"10"}"#;
+    deps.storage.set(
+        &[to_length_prefixed(b"bank").as_slice(), voter.as_slice()].concat(),
+        format!(r#"{{"share":"10","locked_balance":[[1,{}]]}}"#, legacy_vote).as_bytes(),
+    );
+    deps.storage.set(
+        &[
+            to_length_prefixed_nested(&[b"poll_voter", &1u64.to_be_bytes()]).as_slice(),
+            voter.as_slice(),
+        ]
+        .concat(),
+        legacy_vote.as_bytes(),
+    );
+    deps.storage.set(
+        &[
+            to_length_prefixed_nested(&[b"poll_indexer", b"InProgress"]).as_slice(),
+            &1u64.to_be_bytes(),
+        ]
+        .concat(),
+        b"true",
+    );
+
+    let msg = MigrateMsg {
+        quorum: Some(Decimal::percent(20)),
+        threshold: None,
+        veto_threshold: None,
+        voting_period: None,
+        timelock_period: None,
+        expiration_period: None,
+        proposal_deposit: None,
+        snapshot_period: None,
+        unbonding_period: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
+    };
+    let res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate"),
+            attr("from_version", "0.1.0"),
+            attr("to_version", env!("CARGO_PKG_VERSION")),
+        ]
+    );
+
+    let version = get_contract_version(&deps.storage).unwrap();
+    assert_eq!("crates.io:governance", version.contract);
+    assert_eq!(env!("CARGO_PKG_VERSION"), version.version);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(Decimal::percent(20), config.quorum);
+    assert_eq!(Decimal::permille(334), config.veto_threshold);
+    assert_eq!(
+        Duration::Height(DEFAULT_VOTING_PERIOD),
+        config.voting_period
+    );
+    assert_eq!(Duration::Height(DEFAULT_FIX_PERIOD), config.snapshot_period);
+    assert_eq!(
+        Some(Addr::unchecked(MOCK_CONTRACT_ADDR)),
+        config.pause.governance
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+    let poll: PollResponse = from_binary(&res).unwrap();
+    assert_eq!(Expiration::AtHeight(22345), poll.end);
+    assert_eq!(mock_env().block.height, poll.start_height);
+    assert_eq!(
+        Some(vec![PollExecuteMsg {
+            order: 1,
+            msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: VOTING_TOKEN.to_string(),
+                msg: Binary::from(b"{}".to_vec()),
+                funds: vec![],
+            }),
+        }]),
+        poll.execute_data
+    );
+
+    // the stores are moved to the new layout and the legacy keys are removed
+    let state = STATE.load(&deps.storage).unwrap();
+    assert_eq!(Addr::unchecked(MOCK_CONTRACT_ADDR), state.contract_addr);
+    assert_eq!(Uint128::new(10), state.total_share);
+    assert_eq!(
+        Addr::unchecked(VOTING_TOKEN),
+        CONFIG.load(&deps.storage).unwrap().pollterra_token
+    );
+    let token_manager = STAKERS
+        .load(&deps.storage, &Addr::unchecked(TEST_VOTER))
+        .unwrap();
+    assert_eq!(Uint128::new(10), token_manager.share);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Voters {
+            poll_id: 1,
+            start_after: None,
+            limit: None,
+            order_by: None,
+        },
+    )
+    .unwrap();
+    let voters: VotersResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        vec![VotersResponseItem {
+            voter: TEST_VOTER.to_string(),
+            vote: VoteOption::Yes,
+            balance: Uint128::new(10),
+            delegated: false,
+        }],
+        voters.voters
+    );
+
+    // the legacy votes are part of the history of the voters
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::VoterHistory {
+            address: TEST_VOTER.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let history: VoterHistoryResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        vec![VoterHistoryResponseItem {
+            poll_id: 1,
+            status: PollStatus::InProgress,
+            vote: VoteOption::Yes,
+            balance: Uint128::new(10),
+            delegated: false,
+        }],
+        history.votes
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Polls {
+            filter: Some(PollStatus::InProgress),
+            creator: Some(TEST_CREATOR.to_string()),
+            start_after: None,
+            limit: None,
+            order_by: None,
+        },
+    )
+    .unwrap();
+    let polls: PollsResponse = from_binary(&res).unwrap();
+    assert_eq!(1, polls.polls.len());
+
+    assert_eq!(
+        0,
+        deps.storage
+            .range(None, None, Order::Ascending)
+            .filter(|(k, _)| {
+                [
+                    to_length_prefixed(b"config"),
+                    to_length_prefixed(b"state"),
+                    to_length_prefixed(b"bank"),
+                    to_length_prefixed(b"poll"),
+                    to_length_prefixed(b"poll_voter"),
+                    to_length_prefixed(b"poll_indexer"),
+                ]
+                .iter()
+                .any(|prefix| k.starts_with(prefix))
+            })
+            .count()
+    );
+
+    // downgrades are refused
+    set_contract_version(&mut deps.storage, "crates.io:governance", "9.0.0").unwrap();
+    let msg = MigrateMsg {
+        quorum: None,
+        threshold: None,
+        veto_threshold: None,
+        voting_period: None,
+        timelock_period: None,
+        expiration_period: None,
+        proposal_deposit: None,
+        snapshot_period: None,
+        unbonding_period: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
+    };
+    assert!(migrate(deps.as_mut(), mock_env(), msg).is_err());
+}
@@ -1,6 +1,7 @@
 mod common;
 pub mod instantiate;
 mod poll;
+mod shares;
 mod staking;
 pub mod tswap_mock;
 mod validators;
@@ -1,26 +1,38 @@
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, coins, from_binary, to_binary, Addr, Api, CosmosMsg, Deps, DepsMut, Response, SubMsg,
-    Uint128, WasmMsg,
+    attr, coins, from_binary, to_binary, Addr, BankMsg, Binary, ContractResult, CosmosMsg, Decimal,
+    Deps, DepsMut, Env, Reply, Response, SubMsg, Uint128, WasmMsg,
 };
+use cw0::{Duration, Expiration};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::U64Key;
 use terraswap::querier::query_token_balance;
 
 use testutils::mock_querier::mock_dependencies;
 
-use crate::entrypoints::{execute, query};
+use crate::entrypoints::{
+    execute, query, reply, EXECUTE_POLL_REPLY_ID_BASE, MAX_DEPOSITORS, MIN_ADDED_DEPOSIT_PERCENT,
+    MIN_INITIAL_DEPOSIT_PERCENT,
+};
 use crate::error::ContractError;
 use crate::tests::common::{
-    mock_env_height, DEFAULT_EXPIRATION_PERIOD, DEFAULT_PROPOSAL_DEPOSIT, DEFAULT_TIMELOCK_PERIOD,
-    DEFAULT_VOTING_PERIOD, TEST_CREATOR, TEST_VOTER, TEST_VOTER_2, TEST_VOTER_3, VOTING_TOKEN,
+    mock_env_height, DEFAULT_EXPIRATION_PERIOD, DEFAULT_FIX_PERIOD, DEFAULT_PROPOSAL_DEPOSIT,
+    DEFAULT_QUORUM, DEFAULT_TIMELOCK_PERIOD, DEFAULT_VOTING_PERIOD, TEST_CREATOR, TEST_VOTER,
+    TEST_VOTER_2, TEST_VOTER_3, VOTING_TOKEN,
 };
 use crate::tests::{common, instantiate};
+use config::config::DepositSplit;
+use messages::distributor::execute_msgs::ExecuteMsg as DistributorExecuteMsg;
 use messages::governance::execute_msgs::ExecuteMsg;
+use messages::governance::param_change::{ParamChange, ParamChangeMsg};
 use messages::governance::query_msgs::QueryMsg;
 use messages::governance::state::{
-    bank_read, poll_voter_read, state_read, Cw20HookMsg, OrderBy, PollExecuteMsg, PollResponse,
-    PollStatus, PollsResponse, StakerResponse, State, VoteOption, VoterInfo, VotersResponse,
+    ConfigResponse, Cw20HookMsg, OrderBy, PollExecuteMsg, PollParams, PollResponse, PollStatus,
+    PollsResponse, StakerResponse, State, TargetPollParamsMsg, VoteOption, VoterInfo,
+    VotersResponse, POLL_VOTERS, STAKERS, STATE,
 };
+use messages::meta_contract::execute_msgs::ExecuteMsg as MetaContractExecuteMsg;
+use messages::meta_contract::state::Role;
 
 pub fn mock_register_voting_token(deps: DepsMut) {
     let info = mock_info(TEST_CREATOR, &[]);
@@ -31,6 +43,71 @@ pub fn mock_register_voting_token(deps: DepsMut) {
         .expect("contract successfully handles RegisterContracts");
 }
 
+const META_CONTRACT: &str = "meta_contract";
+const COMMUNITY_CONTRACT: &str = "community";
+const DISTRIBUTOR_CONTRACT: &str = "distributor";
+
+pub fn mock_register_known_contracts(deps: DepsMut) {
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        quorum: None,
+        threshold: None,
+        veto_threshold: None,
+        voting_period: None,
+        timelock_period: None,
+        expiration_period: None,
+        proposal_deposit: None,
+        snapshot_period: None,
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: Some(META_CONTRACT.to_string()),
+        community_contract: Some(COMMUNITY_CONTRACT.to_string()),
+        distributor_contract: Some(DISTRIBUTOR_CONTRACT.to_string()),
+    };
+    let _res =
+        execute(deps, mock_env(), info, msg).expect("contract successfully handles UpdateConfig");
+}
+
+// asserts the poll messages are sent through the contract itself and runs them
+pub fn mock_execute_poll_msgs(
+    deps: DepsMut,
+    env: Env,
+    execute_res: &Response,
+    poll_id: u64,
+) -> Response {
+    assert_eq!(
+        execute_res.messages,
+        vec![SubMsg::reply_on_error(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::ExecutePollMsgs { poll_id }).unwrap(),
+                funds: vec![],
+            }),
+            EXECUTE_POLL_REPLY_ID_BASE + poll_id,
+        )]
+    );
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::ExecutePollMsgs { poll_id };
+    execute(deps, env, info, msg).unwrap()
+}
+
+pub fn token_execute_msg(order: u64, msg: Binary) -> PollExecuteMsg {
+    PollExecuteMsg {
+        order,
+        msg: CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: VOTING_TOKEN.to_string(),
+            msg,
+            funds: vec![],
+        }),
+    }
+}
+
 pub fn create_poll_msg(
     title: String,
     description: String,
@@ -45,6 +122,7 @@ pub fn create_poll_msg(
             description,
             link,
             execute_msgs: execute_msg,
+            param_changes: None,
         })
         .unwrap(),
     })
@@ -71,14 +149,20 @@ pub fn assert_create_poll_result(
     );
 
     //confirm poll count
-    let state: State = state_read(deps.storage).load().unwrap();
+    let state: State = STATE.load(deps.storage).unwrap();
     assert_eq!(
         state,
         State {
-            contract_addr: deps.api.addr_canonicalize(MOCK_CONTRACT_ADDR).unwrap(),
+            contract_addr: Addr::unchecked(MOCK_CONTRACT_ADDR),
             poll_count: 1,
             total_share: Uint128::zero(),
             total_deposit: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
+            reward_index: Decimal::zero(),
+            pending_reward: Uint128::zero(),
+            native_reward_index: Decimal::zero(),
+            native_reward_balance: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            total_locked: Uint128::zero(),
         }
     );
 }
@@ -99,14 +183,20 @@ pub fn assert_stake_tokens_result(
         &attr("share", new_share.to_string())
     );
 
-    let state: State = state_read(deps.storage).load().unwrap();
+    let state: State = STATE.load(deps.storage).unwrap();
     assert_eq!(
         state,
         State {
-            contract_addr: deps.api.addr_canonicalize(MOCK_CONTRACT_ADDR).unwrap(),
+            contract_addr: Addr::unchecked(MOCK_CONTRACT_ADDR),
             poll_count,
             total_share: Uint128::from(total_share),
             total_deposit: Uint128::from(total_deposit),
+            reward_index: Decimal::zero(),
+            pending_reward: Uint128::zero(),
+            native_reward_index: Decimal::zero(),
+            native_reward_balance: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            total_locked: Uint128::zero(),
         }
     );
 }
@@ -161,21 +251,9 @@ fn add_several_execute_msgs() {
 
     // push two execute msgs to the list
     let execute_msgs: Vec<PollExecuteMsg> = vec![
-        PollExecuteMsg {
-            order: 1u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz,
-        },
-        PollExecuteMsg {
-            order: 3u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz3,
-        },
-        PollExecuteMsg {
-            order: 2u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz2,
-        },
+        token_execute_msg(1u64, exec_msg_bz),
+        token_execute_msg(3u64, exec_msg_bz3),
+        token_execute_msg(2u64, exec_msg_bz2),
     ];
 
     let msg = create_poll_msg(
@@ -222,6 +300,61 @@ fn successful_create_poll() {
     );
 }
 
+#[test]
+fn query_polls_by_creator() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+    let info = mock_info(VOTING_TOKEN, &[]);
+
+    let msg = create_poll_msg("test".to_string(), "test".to_string(), None, None);
+    execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
+        msg: to_binary(&Cw20HookMsg::CreatePoll {
+            title: "test".to_string(),
+            description: "test".to_string(),
+            link: None,
+            execute_msgs: None,
+            param_changes: None,
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let query_polls = |creator: &str, filter: Option<PollStatus>, start_after: Option<u64>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Polls {
+                filter,
+                creator: Some(creator.to_string()),
+                start_after,
+                limit: None,
+                order_by: Some(OrderBy::Asc),
+            },
+        )
+        .unwrap();
+        let response: PollsResponse = from_binary(&res).unwrap();
+        response
+            .polls
+            .iter()
+            .map(|poll| poll.id)
+            .collect::<Vec<u64>>()
+    };
+
+    assert_eq!(vec![1, 2], query_polls(TEST_CREATOR, None, None));
+    assert_eq!(vec![2], query_polls(TEST_CREATOR, None, Some(1)));
+    assert_eq!(
+        vec![3],
+        query_polls(TEST_VOTER, Some(PollStatus::InProgress), None)
+    );
+    assert!(query_polls(TEST_VOTER, Some(PollStatus::Passed), None).is_empty());
+    assert!(query_polls(TEST_VOTER_2, None, None).is_empty());
+}
+
 #[test]
 fn create_poll_no_quorum() {
     let mut deps = mock_dependencies(&[]);
@@ -377,6 +510,7 @@ fn fails_create_poll_invalid_deposit() {
             description: "TESTTEST".to_string(),
             link: None,
             execute_msgs: None,
+            param_changes: None,
         })
         .unwrap(),
     });
@@ -405,11 +539,7 @@ fn expire_poll() {
         amount: Uint128::new(123),
     })
     .unwrap();
-    let execute_msgs: Vec<PollExecuteMsg> = vec![PollExecuteMsg {
-        order: 1u64,
-        contract: VOTING_TOKEN.to_string(),
-        msg: exec_msg_bz,
-    }];
+    let execute_msgs: Vec<PollExecuteMsg> = vec![token_execute_msg(1u64, exec_msg_bz)];
     let msg = create_poll_msg(
         "test".to_string(),
         "test".to_string(),
@@ -448,7 +578,13 @@ fn expire_poll() {
     });
 
     let info = mock_info(VOTING_TOKEN, &[]);
-    let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let execute_res = execute(
+        deps.as_mut(),
+        common::mock_env_before(&creator_env),
+        info,
+        msg,
+    )
+    .unwrap();
     assert_stake_tokens_result(
         stake_amount,
         DEFAULT_PROPOSAL_DEPOSIT,
@@ -549,6 +685,7 @@ fn expire_poll() {
         mock_env(),
         QueryMsg::Polls {
             filter: Some(PollStatus::Expired),
+            creator: None,
             start_after: None,
             limit: None,
             order_by: Some(OrderBy::Desc),
@@ -583,7 +720,7 @@ fn fails_end_poll_before_end_height() {
 
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
     let value: PollResponse = from_binary(&res).unwrap();
-    assert_eq!(DEFAULT_VOTING_PERIOD, value.end_height);
+    assert_eq!(Expiration::AtHeight(DEFAULT_VOTING_PERIOD), value.end);
 
     let msg = ExecuteMsg::EndPoll { poll_id: 1 };
     let env = common::mock_env_height(0, 10000);
@@ -626,21 +763,9 @@ fn successful_end_poll() {
 
     //add three messages with different order
     let execute_msgs: Vec<PollExecuteMsg> = vec![
-        PollExecuteMsg {
-            order: 3u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz3.clone(),
-        },
-        PollExecuteMsg {
-            order: 2u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz2.clone(),
-        },
-        PollExecuteMsg {
-            order: 1u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz.clone(),
-        },
+        token_execute_msg(3u64, exec_msg_bz3.clone()),
+        token_execute_msg(2u64, exec_msg_bz2.clone()),
+        token_execute_msg(1u64, exec_msg_bz.clone()),
     ];
 
     let msg = create_poll_msg(
@@ -681,7 +806,13 @@ fn successful_end_poll() {
     });
 
     let info = mock_info(VOTING_TOKEN, &[]);
-    let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let execute_res = execute(
+        deps.as_mut(),
+        common::mock_env_before(&creator_env),
+        info,
+        msg,
+    )
+    .unwrap();
     assert_stake_tokens_result(
         stake_amount,
         DEFAULT_PROPOSAL_DEPOSIT,
@@ -784,9 +915,10 @@ fn successful_end_poll() {
 
     creator_env.block.height += DEFAULT_TIMELOCK_PERIOD;
     let msg = ExecuteMsg::ExecutePoll { poll_id: 1 };
-    let execute_res = execute(deps.as_mut(), creator_env, creator_info, msg).unwrap();
+    let execute_res = execute(deps.as_mut(), creator_env.clone(), creator_info, msg).unwrap();
+    let msgs_res = mock_execute_poll_msgs(deps.as_mut(), creator_env, &execute_res, 1);
     assert_eq!(
-        execute_res.messages,
+        msgs_res.messages,
         vec![
             SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: VOTING_TOKEN.to_string(),
@@ -816,6 +948,7 @@ fn successful_end_poll() {
         mock_env(),
         QueryMsg::Polls {
             filter: Some(PollStatus::Passed),
+            creator: None,
             start_after: None,
             limit: None,
             order_by: None,
@@ -830,6 +963,7 @@ fn successful_end_poll() {
         mock_env(),
         QueryMsg::Polls {
             filter: Some(PollStatus::InProgress),
+            creator: None,
             start_after: None,
             limit: None,
             order_by: None,
@@ -844,6 +978,7 @@ fn successful_end_poll() {
         mock_env(),
         QueryMsg::Polls {
             filter: Some(PollStatus::Executed),
+            creator: None,
             start_after: None,
             limit: None,
             order_by: Some(OrderBy::Desc),
@@ -884,25 +1019,26 @@ fn successful_end_poll() {
             balance: Uint128::from(stake_amount),
             share: Uint128::from(stake_amount),
             locked_balance: vec![],
+            assets: vec![],
+            voting_power: Uint128::from(stake_amount),
         }
     );
 
     // But the data is still in the store
-    let voter_addr_raw = deps.api.addr_canonicalize(TEST_VOTER).unwrap();
-    let voter = poll_voter_read(&deps.storage, 1u64)
-        .load(voter_addr_raw.as_slice())
+    let voter_addr = Addr::unchecked(TEST_VOTER);
+    let voter = POLL_VOTERS
+        .load(&deps.storage, (U64Key::new(1), &voter_addr))
         .unwrap();
     assert_eq!(
         voter,
         VoterInfo {
             vote: VoteOption::Yes,
             balance: Uint128::from(stake_amount),
+            delegated: false,
         }
     );
 
-    let token_manager = bank_read(&deps.storage)
-        .load(voter_addr_raw.as_slice())
-        .unwrap();
+    let token_manager = STAKERS.load(&deps.storage, &voter_addr).unwrap();
     assert_eq!(
         token_manager.locked_balance,
         vec![(
@@ -910,6 +1046,7 @@ fn successful_end_poll() {
             VoterInfo {
                 vote: VoteOption::Yes,
                 balance: Uint128::from(stake_amount),
+                delegated: false,
             }
         )]
     );
@@ -923,14 +1060,13 @@ fn end_poll_zero_quorum() {
     let mut creator_env = common::mock_env_height(1000, 10000);
     let mut creator_info = mock_info(VOTING_TOKEN, &[]);
 
-    let execute_msgs: Vec<PollExecuteMsg> = vec![PollExecuteMsg {
-        order: 1u64,
-        contract: VOTING_TOKEN.to_string(),
-        msg: to_binary(&Cw20ExecuteMsg::Burn {
+    let execute_msgs: Vec<PollExecuteMsg> = vec![token_execute_msg(
+        1u64,
+        to_binary(&Cw20ExecuteMsg::Burn {
             amount: Uint128::new(123),
         })
         .unwrap(),
-    }];
+    )];
 
     let msg = create_poll_msg(
         "test".to_string(),
@@ -995,6 +1131,7 @@ fn end_poll_zero_quorum() {
         mock_env(),
         QueryMsg::Polls {
             filter: Some(PollStatus::Rejected),
+            creator: None,
             start_after: None,
             limit: None,
             order_by: Some(OrderBy::Desc),
@@ -1009,6 +1146,7 @@ fn end_poll_zero_quorum() {
         mock_env(),
         QueryMsg::Polls {
             filter: Some(PollStatus::InProgress),
+            creator: None,
             start_after: None,
             limit: None,
             order_by: None,
@@ -1023,6 +1161,7 @@ fn end_poll_zero_quorum() {
         mock_env(),
         QueryMsg::Polls {
             filter: Some(PollStatus::Passed),
+            creator: None,
             start_after: None,
             limit: None,
             order_by: None,
@@ -1075,7 +1214,13 @@ fn end_poll_quorum_rejected() {
     });
 
     let info = mock_info(VOTING_TOKEN, &[]);
-    let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let execute_res = execute(
+        deps.as_mut(),
+        common::mock_env_before(&creator_env),
+        info,
+        msg,
+    )
+    .unwrap();
     assert_stake_tokens_result(
         stake_amount,
         DEFAULT_PROPOSAL_DEPOSIT,
@@ -1208,7 +1353,13 @@ fn end_poll_nay_rejected() {
     });
 
     let info = mock_info(VOTING_TOKEN, &[]);
-    let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let execute_res = execute(
+        deps.as_mut(),
+        common::mock_env_before(&creator_env),
+        info,
+        msg,
+    )
+    .unwrap();
     assert_stake_tokens_result(
         voter1_stake,
         DEFAULT_PROPOSAL_DEPOSIT,
@@ -1233,7 +1384,13 @@ fn end_poll_nay_rejected() {
     });
 
     let info = mock_info(VOTING_TOKEN, &[]);
-    let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let execute_res = execute(
+        deps.as_mut(),
+        common::mock_env_before(&creator_env),
+        info,
+        msg,
+    )
+    .unwrap();
     assert_stake_tokens_result(
         voter1_stake + voter2_stake,
         DEFAULT_PROPOSAL_DEPOSIT,
@@ -1268,186 +1425,1639 @@ fn end_poll_nay_rejected() {
     );
 }
 
-#[test]
-fn execute_poll_with_order() {
-    const POLL_START_HEIGHT: u64 = 1000;
-    const POLL_ID: u64 = 1;
-    let stake_amount = 1000;
-
-    let mut deps = mock_dependencies(&coins(1000, VOTING_TOKEN));
+// stakes and votes for each voter on a new poll, then ends it
+fn end_poll_with_votes(votes: Vec<(&str, u128, VoteOption)>) -> Response {
+    let mut deps = mock_dependencies(&[]);
     instantiate::mock_instantiate(deps.as_mut());
     mock_register_voting_token(deps.as_mut());
-    let mut creator_env = common::mock_env_height(POLL_START_HEIGHT, 10000);
-    let mut creator_info = mock_info(VOTING_TOKEN, &coins(2, VOTING_TOKEN));
-
-    let exec_msg_bz = to_binary(&Cw20ExecuteMsg::Burn {
-        amount: Uint128::new(10),
-    })
-    .unwrap();
-
-    let exec_msg_bz2 = to_binary(&Cw20ExecuteMsg::Burn {
-        amount: Uint128::new(20),
-    })
-    .unwrap();
-
-    let exec_msg_bz3 = to_binary(&Cw20ExecuteMsg::Burn {
-        amount: Uint128::new(30),
-    })
-    .unwrap();
-    let exec_msg_bz4 = to_binary(&Cw20ExecuteMsg::Burn {
-        amount: Uint128::new(40),
-    })
-    .unwrap();
-    let exec_msg_bz5 = to_binary(&Cw20ExecuteMsg::Burn {
-        amount: Uint128::new(50),
-    })
-    .unwrap();
-
-    //add three messages with different order
-    let execute_msgs: Vec<PollExecuteMsg> = vec![
-        PollExecuteMsg {
-            order: 3u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz3.clone(),
-        },
-        PollExecuteMsg {
-            order: 4u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz4.clone(),
-        },
-        PollExecuteMsg {
-            order: 2u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz2.clone(),
-        },
-        PollExecuteMsg {
-            order: 5u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz5.clone(),
-        },
-        PollExecuteMsg {
-            order: 1u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz.clone(),
-        },
-    ];
-
-    let msg = create_poll_msg(
-        "test".to_string(),
-        "test".to_string(),
-        None,
-        Some(execute_msgs),
-    );
-
-    let execute_res = execute(
-        deps.as_mut(),
-        creator_env.clone(),
-        creator_info.clone(),
-        msg,
-    )
-    .unwrap();
-
-    assert_create_poll_result(
-        1,
-        creator_env.block.height + DEFAULT_VOTING_PERIOD,
-        TEST_CREATOR,
-        execute_res,
-        deps.as_ref(),
-    );
-
-    deps.querier.with_token_balances(&[(
-        &VOTING_TOKEN.to_string(),
-        &[(
-            &MOCK_CONTRACT_ADDR.to_string(),
-            &Uint128::from((stake_amount + DEFAULT_PROPOSAL_DEPOSIT) as u128),
-        )],
-    )]);
 
-    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-        sender: TEST_VOTER.to_string(),
-        amount: Uint128::from(stake_amount as u128),
-        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
-    });
+    let msg = create_poll_msg("test".to_string(), "test".to_string(), None, None);
+    let info = mock_info(VOTING_TOKEN, &coins(2, VOTING_TOKEN));
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let mut total_stake = 0u128;
+    for (voter, stake, _) in votes.iter() {
+        total_stake += stake;
+        deps.querier.with_token_balances(&[(
+            &VOTING_TOKEN.to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(total_stake + DEFAULT_PROPOSAL_DEPOSIT),
+            )],
+        )]);
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: voter.to_string(),
+            amount: Uint128::from(*stake),
+            msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+        });
+        let info = mock_info(VOTING_TOKEN, &[]);
+        let _res = execute(
+            deps.as_mut(),
+            common::mock_env_before(&mock_env()),
+            info,
+            msg,
+        )
+        .unwrap();
+    }
 
-    let info = mock_info(VOTING_TOKEN, &[]);
-    let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    assert_stake_tokens_result(
-        stake_amount,
-        DEFAULT_PROPOSAL_DEPOSIT,
-        stake_amount,
-        1,
-        execute_res,
-        deps.as_ref(),
-    );
+    for (voter, stake, vote) in votes {
+        let info = mock_info(voter, &[]);
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote,
+            amount: Uint128::from(stake),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
 
-    let msg = ExecuteMsg::CastVote {
-        poll_id: 1,
-        vote: VoteOption::Yes,
-        amount: Uint128::from(stake_amount),
-    };
-    let env = common::mock_env_height(POLL_START_HEIGHT, 10000);
-    let info = mock_info(TEST_VOTER, &[]);
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let mut env = mock_env();
+    env.block.height += DEFAULT_VOTING_PERIOD;
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    execute(deps.as_mut(), env, info, msg).unwrap()
+}
 
+#[test]
+fn end_poll_abstain_excluded_from_threshold() {
+    // abstain votes reach the quorum, the only other vote is in favor
+    let execute_res = end_poll_with_votes(vec![
+        (TEST_VOTER, 100, VoteOption::Yes),
+        (TEST_VOTER_2, 1000, VoteOption::Abstain),
+    ]);
     assert_eq!(
         execute_res.attributes,
         vec![
-            attr("action", "cast_vote"),
-            attr("poll_id", POLL_ID.to_string().as_str()),
-            attr("amount", "1000"),
-            attr("voter", TEST_VOTER),
-            attr("vote_option", "yes"),
+            attr("action", "end_poll"),
+            attr("poll_id", "1"),
+            attr("rejected_reason", "Poll Passed"),
+            attr("passed", "true"),
         ]
     );
+}
 
-    creator_info.sender = Addr::unchecked(TEST_CREATOR);
-    creator_env.block.height += DEFAULT_VOTING_PERIOD;
-
-    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
-    let execute_res = execute(
-        deps.as_mut(),
-        creator_env.clone(),
-        creator_info.clone(),
-        msg,
-    )
-    .unwrap();
-
+#[test]
+fn end_poll_vetoed_burns_deposit() {
+    let execute_res = end_poll_with_votes(vec![
+        (TEST_VOTER, 200, VoteOption::Yes),
+        (TEST_VOTER_2, 100, VoteOption::NoWithVeto),
+    ]);
     assert_eq!(
         execute_res.attributes,
         vec![
             attr("action", "end_poll"),
             attr("poll_id", "1"),
-            attr("rejected_reason", "Poll Passed"),
-            attr("passed", "true"),
+            attr("rejected_reason", "Vetoed"),
+            attr("passed", "false"),
         ]
     );
     assert_eq!(
         execute_res.messages,
         vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: VOTING_TOKEN.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: TEST_CREATOR.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn {
                 amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
             })
             .unwrap(),
             funds: vec![],
         }))]
     );
+}
+
+#[test]
+fn time_based_poll_periods() {
+    let mut deps = mock_dependencies(&[]);
+    let mut msg = instantiate::instantiate_msg();
+    msg.voting_period = Duration::Time(DEFAULT_VOTING_PERIOD);
+    msg.timelock_period = Duration::Time(DEFAULT_TIMELOCK_PERIOD);
+    msg.expiration_period = Duration::Time(DEFAULT_EXPIRATION_PERIOD);
+    msg.snapshot_period = Duration::Time(DEFAULT_FIX_PERIOD);
+    crate::entrypoints::instantiate(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg)
+        .unwrap();
+    mock_register_voting_token(deps.as_mut());
+
+    let poll_env = mock_env();
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), common::mock_env_before(&poll_env), info, msg).unwrap();
 
-    // End poll will withdraw deposit balance
     deps.querier.with_token_balances(&[(
         &VOTING_TOKEN.to_string(),
         &[(
             &MOCK_CONTRACT_ADDR.to_string(),
-            &Uint128::from(stake_amount as u128),
+            &Uint128::from(100u128 + DEFAULT_PROPOSAL_DEPOSIT),
         )],
     )]);
-
+    let execute_msgs = vec![token_execute_msg(
+        1u64,
+        to_binary(&Cw20ExecuteMsg::Burn {
+            amount: Uint128::new(123),
+        })
+        .unwrap(),
+    )];
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        Some(execute_msgs),
+    );
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let execute_res = execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+    let end_time = poll_env.block.time.plus_seconds(DEFAULT_VOTING_PERIOD);
+    assert_eq!(
+        execute_res.attributes.last(),
+        Some(&attr("end_time", end_time.seconds().to_string()))
+    );
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(100u128),
+    };
+    let info = mock_info(TEST_VOTER, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    // the blocks do not matter, the poll ends at its time
+    let mut env = poll_env;
+    env.block.height += 2 * DEFAULT_VOTING_PERIOD;
+    env.block.time = end_time.minus_seconds(1);
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    let execute_err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(ContractError::PollVotingPeriod {}, execute_err);
+
+    env.block.time = end_time;
+    let execute_res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(execute_res.attributes.last(), Some(&attr("passed", "true")));
+
+    let msg = ExecuteMsg::ExecutePoll { poll_id: 1 };
+    env.block.time = end_time.plus_seconds(DEFAULT_TIMELOCK_PERIOD - 1);
+    let execute_err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(ContractError::TimelockNotExpired {}, execute_err);
+
+    env.block.time = end_time.plus_seconds(DEFAULT_TIMELOCK_PERIOD);
+    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(execute_res.messages.len(), 1);
+}
+
+#[test]
+fn execute_poll_with_native_msgs() {
+    let mut deps = mock_dependencies(&coins(100, "uusd"));
+    instantiate::mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+
+    let poll_env = mock_env();
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), common::mock_env_before(&poll_env), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(100u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+
+    // unsupported messages are refused when the poll is created
+    let instantiate_msg = CosmosMsg::Wasm(WasmMsg::Instantiate {
+        admin: None,
+        code_id: 1,
+        msg: Binary::default(),
+        funds: vec![],
+        label: "poll".to_string(),
+    });
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        Some(vec![PollExecuteMsg {
+            order: 1u64,
+            msg: instantiate_msg,
+        }]),
+    );
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let execute_err = execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap_err();
+    assert_eq!(ContractError::UnsupportedPollMsg {}, execute_err);
+
+    let send_msg = CosmosMsg::Bank(BankMsg::Send {
+        to_address: TEST_VOTER_2.to_string(),
+        amount: coins(100, "uusd"),
+    });
+    let migrate_msg = CosmosMsg::Wasm(WasmMsg::Migrate {
+        contract_addr: VOTING_TOKEN.to_string(),
+        new_code_id: 2,
+        msg: Binary::default(),
+    });
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        Some(vec![
+            PollExecuteMsg {
+                order: 2u64,
+                msg: migrate_msg.clone(),
+            },
+            PollExecuteMsg {
+                order: 1u64,
+                msg: send_msg.clone(),
+            },
+        ]),
+    );
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(100u128),
+    };
+    let info = mock_info(TEST_VOTER, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    let mut env = poll_env;
+    env.block.height += DEFAULT_VOTING_PERIOD;
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    env.block.height += DEFAULT_TIMELOCK_PERIOD;
+    let msg = ExecuteMsg::ExecutePoll { poll_id: 1 };
+    let execute_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // the uusd of unclaimed rewards cannot be sent
+    let mut state = STATE.load(&deps.storage).unwrap();
+    state.native_reward_balance = Uint128::from(1u128);
+    STATE.save(&mut deps.storage, &state).unwrap();
+    let info_contract = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let execute_err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info_contract,
+        ExecuteMsg::ExecutePollMsgs { poll_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::NativeRewardsReserved {}, execute_err);
+
+    state.native_reward_balance = Uint128::zero();
+    STATE.save(&mut deps.storage, &state).unwrap();
+    let msgs_res = mock_execute_poll_msgs(deps.as_mut(), env, &execute_res, 1);
+    assert_eq!(
+        msgs_res.messages,
+        vec![SubMsg::new(send_msg), SubMsg::new(migrate_msg)]
+    );
+}
+
+#[test]
+fn execute_poll_with_wasm_native_funds() {
+    let mut deps = mock_dependencies(&coins(100, "uusd"));
+    instantiate::mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+
+    let poll_env = mock_env();
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), common::mock_env_before(&poll_env), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(100u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+
+    let wasm_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: TEST_VOTER_2.to_string(),
+        msg: Binary::default(),
+        funds: coins(100, "uusd"),
+    });
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        Some(vec![PollExecuteMsg {
+            order: 1u64,
+            msg: wasm_msg.clone(),
+        }]),
+    );
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(100u128),
+    };
+    let info = mock_info(TEST_VOTER, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    let mut env = poll_env;
+    env.block.height += DEFAULT_VOTING_PERIOD;
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    env.block.height += DEFAULT_TIMELOCK_PERIOD;
+    let msg = ExecuteMsg::ExecutePoll { poll_id: 1 };
+    let execute_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // the funds of wasm messages cannot take the uusd of unclaimed rewards either
+    let mut state = STATE.load(&deps.storage).unwrap();
+    state.native_reward_balance = Uint128::from(1u128);
+    STATE.save(&mut deps.storage, &state).unwrap();
+    let info_contract = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let execute_err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info_contract,
+        ExecuteMsg::ExecutePollMsgs { poll_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::NativeRewardsReserved {}, execute_err);
+
+    state.native_reward_balance = Uint128::zero();
+    STATE.save(&mut deps.storage, &state).unwrap();
+    let msgs_res = mock_execute_poll_msgs(deps.as_mut(), env, &execute_res, 1);
+    assert_eq!(msgs_res.messages, vec![SubMsg::new(wasm_msg)]);
+}
+
+#[test]
+fn failed_poll_execution_can_be_retried() {
+    let mut deps = mock_dependencies(&coins(100, "uusd"));
+    instantiate::mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+
+    let poll_env = mock_env();
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), common::mock_env_before(&poll_env), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(100u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    let send_msg = CosmosMsg::Bank(BankMsg::Send {
+        to_address: TEST_VOTER_2.to_string(),
+        amount: coins(100, "uusd"),
+    });
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        Some(vec![PollExecuteMsg {
+            order: 1u64,
+            msg: send_msg.clone(),
+        }]),
+    );
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(100u128),
+    };
+    let info = mock_info(TEST_VOTER, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    let mut env = poll_env;
+    env.block.height += DEFAULT_VOTING_PERIOD;
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    env.block.height += DEFAULT_TIMELOCK_PERIOD;
+    let msg = ExecuteMsg::ExecutePoll { poll_id: 1 };
+    let execute_res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+    // only the contract itself can send the poll messages
+    let execute_err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ExecutePollMsgs { poll_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, execute_err);
+
+    // the messages fail, the poll is recorded as failed with the error
+    let failure = Reply {
+        id: EXECUTE_POLL_REPLY_ID_BASE + 1,
+        result: ContractResult::Err("insufficient funds".to_string()),
+    };
+    let reply_res = reply(deps.as_mut(), env.clone(), failure).unwrap();
+    assert_eq!(
+        reply_res.attributes,
+        vec![
+            attr("action", "execute_poll_failed"),
+            attr("poll_id", "1"),
+            attr("error", "insufficient funds"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+    let value: PollResponse = from_binary(&res).unwrap();
+    assert_eq!(PollStatus::Failed, value.status);
+    assert_eq!(Some("insufficient funds".to_string()), value.error);
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Polls {
+            filter: Some(PollStatus::Failed),
+            creator: None,
+            start_after: None,
+            limit: None,
+            order_by: None,
+        },
+    )
+    .unwrap();
+    let response: PollsResponse = from_binary(&res).unwrap();
+    assert_eq!(response.polls.len(), 1);
+
+    // the messages of a failed poll cannot be sent until it is executed again
+    let info_contract = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let execute_err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info_contract,
+        ExecuteMsg::ExecutePollMsgs { poll_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::PollNotPassed {}, execute_err);
+
+    // a re-attempt clears the error and sends the messages again
+    let retry_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(retry_res.messages, execute_res.messages);
+    let msgs_res = mock_execute_poll_msgs(deps.as_mut(), env.clone(), &retry_res, 1);
+    assert_eq!(msgs_res.messages, vec![SubMsg::new(send_msg)]);
+
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+    let value: PollResponse = from_binary(&res).unwrap();
+    assert_eq!(PollStatus::Executed, value.status);
+    assert_eq!(None, value.error);
+
+    // a poll that keeps failing expires as a passed one does
+    let failure = Reply {
+        id: EXECUTE_POLL_REPLY_ID_BASE + 1,
+        result: ContractResult::Err("insufficient funds".to_string()),
+    };
+    reply(deps.as_mut(), env.clone(), failure).unwrap();
+
+    // a failed poll cannot be re-attempted once expired
+    env.block.height += DEFAULT_EXPIRATION_PERIOD;
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::ExecutePoll { poll_id: 1 };
+    let execute_err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(ContractError::PollExpired {}, execute_err);
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::ExpirePoll { poll_id: 1 };
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+    let value: PollResponse = from_binary(&res).unwrap();
+    assert_eq!(PollStatus::Expired, value.status);
+
+    // replies of unknown ids are refused
+    let failure = Reply {
+        id: 1,
+        result: ContractResult::Err("insufficient funds".to_string()),
+    };
+    let reply_err = reply(deps.as_mut(), env, failure).unwrap_err();
+    assert_eq!(ContractError::InvalidReplyId {}, reply_err);
+}
+
+#[test]
+fn cancel_poll() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+
+    let poll_env = mock_env();
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), common::mock_env_before(&poll_env), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(100u128 + 3 * DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    for _ in 0..3 {
+        let msg = create_poll_msg("test".to_string(), "test".to_string(), None, None);
+        let info = mock_info(VOTING_TOKEN, &[]);
+        execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+    }
+
+    // only the creator can cancel
+    let msg = ExecuteMsg::CancelPoll { poll_id: 1 };
+    let info = mock_info(TEST_VOTER, &[]);
+    let execute_err = execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, execute_err);
+
+    let msg = ExecuteMsg::CancelPoll { poll_id: 1 };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let execute_res = execute(deps.as_mut(), poll_env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        execute_res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: VOTING_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: TEST_CREATOR.to_string(),
+                amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    assert_eq!(
+        execute_res.attributes,
+        vec![attr("action", "cancel_poll"), attr("poll_id", "1")]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        poll_env.clone(),
+        QueryMsg::Poll { poll_id: 1 },
+    )
+    .unwrap();
+    let value: PollResponse = from_binary(&res).unwrap();
+    assert_eq!(PollStatus::Cancelled, value.status);
+
+    let state: State = STATE.load(&deps.storage).unwrap();
+    assert_eq!(
+        Uint128::from(2 * DEFAULT_PROPOSAL_DEPOSIT),
+        state.total_deposit
+    );
+
+    // a cancelled poll cannot be cancelled again
+    let execute_err = execute(deps.as_mut(), poll_env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(ContractError::PollNotInProgress {}, execute_err);
+
+    // a poll which has been voted on cannot be cancelled
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 2,
+        vote: VoteOption::No,
+        amount: Uint128::from(100u128),
+    };
+    let voter_info = mock_info(TEST_VOTER, &[]);
+    execute(deps.as_mut(), poll_env.clone(), voter_info, msg).unwrap();
+
+    let msg = ExecuteMsg::CancelPoll { poll_id: 2 };
+    let execute_err = execute(deps.as_mut(), poll_env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(ContractError::PollHasVotes {}, execute_err);
+
+    // a poll cannot be cancelled once its voting period has ended
+    let mut env = poll_env;
+    env.block.height += DEFAULT_VOTING_PERIOD;
+    let msg = ExecuteMsg::CancelPoll { poll_id: 3 };
+    let execute_err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_eq!(ContractError::PollNotInProgress {}, execute_err);
+}
+
+#[test]
+fn veto_poll() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+
+    // the pause guardian vetoes polls
+    let msg = ExecuteMsg::UpdatePausers {
+        guardian: Some(TEST_VOTER_3.to_string()),
+        governance: Some(MOCK_CONTRACT_ADDR.to_string()),
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let poll_env = mock_env();
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), common::mock_env_before(&poll_env), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(100u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        Some(vec![PollExecuteMsg {
+            order: 1u64,
+            msg: CosmosMsg::Bank(BankMsg::Send {
+                to_address: TEST_VOTER_2.to_string(),
+                amount: coins(100, "uusd"),
+            }),
+        }]),
+    );
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(100u128),
+    };
+    let info = mock_info(TEST_VOTER, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    // the poll cannot be vetoed before it passes
+    let msg = ExecuteMsg::VetoPoll { poll_id: 1 };
+    let guardian_info = mock_info(TEST_VOTER_3, &[]);
+    let execute_err = execute(
+        deps.as_mut(),
+        poll_env.clone(),
+        guardian_info.clone(),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::PollNotPassed {}, execute_err);
+
+    let mut env = poll_env;
+    env.block.height += DEFAULT_VOTING_PERIOD;
+    let info = mock_info(TEST_CREATOR, &[]);
+    let end_msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    execute(deps.as_mut(), env.clone(), info.clone(), end_msg).unwrap();
+
+    // only the guardian can veto
+    let execute_err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, execute_err);
+
+    // the poll cannot be vetoed once its timelock period is over
+    let mut late_env = env.clone();
+    late_env.block.height += DEFAULT_TIMELOCK_PERIOD;
+    let execute_err =
+        execute(deps.as_mut(), late_env, guardian_info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(ContractError::TimelockExpired {}, execute_err);
+
+    let execute_res = execute(deps.as_mut(), env.clone(), guardian_info, msg).unwrap();
+    assert_eq!(
+        execute_res.attributes,
+        vec![attr("action", "veto_poll"), attr("poll_id", "1")]
+    );
+
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+    let value: PollResponse = from_binary(&res).unwrap();
+    assert_eq!(PollStatus::Vetoed, value.status);
+
+    // a vetoed poll is never executed
+    env.block.height += DEFAULT_TIMELOCK_PERIOD;
+    let msg = ExecuteMsg::ExecutePoll { poll_id: 1 };
+    let execute_err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_eq!(ContractError::PollNotPassed {}, execute_err);
+}
+
+#[test]
+fn poll_params_by_kind_and_target() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+
+    let text_deposit = DEFAULT_PROPOSAL_DEPOSIT / 2;
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        quorum: None,
+        threshold: None,
+        veto_threshold: None,
+        voting_period: None,
+        timelock_period: None,
+        expiration_period: None,
+        proposal_deposit: None,
+        snapshot_period: None,
+        unbonding_period: None,
+        text_poll_params: Some(PollParams {
+            proposal_deposit: Uint128::from(text_deposit),
+            quorum: Decimal::percent(DEFAULT_QUORUM),
+            threshold: Decimal::percent(70),
+        }),
+        target_poll_params: Some(vec![TargetPollParamsMsg {
+            contract: VOTING_TOKEN.to_string(),
+            params: PollParams {
+                proposal_deposit: Uint128::from(2 * DEFAULT_PROPOSAL_DEPOSIT),
+                quorum: Decimal::zero(),
+                threshold: Decimal::zero(),
+            },
+        }]),
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let poll_env = mock_env();
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    for (voter, amount) in [(TEST_VOTER, 60u128), (TEST_VOTER_2, 40u128)] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: voter.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+        });
+        let info = mock_info(VOTING_TOKEN, &[]);
+        execute(deps.as_mut(), common::mock_env_before(&poll_env), info, msg).unwrap();
+    }
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(100u128 + text_deposit + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+
+    // messages to an overridden contract need the higher deposit
+    let burn_msg = to_binary(&Cw20ExecuteMsg::Burn {
+        amount: Uint128::new(10),
+    })
+    .unwrap();
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        Some(vec![token_execute_msg(1, burn_msg)]),
+    );
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let execute_err = execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap_err();
+    assert_eq!(
+        ContractError::InsufficientProposalDeposit(2 * DEFAULT_PROPOSAL_DEPOSIT),
+        execute_err
+    );
+
+    // a text poll only needs the text poll deposit
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_CREATOR.to_string(),
+        amount: Uint128::from(text_deposit),
+        msg: to_binary(&Cw20HookMsg::CreatePoll {
+            title: "test".to_string(),
+            description: "test".to_string(),
+            link: None,
+            execute_msgs: None,
+            param_changes: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    // messages to other targets use the default requirements
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        Some(vec![PollExecuteMsg {
+            order: 1u64,
+            msg: CosmosMsg::Bank(BankMsg::Send {
+                to_address: TEST_VOTER_2.to_string(),
+                amount: coins(100, "uusd"),
+            }),
+        }]),
+    );
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    for poll_id in [1u64, 2u64] {
+        for (voter, vote, amount) in [
+            (TEST_VOTER, VoteOption::Yes, 60u128),
+            (TEST_VOTER_2, VoteOption::No, 40u128),
+        ] {
+            let msg = ExecuteMsg::CastVote {
+                poll_id,
+                vote,
+                amount: Uint128::from(amount),
+            };
+            let info = mock_info(voter, &[]);
+            execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+        }
+    }
+
+    // 60% of yes votes does not reach the threshold of text polls
+    let mut env = poll_env;
+    env.block.height += DEFAULT_VOTING_PERIOD;
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    let execute_res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert!(execute_res
+        .attributes
+        .contains(&attr("rejected_reason", "Threshold not reached")));
+
+    // the text poll deposit has been refunded
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(100u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    let msg = ExecuteMsg::EndPoll { poll_id: 2 };
+    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert!(execute_res.attributes.contains(&attr("passed", "true")));
+}
+
+#[test]
+fn execute_poll_with_param_changes() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+
+    let poll_env = mock_env();
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), common::mock_env_before(&poll_env), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(100u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+
+    let governance_change = |target: &str, distributor_contract: &str| ParamChangeMsg {
+        order: 1u64,
+        target: target.to_string(),
+        params: ParamChange::Governance {
+            owner: None,
+            quorum: Some(Decimal::percent(40)),
+            threshold: None,
+            veto_threshold: None,
+            voting_period: None,
+            timelock_period: None,
+            expiration_period: None,
+            proposal_deposit: None,
+            snapshot_period: None,
+            unbonding_period: None,
+            text_poll_params: None,
+            target_poll_params: None,
+            deposit_period: None,
+            stake_assets: None,
+            max_lock_weeks: None,
+            meta_contract: None,
+            community_contract: None,
+            distributor_contract: Some(distributor_contract.to_string()),
+        },
+    };
+    let distributor_change = ParamChangeMsg {
+        order: 2u64,
+        target: DISTRIBUTOR_CONTRACT.to_string(),
+        params: ParamChange::Distributor {
+            admins: vec![MOCK_CONTRACT_ADDR.to_string()],
+        },
+    };
+    let create_msg = |param_changes: Vec<ParamChangeMsg>| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: TEST_CREATOR.to_string(),
+            amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
+            msg: to_binary(&Cw20HookMsg::CreatePoll {
+                title: "test".to_string(),
+                description: "test".to_string(),
+                link: None,
+                execute_msgs: None,
+                param_changes: Some(param_changes),
+            })
+            .unwrap(),
+        })
+    };
+
+    // governance changes can only target governance itself
+    let msg = create_msg(vec![governance_change(VOTING_TOKEN, DISTRIBUTOR_CONTRACT)]);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let execute_err = execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap_err();
+    assert_eq!(ContractError::InvalidParamChangeTarget {}, execute_err);
+
+    // the contracts known to governance must be valid addresses
+    let msg = create_msg(vec![governance_change(MOCK_CONTRACT_ADDR, "d")]);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    match execute(deps.as_mut(), poll_env.clone(), info, msg) {
+        Err(ContractError::Std(_)) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    // the other contracts must be known to governance
+    let msg = create_msg(vec![distributor_change.clone()]);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let execute_err = execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap_err();
+    assert_eq!(ContractError::InvalidParamChangeTarget {}, execute_err);
+
+    mock_register_known_contracts(deps.as_mut());
+
+    let msg = create_msg(vec![
+        distributor_change,
+        governance_change(MOCK_CONTRACT_ADDR, DISTRIBUTOR_CONTRACT),
+    ]);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(100u128),
+    };
+    let info = mock_info(TEST_VOTER, &[]);
+    execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+
+    let mut env = poll_env;
+    env.block.height += DEFAULT_VOTING_PERIOD;
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    env.block.height += DEFAULT_TIMELOCK_PERIOD;
+    let msg = ExecuteMsg::ExecutePoll { poll_id: 1 };
+    let execute_res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let msgs_res = mock_execute_poll_msgs(deps.as_mut(), env.clone(), &execute_res, 1);
+
+    let update_config = to_binary(&ExecuteMsg::UpdateConfig {
+        owner: None,
+        quorum: Some(Decimal::percent(40)),
+        threshold: None,
+        veto_threshold: None,
+        voting_period: None,
+        timelock_period: None,
+        expiration_period: None,
+        proposal_deposit: None,
+        snapshot_period: None,
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: Some(DISTRIBUTOR_CONTRACT.to_string()),
+    })
+    .unwrap();
+    assert_eq!(
+        msgs_res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: update_config.clone(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: DISTRIBUTOR_CONTRACT.to_string(),
+                msg: to_binary(&DistributorExecuteMsg::UpdateAdmins {
+                    admins: Some(vec![MOCK_CONTRACT_ADDR.to_string()]),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+
+    // governance owns its config, the change is applied without the owner
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg: ExecuteMsg = from_binary(&update_config).unwrap();
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), env, QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(Decimal::percent(40), config.quorum);
+    assert_eq!(
+        Some(DISTRIBUTOR_CONTRACT.to_string()),
+        config.distributor_contract
+    );
+}
+
+#[test]
+fn create_poll_with_meta_contract_param_changes() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+    mock_register_known_contracts(deps.as_mut());
+
+    let meta_contract_change =
+        |community_contract: Option<&str>, deposit_split: DepositSplit| ParamChangeMsg {
+            order: 1u64,
+            target: META_CONTRACT.to_string(),
+            params: ParamChange::MetaContract {
+                creation_deposit: None,
+                reclaimable_threshold: None,
+                community_contract: community_contract.map(|v| v.to_string()),
+                deposit_split: Some(deposit_split),
+            },
+        };
+    let role_change = |target: &str, granted: bool| ParamChangeMsg {
+        order: 2u64,
+        target: target.to_string(),
+        params: ParamChange::MetaContractRole {
+            address: TEST_VOTER.to_string(),
+            role: Role::Resolver,
+            granted,
+        },
+    };
+    let create_msg = |param_changes: Vec<ParamChangeMsg>| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: TEST_CREATOR.to_string(),
+            amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
+            msg: to_binary(&Cw20HookMsg::CreatePoll {
+                title: "test".to_string(),
+                description: "test".to_string(),
+                link: None,
+                execute_msgs: None,
+                param_changes: Some(param_changes),
+            })
+            .unwrap(),
+        })
+    };
+    let half_split = DepositSplit {
+        burn: Decimal::percent(50),
+        community: Decimal::percent(50),
+    };
+
+    // meta-contract changes can only target the known meta-contract
+    let msg = create_msg(vec![role_change(DISTRIBUTOR_CONTRACT, true)]);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let execute_err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(ContractError::InvalidParamChangeTarget {}, execute_err);
+
+    // the deposit split cannot exceed the deposit
+    let msg = create_msg(vec![meta_contract_change(
+        None,
+        DepositSplit {
+            burn: Decimal::percent(60),
+            community: Decimal::percent(50),
+        },
+    )]);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let execute_err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(ContractError::InvalidDepositSplit {}, execute_err);
+
+    // the deposits can only be sent to the known community contract
+    let msg = create_msg(vec![meta_contract_change(
+        Some(TEST_VOTER_2),
+        half_split.clone(),
+    )]);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let execute_err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(ContractError::UnknownCommunityContract {}, execute_err);
+
+    let msg = create_msg(vec![
+        meta_contract_change(Some(COMMUNITY_CONTRACT), half_split.clone()),
+        role_change(META_CONTRACT, false),
+    ]);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+    let poll: PollResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        poll.execute_data,
+        Some(vec![
+            PollExecuteMsg {
+                order: 1u64,
+                msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: META_CONTRACT.to_string(),
+                    msg: to_binary(&MetaContractExecuteMsg::UpdateConfig {
+                        creation_deposit: None,
+                        reclaimable_threshold: None,
+                        community_contract: Some(COMMUNITY_CONTRACT.to_string()),
+                        deposit_split: Some(half_split),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            },
+            PollExecuteMsg {
+                order: 2u64,
+                msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: META_CONTRACT.to_string(),
+                    msg: to_binary(&MetaContractExecuteMsg::RevokeRole {
+                        address: TEST_VOTER.to_string(),
+                        role: Role::Resolver,
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            },
+        ])
+    );
+}
+
+#[test]
+fn add_deposit_to_draft_poll() {
+    const DEPOSIT_PERIOD: u64 = 100;
+
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        quorum: None,
+        threshold: None,
+        veto_threshold: None,
+        voting_period: None,
+        timelock_period: None,
+        expiration_period: None,
+        proposal_deposit: None,
+        snapshot_period: None,
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: Some(Duration::Height(DEPOSIT_PERIOD)),
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let poll_env = mock_env();
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    execute(deps.as_mut(), common::mock_env_before(&poll_env), info, msg).unwrap();
+
+    let quarter = DEFAULT_PROPOSAL_DEPOSIT / 4;
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(100u128 + DEFAULT_PROPOSAL_DEPOSIT + quarter),
+        )],
+    )]);
+
+    // two drafts are created with a partial deposit
+    for _ in 0..2 {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: TEST_CREATOR.to_string(),
+            amount: Uint128::from(quarter),
+            msg: to_binary(&Cw20HookMsg::CreatePoll {
+                title: "test".to_string(),
+                description: "test".to_string(),
+                link: None,
+                execute_msgs: None,
+                param_changes: None,
+            })
+            .unwrap(),
+        });
+        let info = mock_info(VOTING_TOKEN, &[]);
+        execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+    }
+
+    let res = query(
+        deps.as_ref(),
+        poll_env.clone(),
+        QueryMsg::Poll { poll_id: 1 },
+    )
+    .unwrap();
+    let value: PollResponse = from_binary(&res).unwrap();
+    assert_eq!(PollStatus::Deposit, value.status);
+
+    // drafts cannot be voted on
+    let vote_msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(100u128),
+    };
+    let voter_info = mock_info(TEST_VOTER, &[]);
+    let execute_err = execute(
+        deps.as_mut(),
+        poll_env.clone(),
+        voter_info.clone(),
+        vote_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::PollNotInProgress {}, execute_err);
+
+    let add_deposit_msg = |depositor: &str, amount: u128| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: depositor.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::AddDeposit { poll_id: 1 }).unwrap(),
+        })
+    };
+
+    let mut env = poll_env.clone();
+    env.block.height += 10;
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let min_deposit = DEFAULT_PROPOSAL_DEPOSIT * MIN_ADDED_DEPOSIT_PERCENT as u128 / 100;
+    let msg = add_deposit_msg(TEST_VOTER, min_deposit - 1);
+    let execute_err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        ContractError::InsufficientAddedDeposit(min_deposit),
+        execute_err
+    );
+
+    let msg = add_deposit_msg(TEST_VOTER, quarter);
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // the voting starts once the deposit is reached
+    let msg = add_deposit_msg(TEST_VOTER_2, 2 * quarter);
+    let execute_res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        execute_res.attributes,
+        vec![
+            attr("action", "add_deposit"),
+            attr("poll_id", "1"),
+            attr("depositor", TEST_VOTER_2),
+            attr("amount", (2 * quarter).to_string()),
+            attr(
+                "end_height",
+                (env.block.height + DEFAULT_VOTING_PERIOD).to_string()
+            ),
+        ]
+    );
+
+    let msg = add_deposit_msg(TEST_VOTER_2, quarter);
+    let execute_err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(ContractError::PollNotInDepositPeriod {}, execute_err);
+
+    execute(deps.as_mut(), env.clone(), voter_info, vote_msg).unwrap();
+
+    // the contributors are refunded when the poll ends
+    env.block.height += DEFAULT_VOTING_PERIOD;
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    let execute_res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let refund = |recipient: &str, amount: u128| {
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: VOTING_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: Uint128::from(amount),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))
+    };
+    assert_eq!(
+        execute_res.messages,
+        vec![
+            refund(TEST_CREATOR, quarter),
+            refund(TEST_VOTER, quarter),
+            refund(TEST_VOTER_2, 2 * quarter),
+        ]
+    );
+    assert!(execute_res.attributes.contains(&attr("passed", "true")));
+
+    // a draft which does not reach its deposit is rejected after its deposit period
+    let mut draft_env = poll_env;
+    draft_env.block.height += DEPOSIT_PERIOD - 1;
+    let msg = ExecuteMsg::EndPoll { poll_id: 2 };
+    let execute_err =
+        execute(deps.as_mut(), draft_env.clone(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(ContractError::PollDepositPeriod {}, execute_err);
+
+    draft_env.block.height += 1;
+    let execute_res = execute(deps.as_mut(), draft_env, info, msg).unwrap();
+    assert_eq!(execute_res.messages, vec![refund(TEST_CREATOR, quarter)]);
+    assert!(execute_res
+        .attributes
+        .contains(&attr("rejected_reason", "Deposit not reached")));
+
+    let state: State = STATE.load(&deps.storage).unwrap();
+    assert_eq!(Uint128::zero(), state.total_deposit);
+}
+
+#[test]
+fn add_deposit_up_to_max_depositors() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        quorum: None,
+        threshold: None,
+        veto_threshold: None,
+        voting_period: None,
+        timelock_period: None,
+        expiration_period: None,
+        proposal_deposit: None,
+        snapshot_period: None,
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: Some(Duration::Height(100)),
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let create_msg = |amount: u128| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: TEST_CREATOR.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::CreatePoll {
+                title: "test".to_string(),
+                description: "test".to_string(),
+                link: None,
+                execute_msgs: None,
+                param_changes: None,
+            })
+            .unwrap(),
+        })
+    };
+    let add_deposit_msg = |depositor: &str, amount: u128| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: depositor.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::AddDeposit { poll_id: 1 }).unwrap(),
+        })
+    };
+    let info = mock_info(VOTING_TOKEN, &[]);
+
+    // a draft needs a minimum initial deposit
+    let min_initial_deposit = DEFAULT_PROPOSAL_DEPOSIT * MIN_INITIAL_DEPOSIT_PERCENT as u128 / 100;
+    let execute_err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        create_msg(min_initial_deposit - 1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        ContractError::InsufficientProposalDeposit(min_initial_deposit),
+        execute_err
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        create_msg(min_initial_deposit),
+    )
+    .unwrap();
+
+    // the creator is the first depositor
+    let min_deposit = DEFAULT_PROPOSAL_DEPOSIT * MIN_ADDED_DEPOSIT_PERCENT as u128 / 100;
+    for i in 1..MAX_DEPOSITORS {
+        let msg = add_deposit_msg(&format!("depositor{}", i), min_deposit);
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let msg = add_deposit_msg(TEST_VOTER, min_deposit);
+    let execute_err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        ContractError::TooManyDepositors(MAX_DEPOSITORS),
+        execute_err
+    );
+
+    // the depositors can still complete the deposit
+    let remaining =
+        DEFAULT_PROPOSAL_DEPOSIT - min_initial_deposit - (MAX_DEPOSITORS as u128 - 1) * min_deposit;
+    let msg = add_deposit_msg(TEST_CREATOR, remaining);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+    let value: PollResponse = from_binary(&res).unwrap();
+    assert_eq!(PollStatus::InProgress, value.status);
+}
+
+#[test]
+fn execute_poll_with_order() {
+    const POLL_START_HEIGHT: u64 = 1000;
+    const POLL_ID: u64 = 1;
+    let stake_amount = 1000;
+
+    let mut deps = mock_dependencies(&coins(1000, VOTING_TOKEN));
+    instantiate::mock_instantiate(deps.as_mut());
+    mock_register_voting_token(deps.as_mut());
+    let mut creator_env = common::mock_env_height(POLL_START_HEIGHT, 10000);
+    let mut creator_info = mock_info(VOTING_TOKEN, &coins(2, VOTING_TOKEN));
+
+    let exec_msg_bz = to_binary(&Cw20ExecuteMsg::Burn {
+        amount: Uint128::new(10),
+    })
+    .unwrap();
+
+    let exec_msg_bz2 = to_binary(&Cw20ExecuteMsg::Burn {
+        amount: Uint128::new(20),
+    })
+    .unwrap();
+
+    let exec_msg_bz3 = to_binary(&Cw20ExecuteMsg::Burn {
+        amount: Uint128::new(30),
+    })
+    .unwrap();
+    let exec_msg_bz4 = to_binary(&Cw20ExecuteMsg::Burn {
+        amount: Uint128::new(40),
+    })
+    .unwrap();
+    let exec_msg_bz5 = to_binary(&Cw20ExecuteMsg::Burn {
+        amount: Uint128::new(50),
+    })
+    .unwrap();
+
+    //add three messages with different order
+    let execute_msgs: Vec<PollExecuteMsg> = vec![
+        token_execute_msg(3u64, exec_msg_bz3.clone()),
+        token_execute_msg(4u64, exec_msg_bz4.clone()),
+        token_execute_msg(2u64, exec_msg_bz2.clone()),
+        token_execute_msg(5u64, exec_msg_bz5.clone()),
+        token_execute_msg(1u64, exec_msg_bz.clone()),
+    ];
+
+    let msg = create_poll_msg(
+        "test".to_string(),
+        "test".to_string(),
+        None,
+        Some(execute_msgs),
+    );
+
+    let execute_res = execute(
+        deps.as_mut(),
+        creator_env.clone(),
+        creator_info.clone(),
+        msg,
+    )
+    .unwrap();
+
+    assert_create_poll_result(
+        1,
+        creator_env.block.height + DEFAULT_VOTING_PERIOD,
+        TEST_CREATOR,
+        execute_res,
+        deps.as_ref(),
+    );
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from((stake_amount + DEFAULT_PROPOSAL_DEPOSIT) as u128),
+        )],
+    )]);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(stake_amount as u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let execute_res = execute(
+        deps.as_mut(),
+        common::mock_env_before(&creator_env),
+        info,
+        msg,
+    )
+    .unwrap();
+    assert_stake_tokens_result(
+        stake_amount,
+        DEFAULT_PROPOSAL_DEPOSIT,
+        stake_amount,
+        1,
+        execute_res,
+        deps.as_ref(),
+    );
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(stake_amount),
+    };
+    let env = common::mock_env_height(POLL_START_HEIGHT, 10000);
+    let info = mock_info(TEST_VOTER, &[]);
+    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    assert_eq!(
+        execute_res.attributes,
+        vec![
+            attr("action", "cast_vote"),
+            attr("poll_id", POLL_ID.to_string().as_str()),
+            attr("amount", "1000"),
+            attr("voter", TEST_VOTER),
+            attr("vote_option", "yes"),
+        ]
+    );
+
+    creator_info.sender = Addr::unchecked(TEST_CREATOR);
+    creator_env.block.height += DEFAULT_VOTING_PERIOD;
+
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    let execute_res = execute(
+        deps.as_mut(),
+        creator_env.clone(),
+        creator_info.clone(),
+        msg,
+    )
+    .unwrap();
+
+    assert_eq!(
+        execute_res.attributes,
+        vec![
+            attr("action", "end_poll"),
+            attr("poll_id", "1"),
+            attr("rejected_reason", "Poll Passed"),
+            attr("passed", "true"),
+        ]
+    );
+    assert_eq!(
+        execute_res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: VOTING_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: TEST_CREATOR.to_string(),
+                amount: Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // End poll will withdraw deposit balance
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(stake_amount as u128),
+        )],
+    )]);
+
     creator_env.block.height += DEFAULT_TIMELOCK_PERIOD;
     let msg = ExecuteMsg::ExecutePoll { poll_id: 1 };
-    let execute_res = execute(deps.as_mut(), creator_env, creator_info, msg).unwrap();
+    let execute_res = execute(deps.as_mut(), creator_env.clone(), creator_info, msg).unwrap();
+    let msgs_res = mock_execute_poll_msgs(deps.as_mut(), creator_env, &execute_res, 1);
     assert_eq!(
-        execute_res.messages,
+        msgs_res.messages,
         vec![
             SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: VOTING_TOKEN.to_string(),
@@ -1490,9 +3100,31 @@ fn snapshot_poll() {
     instantiate::mock_instantiate(deps.as_mut());
     mock_register_voting_token(deps.as_mut());
 
-    let msg = create_poll_msg("test".to_string(), "test".to_string(), None, None);
     let mut creator_env = mock_env();
     let creator_info = mock_info(VOTING_TOKEN, &[]);
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(stake_amount),
+        )],
+    )]);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(stake_amount),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    execute(
+        deps.as_mut(),
+        common::mock_env_before(&creator_env),
+        creator_info.clone(),
+        msg,
+    )
+    .unwrap();
+
+    let msg = create_poll_msg("test".to_string(), "test".to_string(), None, None);
     let execute_res = execute(
         deps.as_mut(),
         creator_env.clone(),
@@ -1565,46 +3197,51 @@ fn successful_cast_vote_with_snapshot() {
     instantiate::mock_instantiate(deps.as_mut());
     mock_register_voting_token(deps.as_mut());
 
-    let env = common::mock_env_height(0, 10000);
-    let info = mock_info(VOTING_TOKEN, &[]);
-    let msg = create_poll_msg("test".to_string(), "test".to_string(), None, None);
-
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
-    assert_create_poll_result(
-        1,
-        DEFAULT_VOTING_PERIOD,
-        TEST_CREATOR,
-        execute_res,
-        deps.as_ref(),
-    );
+    let poll_env = common::mock_env_height(1, 10000);
+
+    let mut total_stake = 0u128;
+    for voter in [TEST_VOTER, TEST_VOTER_2, TEST_VOTER_3].iter() {
+        total_stake += 11;
+        deps.querier.with_token_balances(&[(
+            &VOTING_TOKEN.to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(total_stake))],
+        )]);
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: voter.to_string(),
+            amount: Uint128::from(11u128),
+            msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+        });
+
+        let info = mock_info(VOTING_TOKEN, &[]);
+        let execute_res =
+            execute(deps.as_mut(), common::mock_env_before(&poll_env), info, msg).unwrap();
+        assert_stake_tokens_result(total_stake, 0, 11, 0, execute_res, deps.as_ref());
+    }
 
     deps.querier.with_token_balances(&[(
         &VOTING_TOKEN.to_string(),
         &[(
             &MOCK_CONTRACT_ADDR.to_string(),
-            &Uint128::from(11u128 + DEFAULT_PROPOSAL_DEPOSIT),
+            &Uint128::from(total_stake + DEFAULT_PROPOSAL_DEPOSIT),
         )],
     )]);
 
-    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-        sender: TEST_VOTER.to_string(),
-        amount: Uint128::from(11u128),
-        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
-    });
-
     let info = mock_info(VOTING_TOKEN, &[]);
-    let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    assert_stake_tokens_result(
-        11,
-        DEFAULT_PROPOSAL_DEPOSIT,
-        11,
-        1,
-        execute_res,
-        deps.as_ref(),
+    let msg = create_poll_msg("test".to_string(), "test".to_string(), None, None);
+
+    let execute_res = execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+    assert_eq!(
+        execute_res.attributes,
+        vec![
+            attr("action", "create_poll"),
+            attr("creator", TEST_CREATOR),
+            attr("poll_id", "1"),
+            attr("end_height", (1 + DEFAULT_VOTING_PERIOD).to_string()),
+        ]
     );
 
     //cast_vote without snapshot
-    let env = common::mock_env_height(0, 10000);
     let info = mock_info(TEST_VOTER, &coins(11, VOTING_TOKEN));
     let amount = 10u128;
 
@@ -1614,7 +3251,7 @@ fn successful_cast_vote_with_snapshot() {
         amount: Uint128::from(amount),
     };
 
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let execute_res = execute(deps.as_mut(), poll_env, info, msg).unwrap();
     assert_cast_vote_success(TEST_VOTER, amount, 1, VoteOption::Yes, execute_res);
 
     // balance be double
@@ -1622,24 +3259,17 @@ fn successful_cast_vote_with_snapshot() {
         &VOTING_TOKEN.to_string(),
         &[(
             &MOCK_CONTRACT_ADDR.to_string(),
-            &Uint128::from(22u128 + DEFAULT_PROPOSAL_DEPOSIT),
+            &Uint128::from(66u128 + DEFAULT_PROPOSAL_DEPOSIT),
         )],
     )]);
 
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
     let value: PollResponse = from_binary(&res).unwrap();
     assert_eq!(value.staked_amount, None);
-    let end_height = value.end_height;
-
-    //cast another vote
-    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-        sender: TEST_VOTER_2.to_string(),
-        amount: Uint128::from(11u128),
-        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
-    });
-
-    let info = mock_info(VOTING_TOKEN, &[]);
-    let _execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let end_height = match value.end {
+        Expiration::AtHeight(height) => height,
+        _ => panic!("poll must end at a height"),
+    };
 
     // another voter cast a vote
     let msg = ExecuteMsg::CastVote {
@@ -1654,7 +3284,7 @@ fn successful_cast_vote_with_snapshot() {
 
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
     let value: PollResponse = from_binary(&res).unwrap();
-    assert_eq!(value.staked_amount, Some(Uint128::new(22)));
+    assert_eq!(value.staked_amount, Some(Uint128::new(66)));
 
     // snanpshot poll will not go through
     let snap_error = execute(
@@ -1666,24 +3296,16 @@ fn successful_cast_vote_with_snapshot() {
     .unwrap_err();
     assert_eq!(ContractError::SnapshotAlreadyOccurred {}, snap_error);
 
-    // balance be double
+    // balance be triple
     deps.querier.with_token_balances(&[(
         &VOTING_TOKEN.to_string(),
         &[(
             &MOCK_CONTRACT_ADDR.to_string(),
-            &Uint128::from(33u128 + DEFAULT_PROPOSAL_DEPOSIT),
+            &Uint128::from(99u128 + DEFAULT_PROPOSAL_DEPOSIT),
         )],
     )]);
 
     // another voter cast a vote but the snapshot is already occurred
-    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-        sender: TEST_VOTER_3.to_string(),
-        amount: Uint128::from(11u128),
-        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
-    });
-
-    let info = mock_info(VOTING_TOKEN, &[]);
-    let _execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     let msg = ExecuteMsg::CastVote {
         poll_id: 1,
         vote: VoteOption::Yes,
@@ -1696,11 +3318,11 @@ fn successful_cast_vote_with_snapshot() {
 
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
     let value: PollResponse = from_binary(&res).unwrap();
-    assert_eq!(value.staked_amount, Some(Uint128::new(22)));
+    assert_eq!(value.staked_amount, Some(Uint128::new(66)));
 }
 
 #[test]
-fn fails_end_poll_quorum_inflation_without_snapshot_poll() {
+fn late_stake_does_not_inflate_quorum_without_snapshot_poll() {
     const POLL_START_HEIGHT: u64 = 1000;
     const POLL_ID: u64 = 1;
     let stake_amount = 1000;
@@ -1719,16 +3341,8 @@ fn fails_end_poll_quorum_inflation_without_snapshot_poll() {
 
     //add two messages
     let execute_msgs: Vec<PollExecuteMsg> = vec![
-        PollExecuteMsg {
-            order: 1u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz.clone(),
-        },
-        PollExecuteMsg {
-            order: 2u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz,
-        },
+        token_execute_msg(1u64, exec_msg_bz.clone()),
+        token_execute_msg(2u64, exec_msg_bz),
     ];
 
     let msg = create_poll_msg(
@@ -1769,7 +3383,13 @@ fn fails_end_poll_quorum_inflation_without_snapshot_poll() {
     });
 
     let info = mock_info(VOTING_TOKEN, &[]);
-    let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let execute_res = execute(
+        deps.as_mut(),
+        common::mock_env_before(&creator_env),
+        info,
+        msg,
+    )
+    .unwrap();
     assert_stake_tokens_result(
         stake_amount,
         DEFAULT_PROPOSAL_DEPOSIT,
@@ -1812,7 +3432,7 @@ fn fails_end_poll_quorum_inflation_without_snapshot_poll() {
         )],
     )]);
 
-    //cast another vote
+    // another voter stakes
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: TEST_VOTER_2.to_string(),
         amount: Uint128::from(8 * stake_amount as u128),
@@ -1822,7 +3442,7 @@ fn fails_end_poll_quorum_inflation_without_snapshot_poll() {
     let info = mock_info(VOTING_TOKEN, &[]);
     let _execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    // another voter cast a vote
+    // the tokens were staked after the poll started, they cannot vote
     let msg = ExecuteMsg::CastVote {
         poll_id: 1,
         vote: VoteOption::Yes,
@@ -1830,23 +3450,13 @@ fn fails_end_poll_quorum_inflation_without_snapshot_poll() {
     };
     let env = common::mock_env_height(creator_env.block.height, 10000);
     let info = mock_info(TEST_VOTER_2, &[]);
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
-
-    assert_eq!(
-        execute_res.attributes,
-        vec![
-            attr("action", "cast_vote"),
-            attr("poll_id", POLL_ID.to_string().as_str()),
-            attr("amount", "1000"),
-            attr("voter", TEST_VOTER_2),
-            attr("vote_option", "yes"),
-        ]
-    );
+    let execute_err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_eq!(ContractError::InsufficientStaked {}, execute_err);
 
     creator_info.sender = Addr::unchecked(TEST_CREATOR);
     creator_env.block.height += 10;
 
-    // quorum must reach
+    // quorum is reached, the late stake is not counted
     let msg = ExecuteMsg::EndPoll { poll_id: 1 };
     let execute_res = execute(deps.as_mut(), creator_env, creator_info, msg).unwrap();
 
@@ -1855,15 +3465,15 @@ fn fails_end_poll_quorum_inflation_without_snapshot_poll() {
         vec![
             attr("action", "end_poll"),
             attr("poll_id", "1"),
-            attr("rejected_reason", "Quorum not reached"),
-            attr("passed", "false"),
+            attr("rejected_reason", "Poll Passed"),
+            attr("passed", "true"),
         ]
     );
 
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
     let value: PollResponse = from_binary(&res).unwrap();
     assert_eq!(
-        10 * stake_amount,
+        2 * stake_amount,
         value.total_balance_at_end_poll.unwrap().u128()
     );
 }
@@ -1888,16 +3498,8 @@ fn successful_end_poll_with_controlled_quorum() {
 
     //add two messages
     let execute_msgs: Vec<PollExecuteMsg> = vec![
-        PollExecuteMsg {
-            order: 1u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz.clone(),
-        },
-        PollExecuteMsg {
-            order: 2u64,
-            contract: VOTING_TOKEN.to_string(),
-            msg: exec_msg_bz,
-        },
+        token_execute_msg(1u64, exec_msg_bz.clone()),
+        token_execute_msg(2u64, exec_msg_bz),
     ];
 
     let msg = create_poll_msg(
@@ -1938,7 +3540,13 @@ fn successful_end_poll_with_controlled_quorum() {
     });
 
     let info = mock_info(VOTING_TOKEN, &[]);
-    let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let execute_res = execute(
+        deps.as_mut(),
+        common::mock_env_before(&creator_env),
+        info,
+        msg,
+    )
+    .unwrap();
     assert_stake_tokens_result(
         stake_amount,
         DEFAULT_PROPOSAL_DEPOSIT,
@@ -1997,7 +3605,7 @@ fn successful_end_poll_with_controlled_quorum() {
         )],
     )]);
 
-    //cast another vote
+    // another voter stakes
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: TEST_VOTER_2.to_string(),
         amount: Uint128::from(8 * stake_amount as u128),
@@ -2007,6 +3615,7 @@ fn successful_end_poll_with_controlled_quorum() {
     let info = mock_info(VOTING_TOKEN, &[]);
     let _execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
+    // the tokens were staked after the poll started, they cannot vote
     let msg = ExecuteMsg::CastVote {
         poll_id: 1,
         vote: VoteOption::Yes,
@@ -2014,18 +3623,8 @@ fn successful_end_poll_with_controlled_quorum() {
     };
     let env = common::mock_env_height(creator_env.block.height, 10000);
     let info = mock_info(TEST_VOTER_2, &[]);
-    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
-
-    assert_eq!(
-        execute_res.attributes,
-        vec![
-            attr("action", "cast_vote"),
-            attr("poll_id", POLL_ID.to_string().as_str()),
-            attr("amount", "8000"),
-            attr("voter", TEST_VOTER_2),
-            attr("vote_option", "yes"),
-        ]
-    );
+    let execute_err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_eq!(ContractError::InsufficientStaked {}, execute_err);
 
     creator_info.sender = Addr::unchecked(TEST_CREATOR);
     creator_env.block.height += 10;
@@ -2063,7 +3662,7 @@ fn successful_end_poll_with_controlled_quorum() {
         value.total_balance_at_end_poll.unwrap().u128()
     );
 
-    assert_eq!(value.yes_votes.u128(), 9 * stake_amount);
+    assert_eq!(value.yes_votes.u128(), stake_amount);
 
     // actual staked amount is 10 times bigger than staked amount
     let actual_staked_weight = query_token_balance(
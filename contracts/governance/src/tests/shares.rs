@@ -0,0 +1,197 @@
+use cosmwasm_std::{StdError, Uint128};
+
+use crate::error::ContractError;
+use crate::shares::{share_amount, stake_share, withdraw_share};
+
+const CASES: usize = 10_000;
+
+// xorshift, so the cases are the same on every run
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // values spread over all magnitudes below 2^bits, small ones included
+    fn amount(&mut self, bits: u32) -> u128 {
+        let bits = self.next() as u32 % bits + 1;
+        (self.next() as u128) % (1u128 << bits)
+    }
+
+    // a pool with a share price between 1/2^16 and 2^32 tokens
+    fn pool(&mut self) -> (u128, u128) {
+        let total_share = self.amount(60) + 1;
+        let total_balance = match self.next() % 3 {
+            0 => total_share,
+            1 => (total_share >> (self.next() % 16)).max(1),
+            _ => total_share.saturating_mul(self.amount(32) + 1),
+        };
+        (total_share, total_balance)
+    }
+}
+
+#[test]
+fn first_stake_gets_a_share_per_token() {
+    let mut rng = Rng(7);
+    for _ in 0..CASES {
+        let amount = rng.amount(120);
+        let donated = rng.amount(120);
+        assert_eq!(
+            stake_share(amount.into(), Uint128::zero(), donated.into()).unwrap(),
+            Uint128::from(amount)
+        );
+    }
+}
+
+#[test]
+fn staking_never_mints_more_than_the_amount_is_worth() {
+    let mut rng = Rng(11);
+    for _ in 0..CASES {
+        let (total_share, total_balance) = rng.pool();
+        let amount = rng.amount(64) + 1;
+
+        let share = match stake_share(amount.into(), total_share.into(), total_balance.into()) {
+            Ok(share) => share.u128(),
+            Err(ContractError::StakeTooSmall {}) => continue,
+            Err(e) => panic!("unexpected error {}", e),
+        };
+        assert!(share > 0);
+
+        // the share is worth the amount, less at most a token or 0.1% of it
+        let value = share_amount(
+            share.into(),
+            (total_share + share).into(),
+            (total_balance + amount).into(),
+        )
+        .unwrap()
+        .u128();
+        assert!(value <= amount);
+        assert!(amount - value <= std::cmp::max(2, amount / 1000 + 1));
+    }
+}
+
+#[test]
+fn withdrawing_burns_at_least_the_amount_worth() {
+    let mut rng = Rng(13);
+    for _ in 0..CASES {
+        let (total_share, total_balance) = rng.pool();
+        let amount = rng.amount(64).min(total_balance);
+
+        let share = withdraw_share(amount.into(), total_share.into(), total_balance.into())
+            .unwrap()
+            .u128();
+        assert!(share <= total_share);
+
+        // the burnt share is worth the amount, and one share less would not be
+        let value = share_amount(share.into(), total_share.into(), total_balance.into())
+            .unwrap()
+            .u128();
+        assert!(value >= amount);
+        if share > 0 {
+            let less = share_amount((share - 1).into(), total_share.into(), total_balance.into())
+                .unwrap()
+                .u128();
+            assert!(less < amount);
+        }
+    }
+}
+
+#[test]
+fn stake_and_withdraw_round_trip_does_not_profit() {
+    let mut rng = Rng(17);
+    for _ in 0..CASES {
+        let (total_share, total_balance) = rng.pool();
+        let amount = rng.amount(64) + 1;
+
+        let share = match stake_share(amount.into(), total_share.into(), total_balance.into()) {
+            Ok(share) => share,
+            Err(_) => continue,
+        };
+        let total_share = Uint128::from(total_share) + share;
+        let total_balance = Uint128::from(total_balance + amount);
+
+        // withdrawing the whole share pays no more than was staked
+        let withdrawn = share_amount(share, total_share, total_balance).unwrap();
+        assert!(withdrawn.u128() <= amount);
+
+        // and withdrawing the staked amount needs the whole share at least
+        let burnt = withdraw_share(amount.into(), total_share, total_balance).unwrap();
+        assert!(burnt >= share);
+    }
+}
+
+#[test]
+fn donation_cannot_steal_the_next_stake() {
+    let mut rng = Rng(19);
+    for _ in 0..CASES {
+        // the attacker stakes a token into the empty pool and donates to it
+        let attacker_share = stake_share(Uint128::new(1), Uint128::zero(), Uint128::zero())
+            .unwrap()
+            .u128();
+        let donation = rng.amount(100);
+        let victim_amount = rng.amount(100) + 1;
+        let total_balance = 1 + donation;
+
+        let victim_share = match stake_share(
+            victim_amount.into(),
+            attacker_share.into(),
+            total_balance.into(),
+        ) {
+            Ok(share) => share.u128(),
+            Err(ContractError::StakeTooSmall {}) => continue,
+            Err(e) => panic!("unexpected error {}", e),
+        };
+
+        // the attacker withdraws everything, the victim keeps nearly all the stake
+        let total_share = attacker_share + victim_share;
+        let total_balance = total_balance + victim_amount;
+        let victim_value = share_amount(
+            victim_share.into(),
+            total_share.into(),
+            total_balance.into(),
+        )
+        .unwrap()
+        .u128();
+        let attacker_value = share_amount(
+            attacker_share.into(),
+            total_share.into(),
+            total_balance.into(),
+        )
+        .unwrap()
+        .u128();
+        assert!(victim_amount - victim_value <= std::cmp::max(2, victim_amount / 1000 + 1));
+        assert!(attacker_value <= 1 + donation + victim_amount / 1000 + 1);
+    }
+}
+
+#[test]
+fn extreme_values_fail_without_panicking() {
+    let max = Uint128::new(u128::MAX);
+
+    // nothing left to withdraw from
+    match withdraw_share(Uint128::new(1), Uint128::new(1), Uint128::zero()) {
+        Err(StdError::DivideByZero { .. }) => {}
+        _ => panic!("Must return divide by zero error"),
+    }
+
+    // a share worth more than a Uint128
+    match share_amount(max, Uint128::new(1), max) {
+        Err(StdError::ConversionOverflow { .. }) => {}
+        _ => panic!("Must return conversion overflow error"),
+    }
+    match withdraw_share(max, max, Uint128::new(1)) {
+        Err(StdError::ConversionOverflow { .. }) => {}
+        _ => panic!("Must return conversion overflow error"),
+    }
+
+    assert_eq!(stake_share(max, max, max).unwrap(), max);
+    assert_eq!(share_amount(max, max, max).unwrap(), max);
+    assert_eq!(
+        stake_share(Uint128::new(1), Uint128::new(1), max),
+        Err(ContractError::StakeTooSmall {})
+    );
+}
@@ -1,6 +1,9 @@
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{attr, from_binary, to_binary, Addr, Uint128};
-use cw20::Cw20ReceiveMsg;
+use cosmwasm_std::{
+    attr, coins, from_binary, to_binary, Addr, BankMsg, CosmosMsg, Decimal, Deps, DepsMut,
+    Response, StdError, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
 use testutils::mock_querier::mock_dependencies;
 
@@ -10,9 +13,17 @@ use messages::governance::query_msgs::QueryMsg;
 
 use crate::error::ContractError;
 use crate::staking::stake_voting_tokens;
-use crate::tests::common::{TEST_VOTER, VOTING_TOKEN};
+use crate::tests::common::{
+    mock_env_height, DEFAULT_PROPOSAL_DEPOSIT, DEFAULT_VOTING_PERIOD, LP_TOKEN, TEST_CREATOR,
+    TEST_VOTER, TEST_VOTER_2, VOTING_TOKEN,
+};
 use crate::tests::{instantiate, poll};
-use messages::governance::state::{Cw20HookMsg, StakerResponse};
+use messages::governance::state::{
+    ConfigResponse, Cw20HookMsg, LockedPollsResponse, StakeAssetMsg, StakerAssetResponse,
+    StakerResponse, StakerRewardsResponse, TallyResponse, TotalVePowerResponse, Unbonding,
+    UnbondingsResponse, VeLockResponse, VoteOption, LOCK_WEEK_BLOCKS,
+};
+use messages::pause::PauseOperation;
 
 #[test]
 fn share_calculation() {
@@ -98,13 +109,1209 @@ fn share_calculation() {
     assert_eq!(stake_info.locked_balance, vec![]);
 }
 
+fn query_rewards(deps: Deps, address: &str) -> StakerRewardsResponse {
+    let res = query(
+        deps,
+        mock_env(),
+        QueryMsg::StakerRewards {
+            address: address.to_string(),
+        },
+    )
+    .unwrap();
+    from_binary(&res).unwrap()
+}
+
+#[test]
+fn reward_distribution() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+
+    // no one to distribute to
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(40u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(ContractError::NothingStaked {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    let mut total_balance = 0u128;
+    for (staker, amount) in [(TEST_VOTER, 100u128), (TEST_VOTER_2, 300u128)] {
+        total_balance += amount;
+        deps.querier.with_token_balances(&[(
+            &VOTING_TOKEN.to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(total_balance),
+            )],
+        )]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+        });
+        let info = mock_info(VOTING_TOKEN, &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    // rewards are not added to the staked balance
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(440u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(40u128),
+        msg: to_binary(&Cw20HookMsg::DepositReward {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Staker {
+            address: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+    let stake_info: StakerResponse = from_binary(&res).unwrap();
+    assert_eq!(stake_info.balance, Uint128::new(100));
+
+    // poll fees are transferred to governance before being deposited
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(400, "uusd"));
+    let info = mock_info(TEST_VOTER, &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::DepositReward {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit_native_reward"),
+            attr("amount", "400"),
+        ]
+    );
+
+    assert_eq!(
+        query_rewards(deps.as_ref(), TEST_VOTER),
+        StakerRewardsResponse {
+            pending_reward: Uint128::new(10),
+            pending_native_reward: Uint128::new(100),
+        }
+    );
+
+    let info = mock_info(TEST_VOTER, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ClaimRewards {}).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: VOTING_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: TEST_VOTER.to_string(),
+                    amount: Uint128::from(10u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: TEST_VOTER.to_string(),
+                amount: coins(100, "uusd"),
+            })),
+        ]
+    );
+
+    let info = mock_info(TEST_VOTER, &[]);
+    match execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ClaimRewards {}) {
+        Err(ContractError::NothingToClaim {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    // 10 tokens claimed, the staked balance is still 400 for 400 shares
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(430u128))],
+    )]);
+    let info = mock_info(TEST_VOTER_2, &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::CompoundRewards {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound_rewards"),
+            attr("sender", TEST_VOTER_2),
+            attr("share", "30"),
+            attr("amount", "30"),
+        ]
+    );
+
+    assert_eq!(
+        query_rewards(deps.as_ref(), TEST_VOTER_2),
+        StakerRewardsResponse {
+            pending_reward: Uint128::zero(),
+            pending_native_reward: Uint128::new(300),
+        }
+    );
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Staker {
+            address: TEST_VOTER_2.to_string(),
+        },
+    )
+    .unwrap();
+    let stake_info: StakerResponse = from_binary(&res).unwrap();
+    assert_eq!(stake_info.share, Uint128::new(330));
+    assert_eq!(stake_info.balance, Uint128::new(330));
+}
+
+#[test]
+fn withdraw_after_unbonding_period() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        quorum: None,
+        threshold: None,
+        veto_threshold: None,
+        voting_period: None,
+        timelock_period: None,
+        expiration_period: None,
+        proposal_deposit: None,
+        snapshot_period: None,
+        unbonding_period: Some(100),
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
+    };
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env = mock_env();
+    let release_height = env.block.height + 100;
+    let msg = ExecuteMsg::WithdrawVotingTokens {
+        amount: Some(Uint128::from(40u128)),
+    };
+    let info = mock_info(TEST_VOTER, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw"),
+            attr("recipient", TEST_VOTER),
+            attr("amount", "40"),
+            attr("release_height", release_height.to_string()),
+        ]
+    );
+
+    // unbonding tokens are still held, but no longer staked
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Staker {
+            address: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+    let stake_info: StakerResponse = from_binary(&res).unwrap();
+    assert_eq!(stake_info.balance, Uint128::new(60));
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Unbondings {
+            address: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+    let unbondings: UnbondingsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        unbondings.unbondings,
+        vec![Unbonding {
+            amount: Uint128::new(40),
+            release_height,
+            token: None,
+        }]
+    );
+
+    let info = mock_info(TEST_VOTER, &[]);
+    match execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ClaimUnbonded {},
+    ) {
+        Err(ContractError::NothingToClaim {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    let mut env = env;
+    env.block.height = release_height;
+    let info = mock_info(TEST_VOTER, &[]);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::ClaimUnbonded {}).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: VOTING_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: TEST_VOTER.to_string(),
+                amount: Uint128::from(40u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Unbondings {
+            address: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+    let unbondings: UnbondingsResponse = from_binary(&res).unwrap();
+    assert!(unbondings.unbondings.is_empty());
+}
+
 #[test]
 fn fails_insufficient_funds_staking() {
     let mut deps = mock_dependencies(&[]);
 
-    match stake_voting_tokens(deps.as_mut(), Addr::unchecked(""), Uint128::zero()) {
+    match stake_voting_tokens(
+        deps.as_mut(),
+        mock_env(),
+        Addr::unchecked(""),
+        Uint128::zero(),
+    ) {
         Ok(_) => panic!("Must return error"),
         Err(ContractError::InsufficientFunds {}) => (),
         Err(_) => panic!("Unknown error"),
     }
 }
+
+#[test]
+fn fails_staking_into_inflated_share_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+
+    // the first staker gets a single share
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(1u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // then sends 1_000_000 tokens to the contract, so the share is worth 1_000_001
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(1_000_001u128 + 1_500_000u128),
+        )],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER_2.to_string(),
+        amount: Uint128::from(1_500_000u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(ContractError::StakeTooSmall {}) => {}
+        _ => panic!("Must return stake too small error"),
+    }
+
+    // a stake which loses less than 0.1% to rounding goes through
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(1_000_001u128 + 2_000_000_000u128),
+        )],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER_2.to_string(),
+        amount: Uint128::from(2_000_000_000u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "staking"),
+            attr("sender", TEST_VOTER_2),
+            attr("share", "1999"),
+            attr("amount", "2000000000"),
+        ]
+    );
+
+    // invalid addresses are an error rather than a panic
+    match query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Staker {
+            address: "x".to_string(),
+        },
+    ) {
+        Err(ContractError::Std(StdError::GenericErr { .. })) => {}
+        _ => panic!("Must return generic error"),
+    }
+}
+
+#[test]
+fn fails_staking_paused() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+
+    // only the guardian or governance itself can pause
+    let msg = ExecuteMsg::Pause {
+        operations: Some(vec![PauseOperation::Staking]),
+    };
+    let info = mock_info(TEST_VOTER, &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let msg = ExecuteMsg::Pause {
+        operations: Some(vec![PauseOperation::Staking]),
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => {
+            assert_eq!(msg, "staking is paused")
+        }
+        _ => panic!("Must return paused error"),
+    }
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.pause.operations, vec![PauseOperation::Staking]);
+
+    let msg = ExecuteMsg::Unpause {
+        operations: Some(vec![PauseOperation::Staking]),
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+fn update_stake_assets(
+    deps: DepsMut,
+    stake_assets: Vec<(&str, Decimal)>,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        quorum: None,
+        threshold: None,
+        veto_threshold: None,
+        voting_period: None,
+        timelock_period: None,
+        expiration_period: None,
+        proposal_deposit: None,
+        snapshot_period: None,
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: Some(
+            stake_assets
+                .into_iter()
+                .map(|(token, weight)| StakeAssetMsg {
+                    token: token.to_string(),
+                    weight,
+                })
+                .collect(),
+        ),
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
+    };
+    execute(deps, mock_env(), mock_info(TEST_CREATOR, &[]), msg)
+}
+
+fn stake_msg(sender: &str, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    })
+}
+
+#[test]
+fn stake_assets_vote_by_weight() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+
+    for stake_assets in [
+        vec![(VOTING_TOKEN, Decimal::one())],
+        vec![(LP_TOKEN, Decimal::zero())],
+        vec![
+            (LP_TOKEN, Decimal::one()),
+            (LP_TOKEN, Decimal::percent(200)),
+        ],
+    ] {
+        match update_stake_assets(deps.as_mut(), stake_assets) {
+            Err(ContractError::InvalidStakeAsset {}) => (),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+    update_stake_assets(deps.as_mut(), vec![(LP_TOKEN, Decimal::percent(200))]).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        config.stake_assets,
+        vec![StakeAssetMsg {
+            token: LP_TOKEN.to_string(),
+            weight: Decimal::percent(200),
+        }]
+    );
+
+    // other tokens cannot be staked
+    match execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("other_token", &[]),
+        stake_msg(TEST_VOTER_2, 50),
+    ) {
+        Err(ContractError::Unauthorized {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+    )]);
+    let env = mock_env_height(1, 10000);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING_TOKEN, &[]),
+        stake_msg(TEST_VOTER, 100),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(LP_TOKEN, &[]),
+        stake_msg(TEST_VOTER, 25),
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(LP_TOKEN, &[]),
+        stake_msg(TEST_VOTER_2, 50),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "staking"),
+            attr("sender", TEST_VOTER_2),
+            attr("token", LP_TOKEN),
+            attr("amount", "50"),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Staker {
+            address: TEST_VOTER_2.to_string(),
+        },
+    )
+    .unwrap();
+    let stake_info: StakerResponse = from_binary(&res).unwrap();
+    assert_eq!(stake_info.balance, Uint128::zero());
+    assert_eq!(
+        stake_info.assets,
+        vec![StakerAssetResponse {
+            token: LP_TOKEN.to_string(),
+            amount: Uint128::new(50),
+            weight: Decimal::percent(200),
+        }]
+    );
+    assert_eq!(stake_info.voting_power, Uint128::new(100));
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(100u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    let msg = poll::create_poll_msg("test".to_string(), "test".to_string(), None, None);
+    execute(
+        deps.as_mut(),
+        mock_env_height(2, 10000),
+        mock_info(VOTING_TOKEN, &[]),
+        msg,
+    )
+    .unwrap();
+
+    let env = mock_env_height(3, 10000);
+    let vote = |amount: u128| ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(amount),
+    };
+    match execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_VOTER_2, &[]),
+        vote(101),
+    ) {
+        Err(ContractError::InsufficientStaked {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_VOTER_2, &[]),
+        vote(100),
+    )
+    .unwrap();
+
+    // the assets are part of the stake which can vote
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::Tally { poll_id: 1 }).unwrap();
+    let tally: TallyResponse = from_binary(&res).unwrap();
+    assert_eq!(tally.yes_votes, Uint128::new(100));
+    assert_eq!(tally.staked_amount, Uint128::new(250));
+
+    // the locked balance cannot be withdrawn
+    match execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_VOTER_2, &[]),
+        ExecuteMsg::WithdrawAssetTokens {
+            token: LP_TOKEN.to_string(),
+            amount: Some(Uint128::new(1)),
+        },
+    ) {
+        Err(ContractError::InvalidWithdrawAmount {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    // the assets cover the locked balance before the POL
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_VOTER, &[]),
+        vote(120),
+    )
+    .unwrap();
+    let locked_polls = |deps: Deps| -> LockedPollsResponse {
+        let msg = QueryMsg::LockedPolls {
+            address: TEST_VOTER.to_string(),
+        };
+        from_binary(&query(deps, mock_env_height(3, 10000), msg).unwrap()).unwrap()
+    };
+    assert_eq!(locked_polls(deps.as_ref()).locked_balance, Uint128::new(70));
+    match execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::WithdrawVotingTokens {
+            amount: Some(Uint128::new(31)),
+        },
+    ) {
+        Err(ContractError::InvalidWithdrawAmount {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::WithdrawVotingTokens { amount: None },
+    )
+    .unwrap();
+    assert_eq!(res.attributes.last(), Some(&attr("amount", "30")));
+    assert_eq!(locked_polls(deps.as_ref()).locked_balance, Uint128::new(70));
+}
+
+#[test]
+fn withdraw_stake_asset_after_unbonding_period() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+    update_stake_assets(deps.as_mut(), vec![(LP_TOKEN, Decimal::one())]).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        quorum: None,
+        threshold: None,
+        veto_threshold: None,
+        voting_period: None,
+        timelock_period: None,
+        expiration_period: None,
+        proposal_deposit: None,
+        snapshot_period: None,
+        unbonding_period: Some(100),
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: None,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(LP_TOKEN, &[]),
+        stake_msg(TEST_VOTER, 50),
+    )
+    .unwrap();
+
+    match execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::WithdrawAssetTokens {
+            token: LP_TOKEN.to_string(),
+            amount: Some(Uint128::new(51)),
+        },
+    ) {
+        Err(ContractError::InvalidWithdrawAmount {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    // the removed assets can still be withdrawn
+    update_stake_assets(deps.as_mut(), vec![]).unwrap();
+
+    let env = mock_env();
+    let release_height = env.block.height + 100;
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::WithdrawAssetTokens {
+            token: LP_TOKEN.to_string(),
+            amount: None,
+        },
+    )
+    .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Unbondings {
+            address: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+    let unbondings: UnbondingsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        unbondings.unbondings,
+        vec![Unbonding {
+            amount: Uint128::new(50),
+            release_height,
+            token: Some(Addr::unchecked(LP_TOKEN)),
+        }]
+    );
+
+    let mut env = env;
+    env.block.height = release_height;
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::ClaimUnbonded {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: LP_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: TEST_VOTER.to_string(),
+                amount: Uint128::from(50u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+fn update_max_lock_weeks(deps: DepsMut, max_lock_weeks: u64) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        quorum: None,
+        threshold: None,
+        veto_threshold: None,
+        voting_period: None,
+        timelock_period: None,
+        expiration_period: None,
+        proposal_deposit: None,
+        snapshot_period: None,
+        unbonding_period: None,
+        text_poll_params: None,
+        target_poll_params: None,
+        deposit_period: None,
+        stake_assets: None,
+        max_lock_weeks: Some(max_lock_weeks),
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
+    };
+    execute(deps, mock_env(), mock_info(TEST_CREATOR, &[]), msg)
+}
+
+fn lock_msg(sender: &str, amount: u128, hook: Cw20HookMsg) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&hook).unwrap(),
+    })
+}
+
+fn query_ve_lock(deps: Deps, address: &str, height: u64) -> VeLockResponse {
+    let msg = QueryMsg::VeLock {
+        address: address.to_string(),
+        height: Some(height),
+    };
+    from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+}
+
+fn query_total_ve_power(deps: Deps, height: u64) -> Uint128 {
+    let msg = QueryMsg::TotalVePower {
+        height: Some(height),
+    };
+    let res: TotalVePowerResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+    res.voting_power
+}
+
+#[test]
+fn lock_power_decays_until_unlock() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+    let week = LOCK_WEEK_BLOCKS;
+
+    // locking is disabled by default
+    let env = mock_env_height(week, 10000);
+    let lock = |weeks: u64| Cw20HookMsg::Lock { duration: weeks };
+    match execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING_TOKEN, &[]),
+        lock_msg(TEST_VOTER, 5200, lock(52)),
+    ) {
+        Err(ContractError::LockingDisabled {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+    match update_max_lock_weeks(deps.as_mut(), 209) {
+        Err(ContractError::InvalidLockDuration(208)) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+    update_max_lock_weeks(deps.as_mut(), 104).unwrap();
+
+    for weeks in [0, 105] {
+        match execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(VOTING_TOKEN, &[]),
+            lock_msg(TEST_VOTER, 5200, lock(weeks)),
+        ) {
+            Err(ContractError::InvalidLockDuration(104)) => (),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(VOTING_TOKEN, &[]),
+        lock_msg(TEST_VOTER, 5200, lock(52)),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "lock"),
+            attr("sender", TEST_VOTER),
+            attr("amount", "5200"),
+            attr("end_height", (53 * week).to_string()),
+        ]
+    );
+
+    // the end is rounded down to the start of a week
+    execute(
+        deps.as_mut(),
+        mock_env_height(2 * week + 10, 10000),
+        mock_info(VOTING_TOKEN, &[]),
+        lock_msg(TEST_VOTER_2, 1040, lock(104)),
+    )
+    .unwrap();
+
+    // a lock of 52 weeks votes with its amount, losing its power until it ends
+    let power = |deps: Deps, address: &str, height: u64| {
+        query_ve_lock(deps, address, height).voting_power.u128()
+    };
+    assert_eq!(power(deps.as_ref(), TEST_VOTER, week), 0);
+    assert_eq!(power(deps.as_ref(), TEST_VOTER, 2 * week), 5100);
+    assert_eq!(power(deps.as_ref(), TEST_VOTER_2, 2 * week), 0);
+    assert_eq!(query_total_ve_power(deps.as_ref(), 2 * week).u128(), 5100);
+    assert_eq!(power(deps.as_ref(), TEST_VOTER, 27 * week), 2600);
+    assert_eq!(power(deps.as_ref(), TEST_VOTER_2, 27 * week), 1580);
+    assert_eq!(query_total_ve_power(deps.as_ref(), 27 * week).u128(), 4180);
+    assert_eq!(power(deps.as_ref(), TEST_VOTER, 53 * week), 0);
+    assert_eq!(query_total_ve_power(deps.as_ref(), 53 * week).u128(), 1060);
+    assert_eq!(query_total_ve_power(deps.as_ref(), 106 * week).u128(), 0);
+
+    // the lock can be increased and extended until the longest duration from now
+    let env = mock_env_height(27 * week, 10000);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING_TOKEN, &[]),
+        lock_msg(TEST_VOTER, 1000, Cw20HookMsg::IncreaseLock {}),
+    )
+    .unwrap();
+    match execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::ExtendLock { duration: 79 },
+    ) {
+        Err(ContractError::InvalidLockDuration(104)) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::ExtendLock { duration: 26 },
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_ve_lock(deps.as_ref(), TEST_VOTER, 28 * week),
+        VeLockResponse {
+            amount: Uint128::new(6200),
+            end_height: 79 * week,
+            voting_power: Uint128::new(6080),
+        }
+    );
+    // the earlier heights keep the lock they had
+    assert_eq!(power(deps.as_ref(), TEST_VOTER, 27 * week), 2600);
+    assert_eq!(query_total_ve_power(deps.as_ref(), 27 * week).u128(), 4180);
+    assert_eq!(query_total_ve_power(deps.as_ref(), 28 * week).u128(), 7640);
+    assert_eq!(query_total_ve_power(deps.as_ref(), 79 * week).u128(), 540);
+}
+
+#[test]
+fn locks_vote_in_polls() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+    update_max_lock_weeks(deps.as_mut(), 104).unwrap();
+    let week = LOCK_WEEK_BLOCKS;
+
+    let env = mock_env_height(week - 1, 10000);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING_TOKEN, &[]),
+        lock_msg(TEST_VOTER, 5200, Cw20HookMsg::Lock { duration: 104 }),
+    )
+    .unwrap();
+
+    // the locked POL is not part of the staked balance
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(5300u128))],
+    )]);
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info(VOTING_TOKEN, &[]),
+        stake_msg(TEST_VOTER_2, 100),
+    )
+    .unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(5300u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    let msg = poll::create_poll_msg("test".to_string(), "test".to_string(), None, None);
+    let env = mock_env_height(week, 10000);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING_TOKEN, &[]),
+        msg,
+    )
+    .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Staker {
+            address: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+    let stake_info: StakerResponse = from_binary(&res).unwrap();
+    assert_eq!(stake_info.balance, Uint128::zero());
+    assert_eq!(stake_info.voting_power, Uint128::new(10300));
+
+    // the lock votes with its power when the poll started
+    let env = mock_env_height(week + 10, 10000);
+    let vote = |amount: u128| ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(amount),
+    };
+    match execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_VOTER, &[]),
+        vote(10301),
+    ) {
+        Err(ContractError::InsufficientStaked {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_VOTER, &[]),
+        vote(10300),
+    )
+    .unwrap();
+
+    let res = query(deps.as_ref(), env, QueryMsg::Tally { poll_id: 1 }).unwrap();
+    let tally: TallyResponse = from_binary(&res).unwrap();
+    assert_eq!(tally.yes_votes, Uint128::new(10300));
+    assert_eq!(tally.staked_amount, Uint128::new(10400));
+}
+
+#[test]
+fn withdraw_expired_lock() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+    update_max_lock_weeks(deps.as_mut(), 104).unwrap();
+    let week = LOCK_WEEK_BLOCKS;
+
+    match execute(
+        deps.as_mut(),
+        mock_env_height(week, 10000),
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::ExtendLock { duration: 1 },
+    ) {
+        Err(ContractError::NoLock {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+    execute(
+        deps.as_mut(),
+        mock_env_height(week, 10000),
+        mock_info(VOTING_TOKEN, &[]),
+        lock_msg(TEST_VOTER, 100, Cw20HookMsg::Lock { duration: 1 }),
+    )
+    .unwrap();
+
+    match execute(
+        deps.as_mut(),
+        mock_env_height(2 * week - 1, 10000),
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::WithdrawExpired {},
+    ) {
+        Err(ContractError::LockNotExpired {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    // an ended lock cannot be changed, only withdrawn
+    let env = mock_env_height(2 * week, 10000);
+    for (sender, msg) in [
+        (
+            VOTING_TOKEN,
+            lock_msg(TEST_VOTER, 100, Cw20HookMsg::IncreaseLock {}),
+        ),
+        (TEST_VOTER, ExecuteMsg::ExtendLock { duration: 1 }),
+    ] {
+        match execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg) {
+            Err(ContractError::LockExpired {}) => (),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+    match execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VOTING_TOKEN, &[]),
+        lock_msg(TEST_VOTER, 100, Cw20HookMsg::Lock { duration: 1 }),
+    ) {
+        Err(ContractError::LockExists {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::WithdrawExpired {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: VOTING_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: TEST_VOTER.to_string(),
+                amount: Uint128::from(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    assert_eq!(
+        query_ve_lock(deps.as_ref(), TEST_VOTER, 2 * week + 1),
+        VeLockResponse {
+            amount: Uint128::zero(),
+            end_height: 0,
+            voting_power: Uint128::zero(),
+        }
+    );
+
+    match execute(
+        deps.as_mut(),
+        env,
+        mock_info(TEST_VOTER, &[]),
+        ExecuteMsg::WithdrawExpired {},
+    ) {
+        Err(ContractError::NoLock {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn tally_matches_end_poll_without_staked_pol() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+    update_stake_assets(deps.as_mut(), vec![(LP_TOKEN, Decimal::percent(200))]).unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env_height(1, 10000),
+        mock_info(LP_TOKEN, &[]),
+        stake_msg(TEST_VOTER, 50),
+    )
+    .unwrap();
+
+    // the balance only holds the deposit, no POL is staked
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    let msg = poll::create_poll_msg("test".to_string(), "test".to_string(), None, None);
+    execute(
+        deps.as_mut(),
+        mock_env_height(2, 10000),
+        mock_info(VOTING_TOKEN, &[]),
+        msg,
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::new(100),
+    };
+    execute(
+        deps.as_mut(),
+        mock_env_height(3, 10000),
+        mock_info(TEST_VOTER, &[]),
+        msg,
+    )
+    .unwrap();
+
+    let env = mock_env_height(3 + DEFAULT_VOTING_PERIOD, 10000);
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::Tally { poll_id: 1 }).unwrap();
+    let tally: TallyResponse = from_binary(&res).unwrap();
+    assert_eq!(tally.staked_amount, Uint128::new(100));
+    assert_eq!(tally.quorum, Decimal::one());
+    assert!(tally.passing);
+
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    let res = execute(deps.as_mut(), env, mock_info(TEST_CREATOR, &[]), msg).unwrap();
+    assert!(res.attributes.contains(&attr("passed", "true")));
+}
@@ -2,12 +2,18 @@ use crate::entrypoints::{
     MAX_DESC_LENGTH, MAX_LINK_LENGTH, MAX_TITLE_LENGTH, MIN_DESC_LENGTH, MIN_LINK_LENGTH,
     MIN_TITLE_LENGTH,
 };
-use crate::tests::common::{DEFAULT_EXPIRATION_PERIOD, DEFAULT_TIMELOCK_PERIOD};
+use crate::error::ContractError;
+use crate::tests::common::{
+    DEFAULT_EXPIRATION_PERIOD, DEFAULT_FIX_PERIOD, DEFAULT_TIMELOCK_PERIOD, DEFAULT_VOTING_PERIOD,
+};
 use crate::validators::{
-    validate_decimal_value, validate_poll_description, validate_poll_link, validate_poll_period,
-    validate_poll_title, validate_quorum, validate_threshold,
+    validate_decimal_value, validate_poll_description, validate_poll_link, validate_poll_msg,
+    validate_poll_period, validate_poll_title, validate_quorum, validate_threshold,
 };
-use cosmwasm_std::Decimal;
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{coins, Addr, BankMsg, Binary, CosmosMsg, Decimal, Uint128, WasmMsg};
+use cw0::Duration;
+use messages::governance::state::Config;
 use std::str::FromStr;
 
 /**
@@ -238,6 +244,30 @@ fn valid_poll_description() {
     assert!(valid.is_ok());
 }
 
+fn config_with_periods(timelock_period: Duration, expiration_period: Duration) -> Config {
+    Config {
+        owner: Addr::unchecked(""),
+        pollterra_token: Addr::unchecked(""),
+        quorum: Decimal::zero(),
+        threshold: Decimal::zero(),
+        veto_threshold: Decimal::zero(),
+        voting_period: Duration::Height(DEFAULT_VOTING_PERIOD),
+        timelock_period,
+        expiration_period,
+        proposal_deposit: Uint128::zero(),
+        snapshot_period: Duration::Height(DEFAULT_FIX_PERIOD),
+        unbonding_period: 0,
+        text_poll_params: None,
+        target_poll_params: vec![],
+        deposit_period: None,
+        stake_assets: vec![],
+        max_lock_weeks: 0,
+        meta_contract: None,
+        community_contract: None,
+        distributor_contract: None,
+    }
+}
+
 /**
  * Tests [validate_poll_period] with a valid value, i.e. timelock_period < expiration_period.
  */
@@ -246,7 +276,10 @@ fn valid_poll_period() {
     let timelock_period = DEFAULT_TIMELOCK_PERIOD;
     let expiration_period = DEFAULT_EXPIRATION_PERIOD;
     assert!(expiration_period > timelock_period);
-    let valid = validate_poll_period(timelock_period, expiration_period);
+    let valid = validate_poll_period(&config_with_periods(
+        Duration::Height(timelock_period),
+        Duration::Height(expiration_period),
+    ));
     assert!(valid.is_ok());
 }
 
@@ -255,7 +288,102 @@ fn valid_poll_period() {
  */
 #[test]
 fn invalid_poll_period() {
-    let timelock_period = 20000u64;
-    let expiration_period = 10000u64;
-    validate_poll_period(timelock_period, expiration_period).unwrap_err();
+    let timelock_period = Duration::Height(20000u64);
+    let expiration_period = Duration::Height(10000u64);
+    validate_poll_period(&config_with_periods(timelock_period, expiration_period)).unwrap_err();
+}
+
+/**
+ * Tests [validate_poll_period] with periods measured in both blocks and seconds.
+ */
+#[test]
+fn invalid_poll_period_unit() {
+    let config = config_with_periods(Duration::Time(10000u64), Duration::Time(20000u64));
+    assert_eq!(
+        validate_poll_period(&config),
+        Err(ContractError::InvalidPollPeriodUnit {})
+    );
+
+    let config = Config {
+        voting_period: Duration::Time(DEFAULT_VOTING_PERIOD),
+        snapshot_period: Duration::Time(DEFAULT_FIX_PERIOD),
+        ..config
+    };
+    assert!(validate_poll_period(&config).is_ok());
+}
+
+/**
+ * Tests [validate_poll_msg] with the messages governance can execute.
+ */
+#[test]
+fn valid_poll_msg() {
+    let api = MockApi::default();
+    let msgs: Vec<CosmosMsg> = vec![
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "community".to_string(),
+            amount: coins(100, "uusd"),
+        }),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "distributor".to_string(),
+            msg: Binary::default(),
+            funds: coins(100, "uusd"),
+        }),
+        CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: "distributor".to_string(),
+            new_code_id: 2,
+            msg: Binary::default(),
+        }),
+        CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+            contract_addr: "distributor".to_string(),
+            admin: "governance".to_string(),
+        }),
+    ];
+    for msg in msgs.iter() {
+        assert!(validate_poll_msg(&api, msg).is_ok());
+    }
+}
+
+/**
+ * Tests [validate_poll_msg] with messages governance refuses to execute.
+ */
+#[test]
+fn invalid_poll_msg() {
+    let api = MockApi::default();
+
+    let msg = CosmosMsg::Bank(BankMsg::Send {
+        to_address: "community".to_string(),
+        amount: vec![],
+    });
+    assert_eq!(
+        validate_poll_msg(&api, &msg),
+        Err(ContractError::InvalidPollMsgFunds {})
+    );
+
+    let msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "distributor".to_string(),
+        msg: Binary::default(),
+        funds: coins(0, "uusd"),
+    });
+    assert_eq!(
+        validate_poll_msg(&api, &msg),
+        Err(ContractError::InvalidPollMsgFunds {})
+    );
+
+    let msg = CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+        contract_addr: "distributor".to_string(),
+        admin: "a".to_string(),
+    });
+    assert!(validate_poll_msg(&api, &msg).is_err());
+
+    let msg = CosmosMsg::Wasm(WasmMsg::Instantiate {
+        admin: None,
+        code_id: 1,
+        msg: Binary::default(),
+        funds: vec![],
+        label: "poll".to_string(),
+    });
+    assert_eq!(
+        validate_poll_msg(&api, &msg),
+        Err(ContractError::UnsupportedPollMsg {})
+    );
 }
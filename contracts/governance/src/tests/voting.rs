@@ -1,23 +1,27 @@
 use testutils::mock_querier::mock_dependencies;
 
-use crate::entrypoints::{execute, query};
+use crate::entrypoints::{execute, query, MAX_DELEGATORS};
 use messages::governance::execute_msgs::ExecuteMsg;
 use messages::governance::query_msgs::QueryMsg;
 
 use crate::error::ContractError;
 use crate::tests::common::{
-    DEFAULT_PROPOSAL_DEPOSIT, DEFAULT_VOTING_PERIOD, TEST_CREATOR, TEST_VOTER, VOTING_TOKEN,
+    DEFAULT_PROPOSAL_DEPOSIT, DEFAULT_QUORUM, DEFAULT_THRESHOLD, DEFAULT_VETO_THRESHOLD,
+    DEFAULT_VOTING_PERIOD, TEST_CREATOR, TEST_VOTER, TEST_VOTER_2, VOTING_TOKEN,
 };
 use crate::tests::{common, instantiate, poll};
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    coins, from_binary, to_binary, Api, CanonicalAddr, CosmosMsg, SubMsg, Uint128, WasmMsg,
+    attr, coins, from_binary, to_binary, Addr, CosmosMsg, Decimal, SubMsg, Uint128, WasmMsg,
 };
+use cw0::Expiration;
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::U64Key;
 use messages::governance::state::{
-    bank_read, bank_store, poll_store, poll_voter_read, poll_voter_store, state_read, Cw20HookMsg,
-    OrderBy, Poll, PollStatus, StakerResponse, State, TokenManager, VoteOption, VoterInfo,
-    VotersResponse, VotersResponseItem,
+    polls, Cw20HookMsg, DelegationResponse, LockedPoll, LockedPollsResponse, OrderBy, Poll,
+    PollResponse, PollStatus, StakerResponse, State, TallyResponse, TokenManager, VoteOption,
+    VoterHistoryResponse, VoterHistoryResponseItem, VoterInfo, VotersResponse, VotersResponseItem,
+    POLL_VOTERS, STAKERS, STATE,
 };
 
 #[test]
@@ -87,14 +91,14 @@ fn successful_cast_vote() {
     instantiate::mock_instantiate(deps.as_mut());
     poll::mock_register_voting_token(deps.as_mut());
 
-    let env = common::mock_env_height(0, 10000);
+    let env = common::mock_env_height(1, 10000);
     let info = mock_info(VOTING_TOKEN, &[]);
     let msg = poll::create_poll_msg("test".to_string(), "test".to_string(), None, None);
 
     let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
     poll::assert_create_poll_result(
         1,
-        DEFAULT_VOTING_PERIOD,
+        1 + DEFAULT_VOTING_PERIOD,
         TEST_CREATOR,
         execute_res,
         deps.as_ref(),
@@ -115,7 +119,7 @@ fn successful_cast_vote() {
     });
 
     let info = mock_info(VOTING_TOKEN, &[]);
-    let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let execute_res = execute(deps.as_mut(), common::mock_env_height(0, 10000), info, msg).unwrap();
     poll::assert_stake_tokens_result(
         11,
         DEFAULT_PROPOSAL_DEPOSIT,
@@ -125,7 +129,7 @@ fn successful_cast_vote() {
         deps.as_ref(),
     );
 
-    let env = common::mock_env_height(0, 10000);
+    let env = common::mock_env_height(1, 10000);
     let info = mock_info(TEST_VOTER, &coins(11, VOTING_TOKEN));
     let amount = 10u128;
     let msg = ExecuteMsg::CastVote {
@@ -166,8 +170,11 @@ fn successful_cast_vote() {
                 VoterInfo {
                     vote: VoteOption::Yes,
                     balance: Uint128::from(amount),
+                    delegated: false,
                 }
-            )]
+            )],
+            assets: vec![],
+            voting_power: Uint128::from(22u128),
         }
     );
 
@@ -190,6 +197,7 @@ fn successful_cast_vote() {
             voter: TEST_VOTER.to_string(),
             vote: VoteOption::Yes,
             balance: Uint128::from(amount),
+            delegated: false,
         }]
     );
 
@@ -229,14 +237,20 @@ fn successful_withdraw_voting_tokens() {
     let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     poll::assert_stake_tokens_result(11, 0, 11, 0, execute_res, deps.as_ref());
 
-    let state: State = state_read(deps.as_ref().storage).load().unwrap();
+    let state: State = STATE.load(deps.as_ref().storage).unwrap();
     assert_eq!(
         state,
         State {
-            contract_addr: deps.api.addr_canonicalize(MOCK_CONTRACT_ADDR).unwrap(),
+            contract_addr: Addr::unchecked(MOCK_CONTRACT_ADDR),
             poll_count: 0,
             total_share: Uint128::from(11u128),
             total_deposit: Uint128::zero(),
+            reward_index: Decimal::zero(),
+            pending_reward: Uint128::zero(),
+            native_reward_index: Decimal::zero(),
+            native_reward_balance: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            total_locked: Uint128::zero(),
         }
     );
 
@@ -267,14 +281,21 @@ fn successful_withdraw_voting_tokens() {
         }))
     );
 
-    let state: State = state_read(deps.as_ref().storage).load().unwrap();
+    // 5.5 shares are burnt, rounded up in favor of the remaining stakers
+    let state: State = STATE.load(deps.as_ref().storage).unwrap();
     assert_eq!(
         state,
         State {
-            contract_addr: deps.api.addr_canonicalize(MOCK_CONTRACT_ADDR).unwrap(),
+            contract_addr: Addr::unchecked(MOCK_CONTRACT_ADDR),
             poll_count: 0,
-            total_share: Uint128::from(6u128),
+            total_share: Uint128::from(5u128),
             total_deposit: Uint128::zero(),
+            reward_index: Decimal::zero(),
+            pending_reward: Uint128::zero(),
+            native_reward_index: Decimal::zero(),
+            native_reward_balance: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            total_locked: Uint128::zero(),
         }
     );
 }
@@ -300,14 +321,20 @@ fn successful_withdraw_voting_tokens_all() {
     let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     poll::assert_stake_tokens_result(11, 0, 11, 0, execute_res, deps.as_ref());
 
-    let state: State = state_read(deps.as_ref().storage).load().unwrap();
+    let state: State = STATE.load(deps.as_ref().storage).unwrap();
     assert_eq!(
         state,
         State {
-            contract_addr: deps.api.addr_canonicalize(MOCK_CONTRACT_ADDR).unwrap(),
+            contract_addr: Addr::unchecked(MOCK_CONTRACT_ADDR),
             poll_count: 0,
             total_share: Uint128::from(11u128),
             total_deposit: Uint128::zero(),
+            reward_index: Decimal::zero(),
+            pending_reward: Uint128::zero(),
+            native_reward_index: Decimal::zero(),
+            native_reward_balance: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            total_locked: Uint128::zero(),
         }
     );
 
@@ -336,14 +363,20 @@ fn successful_withdraw_voting_tokens_all() {
         }))
     );
 
-    let state: State = state_read(deps.as_ref().storage).load().unwrap();
+    let state: State = STATE.load(deps.as_ref().storage).unwrap();
     assert_eq!(
         state,
         State {
-            contract_addr: deps.api.addr_canonicalize(MOCK_CONTRACT_ADDR).unwrap(),
+            contract_addr: Addr::unchecked(MOCK_CONTRACT_ADDR),
             poll_count: 0,
             total_share: Uint128::zero(),
             total_deposit: Uint128::zero(),
+            reward_index: Decimal::zero(),
+            pending_reward: Uint128::zero(),
+            native_reward_index: Decimal::zero(),
+            native_reward_balance: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            total_locked: Uint128::zero(),
         }
     );
 }
@@ -370,16 +403,20 @@ fn withdraw_voting_tokens_remove_not_in_progress_poll_voter_info() {
     poll::assert_stake_tokens_result(11, 0, 11, 0, execute_res, deps.as_ref());
 
     // make fake polls; one in progress & one in passed
-    poll_store(&mut deps.storage)
+    polls()
         .save(
-            &1u64.to_be_bytes(),
+            &mut deps.storage,
+            U64Key::new(1),
             &Poll {
                 id: 1u64,
-                creator: CanonicalAddr::from(vec![]),
+                creator: Addr::unchecked(""),
                 status: PollStatus::InProgress,
                 yes_votes: Uint128::zero(),
                 no_votes: Uint128::zero(),
-                end_height: 0u64,
+                abstain_votes: Uint128::zero(),
+                veto_votes: Uint128::zero(),
+                start_height: 0u64,
+                end: Expiration::AtHeight(0u64),
                 title: "title".to_string(),
                 description: "description".to_string(),
                 deposit_amount: Uint128::zero(),
@@ -387,20 +424,26 @@ fn withdraw_voting_tokens_remove_not_in_progress_poll_voter_info() {
                 execute_data: None,
                 total_balance_at_end_poll: None,
                 staked_amount: None,
+                error: None,
+                deposits: vec![],
             },
         )
         .unwrap();
 
-    poll_store(&mut deps.storage)
+    polls()
         .save(
-            &2u64.to_be_bytes(),
+            &mut deps.storage,
+            U64Key::new(2),
             &Poll {
                 id: 1u64,
-                creator: CanonicalAddr::from(vec![]),
+                creator: Addr::unchecked(""),
                 status: PollStatus::Passed,
                 yes_votes: Uint128::zero(),
                 no_votes: Uint128::zero(),
-                end_height: 0u64,
+                abstain_votes: Uint128::zero(),
+                veto_votes: Uint128::zero(),
+                start_height: 0u64,
+                end: Expiration::AtHeight(0u64),
                 title: "title".to_string(),
                 description: "description".to_string(),
                 deposit_amount: Uint128::zero(),
@@ -408,32 +451,39 @@ fn withdraw_voting_tokens_remove_not_in_progress_poll_voter_info() {
                 execute_data: None,
                 total_balance_at_end_poll: None,
                 staked_amount: None,
+                error: None,
+                deposits: vec![],
             },
         )
         .unwrap();
 
-    let voter_addr_raw = deps.api.addr_canonicalize(TEST_VOTER).unwrap();
-    poll_voter_store(&mut deps.storage, 1u64)
+    let voter_addr = Addr::unchecked(TEST_VOTER);
+    POLL_VOTERS
         .save(
-            voter_addr_raw.as_slice(),
+            &mut deps.storage,
+            (U64Key::new(1), &voter_addr),
             &VoterInfo {
                 vote: VoteOption::Yes,
                 balance: Uint128::from(5u128),
+                delegated: false,
             },
         )
         .unwrap();
-    poll_voter_store(&mut deps.storage, 2u64)
+    POLL_VOTERS
         .save(
-            voter_addr_raw.as_slice(),
+            &mut deps.storage,
+            (U64Key::new(2), &voter_addr),
             &VoterInfo {
                 vote: VoteOption::Yes,
                 balance: Uint128::from(5u128),
+                delegated: false,
             },
         )
         .unwrap();
-    bank_store(&mut deps.storage)
+    STAKERS
         .save(
-            voter_addr_raw.as_slice(),
+            &mut deps.storage,
+            &voter_addr,
             &TokenManager {
                 share: Uint128::from(11u128),
                 locked_balance: vec![
@@ -442,6 +492,7 @@ fn withdraw_voting_tokens_remove_not_in_progress_poll_voter_info() {
                         VoterInfo {
                             vote: VoteOption::Yes,
                             balance: Uint128::from(5u128),
+                            delegated: false,
                         },
                     ),
                     (
@@ -449,9 +500,11 @@ fn withdraw_voting_tokens_remove_not_in_progress_poll_voter_info() {
                         VoterInfo {
                             vote: VoteOption::Yes,
                             balance: Uint128::from(5u128),
+                            delegated: false,
                         },
                     ),
                 ],
+                ..TokenManager::default()
             },
         )
         .unwrap();
@@ -463,23 +516,22 @@ fn withdraw_voting_tokens_remove_not_in_progress_poll_voter_info() {
     };
 
     let _ = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    let voter = poll_voter_read(&deps.storage, 1u64)
-        .load(voter_addr_raw.as_slice())
+    let voter = POLL_VOTERS
+        .load(&deps.storage, (U64Key::new(1), &voter_addr))
         .unwrap();
     assert_eq!(
         voter,
         VoterInfo {
             vote: VoteOption::Yes,
             balance: Uint128::from(5u128),
+            delegated: false,
         }
     );
-    assert!(poll_voter_read(&deps.storage, 2u64)
-        .load(voter_addr_raw.as_slice())
+    assert!(POLL_VOTERS
+        .load(&deps.storage, (U64Key::new(2), &voter_addr))
         .is_err(),);
 
-    let token_manager = bank_read(&deps.storage)
-        .load(voter_addr_raw.as_slice())
-        .unwrap();
+    let token_manager = STAKERS.load(&deps.storage, &voter_addr).unwrap();
     assert_eq!(
         token_manager.locked_balance,
         vec![(
@@ -487,6 +539,7 @@ fn withdraw_voting_tokens_remove_not_in_progress_poll_voter_info() {
             VoterInfo {
                 vote: VoteOption::Yes,
                 balance: Uint128::from(5u128),
+                delegated: false,
             }
         )]
     );
@@ -548,12 +601,12 @@ fn fails_withdraw_too_many_tokens() {
 }
 
 #[test]
-fn fails_cast_vote_twice() {
+fn successful_change_vote() {
     let mut deps = mock_dependencies(&[]);
     instantiate::mock_instantiate(deps.as_mut());
     poll::mock_register_voting_token(deps.as_mut());
 
-    let env = common::mock_env_height(0, 10000);
+    let env = common::mock_env_height(1, 10000);
     let info = mock_info(VOTING_TOKEN, &coins(2, VOTING_TOKEN));
 
     let msg = poll::create_poll_msg("test".to_string(), "test".to_string(), None, None);
@@ -582,7 +635,7 @@ fn fails_cast_vote_twice() {
     });
 
     let info = mock_info(VOTING_TOKEN, &[]);
-    let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let execute_res = execute(deps.as_mut(), common::mock_env_height(0, 10000), info, msg).unwrap();
     poll::assert_stake_tokens_result(
         11,
         DEFAULT_PROPOSAL_DEPOSIT,
@@ -598,25 +651,299 @@ fn fails_cast_vote_twice() {
         vote: VoteOption::Yes,
         amount: Uint128::from(amount),
     };
-    let env = common::mock_env_height(0, 10000);
+    let env = common::mock_env_height(1, 10000);
     let info = mock_info(TEST_VOTER, &[]);
     let execute_res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
     poll::assert_cast_vote_success(TEST_VOTER, amount, 1, VoteOption::Yes, execute_res);
 
+    // the second vote replaces the first one
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::No,
+        amount: Uint128::from(10u128),
+    };
+    let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
+    poll::assert_cast_vote_success(TEST_VOTER, 10, 1, VoteOption::No, execute_res);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+    let value: PollResponse = from_binary(&res).unwrap();
+    assert_eq!(Uint128::zero(), value.yes_votes);
+    assert_eq!(Uint128::from(10u128), value.no_votes);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Staker {
+            address: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+    let response: StakerResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.locked_balance,
+        vec![(
+            1u64,
+            VoterInfo {
+                vote: VoteOption::No,
+                balance: Uint128::from(10u128),
+                delegated: false,
+            }
+        )]
+    );
+}
+
+#[test]
+fn moved_stake_cannot_vote_twice() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(11u128))],
+    )]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER.to_string(),
+        amount: Uint128::from(11u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(
+        deps.as_mut(),
+        common::mock_env_before(&mock_env()),
+        info,
+        msg,
+    )
+    .unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(11u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    let msg = poll::create_poll_msg("test".to_string(), "test".to_string(), None, None);
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the tokens are withdrawn and staked again by another voter
+    let msg = ExecuteMsg::WithdrawVotingTokens { amount: None };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info(TEST_VOTER, &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: TEST_VOTER_2.to_string(),
+        amount: Uint128::from(11u128),
+        msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+    });
+    let info = mock_info(VOTING_TOKEN, &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
     let msg = ExecuteMsg::CastVote {
         poll_id: 1,
         vote: VoteOption::Yes,
-        amount: Uint128::from(amount),
+        amount: Uint128::from(11u128),
     };
-    let res = execute(deps.as_mut(), env, info, msg);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_VOTER_2, &[]),
+        msg.clone(),
+    );
+    assert_eq!(res.unwrap_err(), ContractError::InsufficientStaked {});
 
-    match res {
-        Ok(_) => panic!("Must return error"),
-        Err(ContractError::AlreadyVoted {}) => (),
-        Err(e) => panic!("Unexpected error: {:?}", e),
+    // the voting power stays with the share staked when the poll started
+    let execute_res = execute(deps.as_mut(), mock_env(), mock_info(TEST_VOTER, &[]), msg).unwrap();
+    poll::assert_cast_vote_success(TEST_VOTER, 11, 1, VoteOption::Yes, execute_res);
+}
+
+#[test]
+fn successful_delegated_vote() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+
+    let msg = poll::create_poll_msg("test".to_string(), "test".to_string(), None, None);
+    let info = mock_info(VOTING_TOKEN, &coins(2, VOTING_TOKEN));
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(30u128 + DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    for (staker, amount) in [(TEST_VOTER, 10u128), (TEST_VOTER_2, 20u128)] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: staker.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+        });
+        let info = mock_info(VOTING_TOKEN, &[]);
+        let _res = execute(
+            deps.as_mut(),
+            common::mock_env_before(&mock_env()),
+            info,
+            msg,
+        )
+        .unwrap();
+    }
+
+    // cannot delegate without stake
+    let msg = ExecuteMsg::Delegate {
+        delegate: TEST_VOTER.to_string(),
+    };
+    match execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg) {
+        Err(ContractError::NothingStaked {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    // cannot delegate to itself
+    let info = mock_info(TEST_VOTER_2, &[]);
+    let msg = ExecuteMsg::Delegate {
+        delegate: TEST_VOTER_2.to_string(),
+    };
+    match execute(deps.as_mut(), mock_env(), info.clone(), msg) {
+        Err(ContractError::CannotDelegateToSelf {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    let msg = ExecuteMsg::Delegate {
+        delegate: TEST_VOTER.to_string(),
+    };
+    let execute_res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        execute_res.attributes,
+        vec![
+            attr("action", "delegate"),
+            attr("delegator", TEST_VOTER_2),
+            attr("delegate", TEST_VOTER),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Delegation {
+            address: TEST_VOTER.to_string(),
+        },
+    )
+    .unwrap();
+    let response: DelegationResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response,
+        DelegationResponse {
+            delegate: None,
+            delegators: vec![TEST_VOTER_2.to_string()],
+        }
+    );
+
+    // the delegate votes with the full stake of the delegator
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::Yes,
+        amount: Uint128::from(10u128),
+    };
+    let execute_res = execute(deps.as_mut(), mock_env(), mock_info(TEST_VOTER, &[]), msg).unwrap();
+    assert_eq!(
+        execute_res.attributes.last(),
+        Some(&attr("num_delegators", "1"))
+    );
+    let delegated = POLL_VOTERS
+        .load(
+            &deps.storage,
+            (U64Key::new(1), &Addr::unchecked(TEST_VOTER_2)),
+        )
+        .unwrap();
+    assert_eq!(
+        delegated,
+        VoterInfo {
+            vote: VoteOption::Yes,
+            balance: Uint128::from(20u128),
+            delegated: true,
+        }
+    );
+
+    // the delegator overrides the vote of its delegate
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1,
+        vote: VoteOption::No,
+        amount: Uint128::from(5u128),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info(TEST_VOTER_2, &[]), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+    let value: PollResponse = from_binary(&res).unwrap();
+    assert_eq!(Uint128::from(10u128), value.yes_votes);
+    assert_eq!(Uint128::from(5u128), value.no_votes);
+
+    let msg = ExecuteMsg::Undelegate {};
+    let _res = execute(deps.as_mut(), mock_env(), mock_info(TEST_VOTER_2, &[]), msg).unwrap();
+    let msg = ExecuteMsg::Undelegate {};
+    match execute(deps.as_mut(), mock_env(), mock_info(TEST_VOTER_2, &[]), msg) {
+        Err(ContractError::NoDelegation {}) => (),
+        res => panic!("Unexpected result: {:?}", res),
     }
 }
 
+#[test]
+fn fails_delegate_to_full_delegate() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+
+    let delegators = (0..=MAX_DELEGATORS)
+        .map(|i| format!("delegator{}", i))
+        .collect::<Vec<String>>();
+    for (i, delegator) in delegators.iter().enumerate() {
+        deps.querier.with_token_balances(&[(
+            &VOTING_TOKEN.to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(i as u128 + 1),
+            )],
+        )]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: delegator.to_string(),
+            amount: Uint128::from(1u128),
+            msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), mock_env(), mock_info(VOTING_TOKEN, &[]), msg).unwrap();
+    }
+
+    for delegator in delegators[..MAX_DELEGATORS].iter() {
+        let msg = ExecuteMsg::Delegate {
+            delegate: TEST_VOTER.to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info(delegator, &[]), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::Delegate {
+        delegate: TEST_VOTER.to_string(),
+    };
+    let info = mock_info(&delegators[MAX_DELEGATORS], &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(ContractError::TooManyDelegators(max)) => assert_eq!(max, MAX_DELEGATORS),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    // a delegator leaving frees its place
+    let msg = ExecuteMsg::Undelegate {};
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(&delegators[0], &[]),
+        msg,
+    )
+    .unwrap();
+    let msg = ExecuteMsg::Delegate {
+        delegate: TEST_VOTER.to_string(),
+    };
+    let info = mock_info(&delegators[MAX_DELEGATORS], &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
 #[test]
 fn fails_cast_vote_without_poll() {
     let mut deps = mock_dependencies(&[]);
@@ -715,3 +1042,168 @@ fn fails_staking_wrong_token() {
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
 }
+
+#[test]
+fn voter_history_tally_and_locked_polls() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate::mock_instantiate(deps.as_mut());
+    poll::mock_register_voting_token(deps.as_mut());
+
+    let poll_env = mock_env();
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(150u128))],
+    )]);
+    for (voter, amount) in [(TEST_VOTER, 100u128), (TEST_VOTER_2, 50u128)] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: voter.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::StakeVotingTokens {}).unwrap(),
+        });
+        let info = mock_info(VOTING_TOKEN, &[]);
+        execute(deps.as_mut(), common::mock_env_before(&poll_env), info, msg).unwrap();
+    }
+
+    deps.querier.with_token_balances(&[(
+        &VOTING_TOKEN.to_string(),
+        &[(
+            &MOCK_CONTRACT_ADDR.to_string(),
+            &Uint128::from(150u128 + 2 * DEFAULT_PROPOSAL_DEPOSIT),
+        )],
+    )]);
+    for _ in 0..2 {
+        let msg = poll::create_poll_msg("test".to_string(), "test".to_string(), None, None);
+        let info = mock_info(VOTING_TOKEN, &[]);
+        execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+    }
+
+    for (voter, poll_id, vote, amount) in [
+        (TEST_VOTER, 1u64, VoteOption::Yes, 100u128),
+        (TEST_VOTER, 2u64, VoteOption::No, 60u128),
+        (TEST_VOTER_2, 1u64, VoteOption::Abstain, 50u128),
+    ] {
+        let msg = ExecuteMsg::CastVote {
+            poll_id,
+            vote,
+            amount: Uint128::from(amount),
+        };
+        let info = mock_info(voter, &[]);
+        execute(deps.as_mut(), poll_env.clone(), info, msg).unwrap();
+    }
+
+    let res = query(
+        deps.as_ref(),
+        poll_env.clone(),
+        QueryMsg::Tally { poll_id: 1 },
+    )
+    .unwrap();
+    let tally: TallyResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        tally,
+        TallyResponse {
+            poll_id: 1,
+            status: PollStatus::InProgress,
+            yes_votes: Uint128::from(100u128),
+            no_votes: Uint128::zero(),
+            abstain_votes: Uint128::from(50u128),
+            veto_votes: Uint128::zero(),
+            staked_amount: Uint128::from(150u128),
+            quorum: Decimal::one(),
+            threshold: Decimal::one(),
+            veto: Decimal::zero(),
+            required_quorum: Decimal::percent(DEFAULT_QUORUM),
+            required_threshold: Decimal::percent(DEFAULT_THRESHOLD),
+            veto_threshold: Decimal::percent(DEFAULT_VETO_THRESHOLD),
+            passing: true,
+        }
+    );
+
+    let end = Expiration::AtHeight(poll_env.block.height + DEFAULT_VOTING_PERIOD);
+    let locked_polls_msg = QueryMsg::LockedPolls {
+        address: TEST_VOTER.to_string(),
+    };
+    let res = query(deps.as_ref(), poll_env.clone(), locked_polls_msg.clone()).unwrap();
+    let locked: LockedPollsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        locked,
+        LockedPollsResponse {
+            locked_balance: Uint128::from(100u128),
+            polls: vec![
+                LockedPoll {
+                    poll_id: 1,
+                    end,
+                    vote: VoteOption::Yes,
+                    balance: Uint128::from(100u128),
+                },
+                LockedPoll {
+                    poll_id: 2,
+                    end,
+                    vote: VoteOption::No,
+                    balance: Uint128::from(60u128),
+                },
+            ],
+        }
+    );
+
+    let mut env = poll_env;
+    env.block.height += DEFAULT_VOTING_PERIOD;
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // the ended poll does not lock the stake anymore, but stays in the history
+    let res = query(deps.as_ref(), env.clone(), locked_polls_msg).unwrap();
+    let locked: LockedPollsResponse = from_binary(&res).unwrap();
+    assert_eq!(Uint128::from(60u128), locked.locked_balance);
+    assert_eq!(1, locked.polls.len());
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::VoterHistory {
+            address: TEST_VOTER.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let history: VoterHistoryResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        history.votes,
+        vec![
+            VoterHistoryResponseItem {
+                poll_id: 2,
+                status: PollStatus::InProgress,
+                vote: VoteOption::No,
+                balance: Uint128::from(60u128),
+                delegated: false,
+            },
+            VoterHistoryResponseItem {
+                poll_id: 1,
+                status: PollStatus::Passed,
+                vote: VoteOption::Yes,
+                balance: Uint128::from(100u128),
+                delegated: false,
+            },
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::VoterHistory {
+            address: TEST_VOTER.to_string(),
+            start_after: Some(2),
+            limit: None,
+        },
+    )
+    .unwrap();
+    let history: VoterHistoryResponse = from_binary(&res).unwrap();
+    assert_eq!(1, history.votes.len());
+    assert_eq!(1, history.votes[0].poll_id);
+
+    let res = query(deps.as_ref(), env, QueryMsg::Tally { poll_id: 1 }).unwrap();
+    let tally: TallyResponse = from_binary(&res).unwrap();
+    assert_eq!(PollStatus::Passed, tally.status);
+    assert_eq!(Uint128::from(150u128), tally.staked_amount);
+}
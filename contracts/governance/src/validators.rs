@@ -1,12 +1,15 @@
 use core::result::Result::{Err, Ok};
 
-use cosmwasm_std::{Decimal, StdError, StdResult};
+use cosmwasm_std::{Addr, Api, BankMsg, CosmosMsg, Decimal, StdError, StdResult, WasmMsg};
+use cw0::Duration;
 
 use crate::entrypoints::{
-    MAX_DESC_LENGTH, MAX_LINK_LENGTH, MAX_QUORUM, MAX_THRESHOLD, MAX_TITLE_LENGTH, MIN_DESC_LENGTH,
-    MIN_LINK_LENGTH, MIN_TITLE_LENGTH,
+    MAX_DESC_LENGTH, MAX_LINK_LENGTH, MAX_LOCK_WEEKS, MAX_QUORUM, MAX_THRESHOLD, MAX_TITLE_LENGTH,
+    MAX_VETO_THRESHOLD, MIN_DESC_LENGTH, MIN_LINK_LENGTH, MIN_TITLE_LENGTH,
 };
 use crate::error::ContractError;
+use messages::governance::param_change::{ParamChange, ParamChangeMsg};
+use messages::governance::state::{Config, PollParams};
 
 /**
  * Validates that the provided [Decimal] value is in between [0,max_value].
@@ -43,6 +46,52 @@ pub fn validate_threshold(threshold: Decimal) -> Result<(), ContractError> {
     }
 }
 
+pub fn validate_poll_params(params: &PollParams) -> Result<(), ContractError> {
+    validate_quorum(params.quorum)?;
+    validate_threshold(params.threshold)
+}
+
+/**
+ * Validates that the stake assets are distinct tokens other than POL, voting with a positive weight.
+ */
+pub fn validate_stake_assets(config: &Config) -> Result<(), ContractError> {
+    for (i, asset) in config.stake_assets.iter().enumerate() {
+        if asset.weight.is_zero()
+            || asset.token == config.pollterra_token
+            || config.stake_assets[..i]
+                .iter()
+                .any(|other| other.token == asset.token)
+        {
+            return Err(ContractError::InvalidStakeAsset {});
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * Validates the longest duration POL can be locked for, zero disables locking.
+ */
+pub fn validate_max_lock_weeks(max_lock_weeks: u64) -> Result<(), ContractError> {
+    if max_lock_weeks > MAX_LOCK_WEEKS {
+        return Err(ContractError::InvalidLockDuration(MAX_LOCK_WEEKS));
+    }
+
+    Ok(())
+}
+
+/**
+ * Validates the veto threshold parameter used to instantiate the contract. It should be between [0,1].
+ */
+pub fn validate_veto_threshold(veto_threshold: Decimal) -> Result<(), ContractError> {
+    match validate_decimal_value(veto_threshold, MAX_VETO_THRESHOLD) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(ContractError::PollVetoThresholdInvalidValue(
+            MAX_VETO_THRESHOLD.to_string(),
+        )),
+    }
+}
+
 /**
  * Validates that the link is valid when creating a poll.
  */
@@ -87,16 +136,193 @@ pub fn validate_poll_description(description: &str) -> Result<(), ContractError>
 }
 
 /**
- * Validates both the expiration_period and timelock_period so that the poll is valid,
- * i.e. it's not expired before it is executed.
+ * Validates that a message of the poll can be executed by governance, i.e. it is a bank send
+ * or a wasm execution, migration or admin update of valid addresses which sends no zero coins.
  */
-pub fn validate_poll_period(
-    timelock_period: u64,
-    expiration_period: u64,
+pub fn validate_poll_msg(api: &dyn Api, msg: &CosmosMsg) -> Result<(), ContractError> {
+    match msg {
+        CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+            api.addr_validate(to_address)?;
+            if amount.is_empty() || amount.iter().any(|coin| coin.amount.is_zero()) {
+                return Err(ContractError::InvalidPollMsgFunds {});
+            }
+        }
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            funds,
+            ..
+        }) => {
+            api.addr_validate(contract_addr)?;
+            if funds.iter().any(|coin| coin.amount.is_zero()) {
+                return Err(ContractError::InvalidPollMsgFunds {});
+            }
+        }
+        CosmosMsg::Wasm(WasmMsg::Migrate { contract_addr, .. })
+        | CosmosMsg::Wasm(WasmMsg::ClearAdmin { contract_addr }) => {
+            api.addr_validate(contract_addr)?;
+        }
+        CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+            contract_addr,
+            admin,
+        }) => {
+            api.addr_validate(contract_addr)?;
+            api.addr_validate(admin)?;
+        }
+        _ => return Err(ContractError::UnsupportedPollMsg {}),
+    }
+
+    Ok(())
+}
+
+/**
+ * Validates the target and the values of a param change before its poll is created,
+ * the periods of governance are validated together when the change is executed.
+ */
+pub fn validate_param_change(
+    api: &dyn Api,
+    config: &Config,
+    contract: &Addr,
+    change: &ParamChangeMsg,
+) -> Result<(), ContractError> {
+    let target = api.addr_validate(&change.target)?;
+    let known_target = match &change.params {
+        ParamChange::Governance { .. } => Some(contract),
+        ParamChange::MetaContract { .. } | ParamChange::MetaContractRole { .. } => {
+            config.meta_contract.as_ref()
+        }
+        ParamChange::Community { .. } => config.community_contract.as_ref(),
+        ParamChange::Distributor { .. } => config.distributor_contract.as_ref(),
+    };
+    if known_target != Some(&target) {
+        return Err(ContractError::InvalidParamChangeTarget {});
+    }
+
+    match &change.params {
+        ParamChange::Governance {
+            owner,
+            quorum,
+            threshold,
+            veto_threshold,
+            text_poll_params,
+            target_poll_params,
+            stake_assets,
+            max_lock_weeks,
+            meta_contract,
+            community_contract,
+            distributor_contract,
+            ..
+        } => {
+            for address in [
+                owner,
+                meta_contract,
+                community_contract,
+                distributor_contract,
+            ]
+            .iter()
+            .copied()
+            .flatten()
+            {
+                api.addr_validate(address)?;
+            }
+            if let Some(quorum) = quorum {
+                validate_quorum(*quorum)?;
+            }
+            if let Some(threshold) = threshold {
+                validate_threshold(*threshold)?;
+            }
+            if let Some(veto_threshold) = veto_threshold {
+                validate_veto_threshold(*veto_threshold)?;
+            }
+            if let Some(params) = text_poll_params {
+                validate_poll_params(params)?;
+            }
+            for target in target_poll_params.iter().flatten() {
+                api.addr_validate(&target.contract)?;
+                validate_poll_params(&target.params)?;
+            }
+            for asset in stake_assets.iter().flatten() {
+                api.addr_validate(&asset.token)?;
+                if asset.weight.is_zero() {
+                    return Err(ContractError::InvalidStakeAsset {});
+                }
+            }
+            if let Some(max_lock_weeks) = max_lock_weeks {
+                validate_max_lock_weeks(*max_lock_weeks)?;
+            }
+        }
+        ParamChange::MetaContract {
+            community_contract,
+            deposit_split,
+            ..
+        } => {
+            // the meta-contract only sends deposits to the community contract known to governance
+            if let Some(community_contract) = community_contract {
+                if config.community_contract.as_ref()
+                    != Some(&api.addr_validate(community_contract)?)
+                {
+                    return Err(ContractError::UnknownCommunityContract {});
+                }
+            }
+            if let Some(deposit_split) = deposit_split {
+                if !deposit_split.is_valid()
+                    || (config.community_contract.is_none() && !deposit_split.community.is_zero())
+                {
+                    return Err(ContractError::InvalidDepositSplit {});
+                }
+            }
+        }
+        ParamChange::MetaContractRole { address, .. } => {
+            api.addr_validate(address)?;
+        }
+        ParamChange::Community { admins } | ParamChange::Distributor { admins } => {
+            for admin in admins {
+                api.addr_validate(admin)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Validates that the unit of the poll periods is kept while live polls compare their end to them.
+ */
+pub fn validate_poll_period_unit(
+    previous: &Config,
+    config: &Config,
+    live_polls: bool,
 ) -> Result<(), ContractError> {
-    if expiration_period < timelock_period {
-        return Err(ContractError::InvalidPollPeriod {});
+    let in_blocks = |config: &Config| matches!(config.voting_period, Duration::Height(_));
+    if live_polls && in_blocks(previous) != in_blocks(config) {
+        return Err(ContractError::PollPeriodUnitInUse {});
     }
 
     Ok(())
 }
+
+/**
+ * Validates the poll periods so that the poll is valid, i.e. they are all measured in the same unit
+ * and it's not expired before it is executed.
+ */
+pub fn validate_poll_period(config: &Config) -> Result<(), ContractError> {
+    let in_blocks = |period: &Duration| matches!(period, Duration::Height(_));
+    if [
+        config.timelock_period,
+        config.expiration_period,
+        config.snapshot_period,
+    ]
+    .iter()
+    .any(|period| in_blocks(period) != in_blocks(&config.voting_period))
+    {
+        return Err(ContractError::InvalidPollPeriodUnit {});
+    }
+
+    match (config.timelock_period, config.expiration_period) {
+        (Duration::Height(timelock), Duration::Height(expiration))
+        | (Duration::Time(timelock), Duration::Time(expiration))
+            if expiration < timelock =>
+        {
+            Err(ContractError::InvalidPollPeriod {})
+        }
+        _ => Ok(()),
+    }
+}
@@ -25,4 +25,7 @@ fn main() {
     export_schema(&schema_for!(ContractsResponse), &out_dir);
     export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(StateResponse), &out_dir);
+    export_schema(&schema_for!(RolesResponse), &out_dir);
+    export_schema(&schema_for!(ResolutionResponse), &out_dir);
+    export_schema(&schema_for!(PollMigrationResponse), &out_dir);
 }
@@ -1,6 +1,6 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+    to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
     Uint128,
 };
 use cw2::set_contract_version;
@@ -8,17 +8,23 @@ use cw2::set_contract_version;
 use std::str;
 
 use crate::error::ContractError;
-use crate::{executions, queries, replies};
+use crate::{executions, migrations, queries, replies};
+use config::config::DepositSplit;
 use messages::meta_contract::execute_msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg};
 use messages::meta_contract::query_msgs::QueryMsg;
-use messages::meta_contract::state::{Config, State};
+use messages::meta_contract::state::{Config, Role, State};
+use messages::migration::{migrate_version, Version};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:meta-contract";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-// reply_id is only one for now
-pub const INSTANTIATE_REPLY_ID: u64 = 1;
+pub const MIGRATE_POLL_REPLY_ID: u64 = 2;
+
+// every poll creation gets its own reply_id, allocated sequentially from this base
+pub const INSTANTIATE_REPLY_ID_BASE: u64 = 1 << 32;
+// every poll finished by `FinishExpired` replies with its index in the batch, offset from this base
+pub const FINISH_POLL_REPLY_ID_BASE: u64 = 2 << 32;
 
 const DEFAULT_RECLAIMABLE_THRESHOLD: Uint128 = Uint128::new(1_000);
 
@@ -29,17 +35,21 @@ pub fn instantiate(
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    for admin in msg.admins.iter() {
+        let admin = deps.api.addr_validate(admin)?;
+        for role in Role::all() {
+            role.grant(deps.storage, &admin)?;
+        }
+    }
+
     Config {
-        admins: msg
-            .admins
-            .iter()
-            .map(|v| deps.api.addr_validate(v))
-            .collect::<StdResult<Vec<Addr>>>()?,
         token_contract: String::new(),
         creation_deposit: Uint128::zero(),
         reclaimable_threshold: DEFAULT_RECLAIMABLE_THRESHOLD,
         minimum_bet_amount: Uint128::from(1_000u128),
         tax_percentage: Decimal::percent(5),
+        community_contract: None,
+        deposit_split: DepositSplit::burn_all(),
     }
     .save(deps.storage)?;
 
@@ -74,34 +84,81 @@ pub fn execute(
             poll_type,
             winner,
         } => executions::finish_poll(deps, info, poll_contract, poll_type, winner, true),
+        ExecuteMsg::FinishPolls { entries } => executions::finish_polls(deps, info, entries),
+        ExecuteMsg::FinishExpired { limit } => executions::finish_expired(deps, env, info, limit),
+        ExecuteMsg::MigratePolls {
+            code_id,
+            msg,
+            filter,
+            limit,
+        } => executions::migrate_polls(deps, info, code_id, msg, filter, limit),
         ExecuteMsg::Transfer { recipient, amount } => {
             executions::transfer(deps, env, info, recipient, amount)
         }
         ExecuteMsg::UpdateConfig {
             creation_deposit,
             reclaimable_threshold,
-            new_admins,
+            community_contract,
+            deposit_split,
         } => executions::update_config(
             deps,
             info,
             creation_deposit,
             reclaimable_threshold,
-            new_admins,
+            community_contract,
+            deposit_split,
         ),
+        ExecuteMsg::GrantRole { address, role } => {
+            executions::grant_role(deps, info, address, role)
+        }
+        ExecuteMsg::RevokeRole { address, role } => {
+            executions::revoke_role(deps, info, address, role)
+        }
+        ExecuteMsg::Pause { operations } => executions::pause(deps, info, true, operations),
+        ExecuteMsg::Unpause { operations } => executions::pause(deps, info, false, operations),
+        ExecuteMsg::UpdatePausers {
+            guardian,
+            governance,
+        } => executions::update_pausers(deps, info, guardian, governance),
+        ExecuteMsg::PausePolls {
+            poll_contracts,
+            operations,
+        } => executions::pause_polls(deps, info, poll_contracts, true, operations),
+        ExecuteMsg::UnpausePolls {
+            poll_contracts,
+            operations,
+        } => executions::pause_polls(deps, info, poll_contracts, false, operations),
+        ExecuteMsg::UpdatePollPausers { poll_contracts } => {
+            executions::update_poll_pausers(deps, info, poll_contracts)
+        }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg.id {
-        INSTANTIATE_REPLY_ID => replies::after_poll_init(deps, msg),
+        MIGRATE_POLL_REPLY_ID => replies::after_poll_migrate(deps, msg),
+        id if id >= FINISH_POLL_REPLY_ID_BASE => replies::after_poll_finish(deps, env, msg),
+        id if id > INSTANTIATE_REPLY_ID_BASE => replies::after_poll_init(deps, msg),
         _ => Err(ContractError::InvalidReplyId {}),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let from = migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if from < Version::new(0, 2, 0) {
+        migrations::v0_2(deps.storage)?;
+        migrations::schedule_finishes(deps.branch())?;
+    }
+
+    migrations::apply_config(deps, msg)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", from.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -110,5 +167,14 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Config {} => to_binary(&queries::query_config(deps)?),
         QueryMsg::State {} => to_binary(&queries::query_state(deps)?),
         QueryMsg::GetContracts {} => to_binary(&queries::query_contracts(deps)?),
+        QueryMsg::Roles {
+            start_after,
+            limit,
+            order_by,
+        } => to_binary(&queries::query_roles(deps, start_after, limit, order_by)?),
+        QueryMsg::Resolution { poll_contract } => {
+            to_binary(&queries::query_resolution(deps, poll_contract)?)
+        }
+        QueryMsg::PollMigration {} => to_binary(&queries::query_poll_migration(deps)?),
     }
 }
@@ -27,9 +27,6 @@ pub enum ContractError {
     #[error("Must deposit more than {0} token")]
     InsufficientTokenDeposit(Uint128),
 
-    #[error("Deposit doesn't match, should be {0} token")]
-    InvalidTokenDeposit(Uint128),
-
     #[error("Invalid zero amount")]
     InvalidZeroAmount {},
 
@@ -50,6 +47,18 @@ pub enum ContractError {
 
     #[error("Poll type should be one of (prediction | opinion)")]
     InvalidPollType {},
+
+    #[error("Deposit split exceeds the deposit, or sends to an unset community contract")]
+    InvalidDepositSplit {},
+
+    #[error("Cannot revoke the last config admin")]
+    LastConfigAdmin {},
+
+    #[error("Resolver threshold should be between 1 and the number of resolvers")]
+    InvalidResolverThreshold {},
+
+    #[error("Poll {0} is not registered")]
+    UnregisteredPoll(String),
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
@@ -1,28 +1,39 @@
+use crate::entrypoints::{
+    FINISH_POLL_REPLY_ID_BASE, INSTANTIATE_REPLY_ID_BASE, MIGRATE_POLL_REPLY_ID,
+};
 use crate::error::ContractError;
-use config::config::PollType;
+use config::config::{DepositSplit, PollConfig, PollType};
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response,
-    StdResult, SubMsg, Uint128, WasmMsg,
+    from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Order,
+    Response, StdResult, Storage, SubMsg, Uint128, WasmMsg,
+};
+use messages::meta_contract::execute_msgs::{Cw20HookMsg, FinishPollEntry};
+use messages::meta_contract::state::{
+    Config, PendingCreation, PendingFinish, PollFilter, PollMigration, PollResolvers, Resolution,
+    Role, ScheduledFinish, State, CONTRACTS, FINISH_SCHEDULE,
 };
-use messages::meta_contract::execute_msgs::Cw20HookMsg;
-use messages::meta_contract::state::{Config, State, CONTRACTS};
 use messages::opinion_poll::execute_msgs::ExecuteMsg as OpinionPollExecuteMsg;
+use messages::opinion_poll::query_msgs::QueryMsg as OpinionPollQueryMsg;
 use messages::prediction_poll::execute_msgs::ExecuteMsg as PredictionPollExecuteMsg;
 
-use cw20::Cw20ReceiveMsg;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Bound;
+use std::str;
 
-use messages::msg::PollInstantiateMsg;
+use messages::msg::{PollInstantiateMsg, PollMigrateMsg};
+use messages::pause::{set_paused, set_pausers, PauseInfo, PauseOperation};
+use messages::utils::{DEFAULT_LIMIT, MAX_LIMIT};
 
-// reply_id is only one for now
-pub const INSTANTIATE_REPLY_ID: u64 = 1;
 const DENOM: &str = "uusd";
 
 pub fn receive_cw20(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Creating)?;
+
     let config = Config::load(deps.storage)?;
 
     if config.token_contract != deps.api.addr_validate(info.sender.as_str())? {
@@ -43,18 +54,24 @@ pub fn receive_cw20(
             resolution_time,
             poll_admin,
             num_side,
+            resolvers,
+            resolver_threshold,
         }) => init_poll(
             deps,
+            env,
             info,
             code_id,
             cw20_msg.sender,
-            cw20_msg.amount,
+            creation_deposit,
+            cw20_msg.amount - creation_deposit,
             poll_name,
             poll_type,
             end_time,
             resolution_time,
             poll_admin,
             num_side,
+            resolvers,
+            resolver_threshold,
         ),
         _ => Err(ContractError::InvalidCw20Msg {}),
     }
@@ -63,22 +80,23 @@ pub fn receive_cw20(
 #[allow(clippy::too_many_arguments)]
 pub fn init_poll(
     deps: DepsMut,
+    env: Env,
     _info: MessageInfo,
     code_id: u64,
     generator: String,
     deposit_amount: Uint128,
+    excess_amount: Uint128,
     poll_name: String,
     poll_type: String,
     end_time: u64,
     resolution_time: Option<u64>,
     poll_admin: Option<String>,
     num_side: Option<u64>,
+    resolvers: Option<Vec<String>>,
+    resolver_threshold: Option<u32>,
 ) -> Result<Response, ContractError> {
     let config = Config::load(deps.storage)?;
-
-    if config.creation_deposit != deposit_amount {
-        return Err(ContractError::InvalidTokenDeposit(config.creation_deposit));
-    }
+    let generator = deps.api.addr_validate(&generator)?;
 
     let poll_type = match poll_type.as_str() {
         "prediction" => Ok(PollType::Prediction),
@@ -103,31 +121,98 @@ pub fn init_poll(
         _ => {}
     }
 
+    let poll_type = poll_type?;
+    let pause_info = PauseInfo::load(deps.storage)?;
+
+    let resolvers = match resolvers {
+        Some(resolvers) => {
+            let mut addrs: Vec<Addr> = vec![];
+            for resolver in resolvers.iter() {
+                let resolver = deps.api.addr_validate(resolver)?;
+                if !addrs.contains(&resolver) {
+                    addrs.push(resolver);
+                }
+            }
+
+            let threshold = resolver_threshold.unwrap_or(1);
+            if threshold == 0 || threshold as usize > addrs.len() {
+                return Err(ContractError::InvalidResolverThreshold {});
+            }
+
+            Some(PollResolvers {
+                poll_type: poll_type.clone(),
+                resolvers: addrs,
+                threshold,
+                approvals: vec![],
+            })
+        }
+        None if resolver_threshold.is_some() => {
+            return Err(ContractError::InvalidResolverThreshold {});
+        }
+        None => None,
+    };
+
+    // polls administered by the meta-contract are upgraded through `MigratePolls`
+    let poll_admin = match poll_admin {
+        Some(poll_admin) => deps.api.addr_validate(&poll_admin)?,
+        None => env.contract.address,
+    };
     let msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Instantiate {
-        admin: poll_admin,
+        admin: Some(poll_admin.to_string()),
         code_id,
         msg: to_binary(&PollInstantiateMsg {
-            generator: deps.api.addr_validate(&generator)?,
-            token_contract: config.token_contract,
+            generator: generator.clone(),
+            token_contract: config.token_contract.clone(),
             deposit_amount,
             reclaimable_threshold: config.reclaimable_threshold,
             poll_name: poll_name.clone(),
-            poll_type: poll_type?,
+            poll_type: poll_type.clone(),
             end_time,
             num_side: num_side.unwrap_or(2),
             resolution_time,
             minimum_bet_amount: Some(config.minimum_bet_amount),
             tax_percentage: Some(config.tax_percentage),
+            community_contract: config.community_contract.as_ref().map(|v| v.to_string()),
+            deposit_split: Some(config.deposit_split.clone()),
+            guardian: pause_info.guardian.map(|v| v.to_string()),
+            governance: pause_info.governance.map(|v| v.to_string()),
         })?,
         funds: vec![],
         label: poll_name,
     });
 
-    let submsg = SubMsg::reply_on_success(msg, INSTANTIATE_REPLY_ID);
+    // the deposit is forwarded to the poll only once its instantiation succeeded
+    let reply_id = INSTANTIATE_REPLY_ID_BASE + PendingCreation::next_sequence(deps.storage)?;
+    PendingCreation {
+        creator: generator.clone(),
+        deposit_amount,
+        poll_type,
+        end_time,
+        resolvers,
+    }
+    .save(deps.storage, reply_id)?;
 
-    Ok(Response::new()
+    let submsg = SubMsg::reply_on_success(msg, reply_id);
+
+    let mut response = Response::new()
         .add_attribute("method", "try_init_poll")
-        .add_submessage(submsg))
+        .add_submessage(submsg);
+
+    // refund what was sent over the creation deposit
+    if !excess_amount.is_zero() {
+        response = response
+            .add_attribute("refund_amount", excess_amount)
+            .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: config.token_contract,
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: generator.to_string(),
+                    amount: excess_amount,
+                })?,
+                funds: vec![],
+            }));
+    }
+
+    Ok(response)
 }
 
 pub fn register_token_contract(
@@ -142,7 +227,7 @@ pub fn register_token_contract(
         return Err(ContractError::TokenAlreadyRegistered {});
     }
 
-    if !config.is_admin(&info.sender) {
+    if !Role::ConfigAdmin.is_granted(deps.storage, &info.sender)? {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -161,63 +246,299 @@ pub fn finish_poll(
     winner: Option<u64>,
     forced: bool, // TODO : only for internal QA
 ) -> Result<Response, ContractError> {
-    let config = Config::load(deps.storage)?;
-
-    if !config.is_admin(&info.sender) {
-        return Err(ContractError::Unauthorized {});
-    }
-
     let poll_type = match poll_type.as_str() {
         "prediction" => Ok(PollType::Prediction),
         "opinion" => Ok(PollType::Opinion),
         _ => Err(ContractError::InvalidPollType {}),
     }?;
 
-    if poll_type == PollType::Prediction && winner.is_none() {
-        return Err(ContractError::EmptyWinner {});
+    let poll_contract = deps.api.addr_validate(&poll_contract)?;
+
+    let resolved_by = if Role::Resolver.is_granted(deps.storage, &info.sender)? {
+        vec![info.sender]
+    } else {
+        // delegated resolvers can only finish their own poll
+        let mut resolvers = match PollResolvers::may_load(deps.storage, &poll_contract)? {
+            Some(resolvers) if !forced && resolvers.is_resolver(&info.sender) => resolvers,
+            _ => return Err(ContractError::Unauthorized {}),
+        };
+
+        if resolvers.poll_type != poll_type {
+            return Err(ContractError::InvalidPollType {});
+        }
+
+        if poll_type == PollType::Prediction && winner.is_none() {
+            return Err(ContractError::EmptyWinner {});
+        }
+
+        resolvers.approve(&info.sender, winner);
+        let approvers = resolvers.approvers(winner);
+
+        if (approvers.len() as u32) < resolvers.threshold {
+            resolvers.save(deps.storage, &poll_contract)?;
+
+            return Ok(Response::new()
+                .add_attribute("method", "approve_resolution")
+                .add_attribute("resolver", info.sender)
+                .add_attribute("approvals", approvers.len().to_string()));
+        }
+
+        approvers
+    };
+
+    if poll_type == PollType::Opinion {
+        unregister_poll(deps.storage, &poll_contract)?;
     }
+    let message = finish_poll_msg(&poll_contract, poll_type, winner, forced)?;
+    let resolved_by_attr = resolved_by
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+    record_resolution(deps.storage, &poll_contract, resolved_by, winner)?;
 
-    let message: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: deps.api.addr_validate(&poll_contract)?.to_string(),
-        msg: match forced {
-            // TODO : only for internal QA
-            true => match poll_type {
-                PollType::Prediction => to_binary(&PredictionPollExecuteMsg::ForceFinishPoll {
-                    winner: winner.unwrap(),
-                })?,
-                PollType::Opinion => {
-                    let addr = &deps.api.addr_validate(poll_contract.as_str())?;
-                    CONTRACTS.remove(deps.storage, addr);
+    Ok(Response::new()
+        .add_message(message)
+        .add_attribute("method", "finish_poll")
+        .add_attribute("resolved_by", resolved_by_attr))
+}
+
+pub fn finish_polls(
+    deps: DepsMut,
+    info: MessageInfo,
+    entries: Vec<FinishPollEntry>,
+) -> Result<Response, ContractError> {
+    if !Role::Resolver.is_granted(deps.storage, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
 
-                    let mut state: State = State::load(deps.storage)?;
-                    state.num_contract -= 1;
-                    state.save(deps.storage)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for entry in entries {
+        let poll_contract = deps.api.addr_validate(&entry.poll_contract)?;
 
-                    to_binary(&OpinionPollExecuteMsg::ForceFinishPoll {})?
-                }
-            },
-            false => match poll_type {
-                PollType::Prediction => to_binary(&PredictionPollExecuteMsg::FinishPoll {
-                    winner: winner.unwrap(),
-                })?,
-                PollType::Opinion => {
-                    let addr = &deps.api.addr_validate(poll_contract.as_str())?;
-                    CONTRACTS.remove(deps.storage, addr);
+        // both poll types answer the config query with a PollConfig
+        let poll_config: PollConfig = deps
+            .querier
+            .query_wasm_smart(&poll_contract, &OpinionPollQueryMsg::Config {})?;
 
-                    let mut state: State = State::load(deps.storage)?;
-                    state.num_contract -= 1;
-                    state.save(deps.storage)?;
+        if poll_config.poll_type == PollType::Opinion {
+            unregister_poll(deps.storage, &poll_contract)?;
+        }
+        messages.push(finish_poll_msg(
+            &poll_contract,
+            poll_config.poll_type,
+            entry.winner,
+            false,
+        )?);
+        record_resolution(
+            deps.storage,
+            &poll_contract,
+            vec![info.sender.clone()],
+            entry.winner,
+        )?;
+    }
 
-                    to_binary(&OpinionPollExecuteMsg::FinishPoll {})?
-                }
-            },
+    Ok(Response::new()
+        .add_attribute("method", "finish_polls")
+        .add_attribute("num_polls", messages.len().to_string())
+        .add_messages(messages))
+}
+
+pub fn finish_expired(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let now = env.block.time.seconds();
+
+    let expired = FINISH_SCHEDULE
+        .range(deps.storage, None, None, Order::Ascending)
+        .take_while(|item| item.as_ref().map_or(true, |(_, v)| v.end_time <= now))
+        .take(limit)
+        .map(|item| item.map(|(_, v)| v))
+        .collect::<StdResult<Vec<ScheduledFinish>>>()?;
+
+    let mut submsgs: Vec<SubMsg> = vec![];
+    for scheduled in expired {
+        // already finished by a resolver
+        if !CONTRACTS.has(deps.storage, &scheduled.contract) {
+            scheduled.remove(deps.storage);
+            continue;
+        }
+
+        let message = finish_poll_msg(&scheduled.contract, PollType::Opinion, None, false)?;
+
+        // the poll is unregistered once it has finished, a poll failing to finish is
+        // scheduled again behind the expired polls so it doesn't block the rest of the queue
+        let reply_id = FINISH_POLL_REPLY_ID_BASE + submsgs.len() as u64;
+        PendingFinish {
+            scheduled,
+            resolved_by: info.sender.clone(),
+        }
+        .save(deps.storage, reply_id)?;
+        submsgs.push(SubMsg::reply_always(message, reply_id));
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "finish_expired")
+        .add_attribute("num_polls", submsgs.len().to_string())
+        .add_submessages(submsgs))
+}
+
+pub fn migrate_polls(
+    deps: DepsMut,
+    info: MessageInfo,
+    code_id: u64,
+    msg: PollMigrateMsg,
+    filter: Option<PollFilter>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let pause_info = PauseInfo::load(deps.storage)?;
+    if !Role::ConfigAdmin.is_granted(deps.storage, &info.sender)?
+        && pause_info.governance.as_ref() != Some(&info.sender)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let filter = filter.unwrap_or_default();
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    // the same code and filter continue an unfinished run, anything else starts over
+    let mut migration = match PollMigration::may_load(deps.storage)? {
+        Some(migration)
+            if migration.code_id == code_id
+                && migration.filter == filter
+                && !migration.finished =>
+        {
+            migration
+        }
+        _ => PollMigration {
+            code_id,
+            filter,
+            last_contract: None,
+            num_migrated: 0,
+            num_failed: 0,
+            finished: false,
         },
-        funds: vec![],
-    });
+    };
+
+    let start = migration
+        .last_contract
+        .as_ref()
+        .map(|v| Bound::exclusive(v.as_bytes()));
+    let contracts = CONTRACTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, _) = item?;
+            deps.api.addr_validate(str::from_utf8(&k)?)
+        })
+        .collect::<StdResult<Vec<Addr>>>()?;
+
+    // polls created before the pause get the pausers of the meta-contract unless given
+    let migrate_msg = to_binary(&PollMigrateMsg {
+        guardian: msg
+            .guardian
+            .or_else(|| pause_info.guardian.as_ref().map(|v| v.to_string())),
+        governance: msg
+            .governance
+            .or_else(|| pause_info.governance.as_ref().map(|v| v.to_string())),
+        ..msg
+    })?;
+
+    let mut submsgs: Vec<SubMsg> = vec![];
+    for contract in contracts.iter() {
+        if let Some(poll_type) = migration.filter.poll_type.as_ref() {
+            let poll_config: PollConfig = deps
+                .querier
+                .query_wasm_smart(contract, &OpinionPollQueryMsg::Config {})?;
+            if &poll_config.poll_type != poll_type {
+                continue;
+            }
+        }
+
+        let message = CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: contract.to_string(),
+            new_code_id: code_id,
+            msg: migrate_msg.clone(),
+        });
+
+        // every migration is counted by its reply, a failed one must not block the rest of the batch
+        submsgs.push(SubMsg::reply_always(message, MIGRATE_POLL_REPLY_ID));
+    }
+
+    migration.finished = contracts.len() < limit;
+    if let Some(last_contract) = contracts.last() {
+        migration.last_contract = Some(last_contract.clone());
+    }
+    migration.save(deps.storage)?;
 
     Ok(Response::new()
-        .add_message(message)
-        .add_attribute("method", "finish_poll"))
+        .add_attribute("method", "migrate_polls")
+        .add_attribute("code_id", code_id.to_string())
+        .add_attribute("num_polls", submsgs.len().to_string())
+        .add_attribute("finished", migration.finished.to_string())
+        .add_submessages(submsgs))
+}
+
+// the delegation of a resolved poll is dropped, so its resolvers cannot finish it again
+pub(crate) fn record_resolution(
+    storage: &mut dyn Storage,
+    poll_contract: &Addr,
+    resolved_by: Vec<Addr>,
+    winner: Option<u64>,
+) -> StdResult<()> {
+    PollResolvers::remove(storage, poll_contract);
+    Resolution {
+        resolved_by,
+        winner,
+    }
+    .save(storage, poll_contract)
+}
+
+// opinion polls are unregistered as they finish
+pub(crate) fn unregister_poll(storage: &mut dyn Storage, poll_contract: &Addr) -> StdResult<()> {
+    if CONTRACTS.has(storage, poll_contract) {
+        CONTRACTS.remove(storage, poll_contract);
+
+        let mut state: State = State::load(storage)?;
+        state.num_contract -= 1;
+        state.save(storage)?;
+    }
+
+    Ok(())
+}
+
+// builds the FinishPoll message of a poll
+fn finish_poll_msg(
+    poll_contract: &Addr,
+    poll_type: PollType,
+    winner: Option<u64>,
+    forced: bool, // TODO : only for internal QA
+) -> Result<CosmosMsg, ContractError> {
+    let msg = match poll_type {
+        PollType::Prediction => {
+            let winner = winner.ok_or(ContractError::EmptyWinner {})?;
+            match forced {
+                // TODO : only for internal QA
+                true => to_binary(&PredictionPollExecuteMsg::ForceFinishPoll { winner })?,
+                false => to_binary(&PredictionPollExecuteMsg::FinishPoll { winner })?,
+            }
+        }
+        PollType::Opinion => match forced {
+            // TODO : only for internal QA
+            true => to_binary(&OpinionPollExecuteMsg::ForceFinishPoll {})?,
+            false => to_binary(&OpinionPollExecuteMsg::FinishPoll {})?,
+        },
+    };
+
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: poll_contract.to_string(),
+        msg,
+        funds: vec![],
+    }))
 }
 
 pub fn transfer(
@@ -227,9 +548,7 @@ pub fn transfer(
     recipient: String,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
-    let config = Config::load(deps.storage)?;
-
-    if !config.is_admin(&info.sender) {
+    if !Role::Treasurer.is_granted(deps.storage, &info.sender)? {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -267,11 +586,12 @@ pub fn update_config(
     info: MessageInfo,
     creation_deposit: Option<Uint128>,
     reclaimable_threshold: Option<Uint128>,
-    new_admins: Option<Vec<String>>,
+    community_contract: Option<String>,
+    deposit_split: Option<DepositSplit>,
 ) -> Result<Response, ContractError> {
     let mut config = Config::load(deps.storage)?;
 
-    if !config.is_admin(&info.sender) {
+    if !Role::ConfigAdmin.is_granted(deps.storage, &info.sender)? {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -286,14 +606,193 @@ pub fn update_config(
         config.reclaimable_threshold = reclaimable_threshold;
     }
 
-    if let Some(new_admins) = new_admins.as_ref() {
-        config.admins = new_admins
-            .iter()
-            .map(|v| deps.api.addr_validate(v))
-            .collect::<StdResult<Vec<Addr>>>()?;
+    if let Some(community_contract) = community_contract {
+        config.community_contract = Some(deps.api.addr_validate(&community_contract)?);
+    }
+
+    if let Some(deposit_split) = deposit_split {
+        config.deposit_split = deposit_split;
+    }
+
+    if !config.deposit_split.is_valid()
+        || (config.community_contract.is_none() && !config.deposit_split.community.is_zero())
+    {
+        return Err(ContractError::InvalidDepositSplit {});
     }
 
     config.save(deps.storage)?;
 
     Ok(Response::new().add_attribute("method", "update_config"))
 }
+
+pub fn grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+) -> Result<Response, ContractError> {
+    if !Role::ConfigAdmin.is_granted(deps.storage, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let address = deps.api.addr_validate(&address)?;
+    role.grant(deps.storage, &address)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "grant_role")
+        .add_attribute("address", address)
+        .add_attribute("role", role.to_string()))
+}
+
+pub fn revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+) -> Result<Response, ContractError> {
+    if !Role::ConfigAdmin.is_granted(deps.storage, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let address = deps.api.addr_validate(&address)?;
+
+    // the contract must not be left without anyone able to grant roles
+    if role == Role::ConfigAdmin
+        && role.is_granted(deps.storage, &address)?
+        && role.count(deps.storage)? == 1
+    {
+        return Err(ContractError::LastConfigAdmin {});
+    }
+
+    role.revoke(deps.storage, &address)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "revoke_role")
+        .add_attribute("address", address)
+        .add_attribute("role", role.to_string()))
+}
+
+pub fn pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+    operations: Option<Vec<PauseOperation>>,
+) -> Result<Response, ContractError> {
+    if !Role::Pauser.is_granted(deps.storage, &info.sender)?
+        && !PauseInfo::load(deps.storage)?.is_pauser(&info.sender)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(set_paused(deps.storage, paused, operations)?)
+}
+
+pub fn update_pausers(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<String>,
+    governance: Option<String>,
+) -> Result<Response, ContractError> {
+    let pause_info = PauseInfo::load(deps.storage)?;
+    if !Role::ConfigAdmin.is_granted(deps.storage, &info.sender)?
+        && pause_info.governance.as_ref() != Some(&info.sender)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let guardian = guardian.map(|v| deps.api.addr_validate(&v)).transpose()?;
+    let governance = governance.map(|v| deps.api.addr_validate(&v)).transpose()?;
+
+    Ok(set_pausers(deps.storage, guardian, governance)?)
+}
+
+pub fn pause_polls(
+    deps: DepsMut,
+    info: MessageInfo,
+    poll_contracts: Vec<String>,
+    paused: bool,
+    operations: Option<Vec<PauseOperation>>,
+) -> Result<Response, ContractError> {
+    if !Role::Pauser.is_granted(deps.storage, &info.sender)?
+        && !PauseInfo::load(deps.storage)?.is_pauser(&info.sender)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // both poll types share the pause messages
+    let msg = match paused {
+        true => to_binary(&OpinionPollExecuteMsg::Pause { operations })?,
+        false => to_binary(&OpinionPollExecuteMsg::Unpause { operations })?,
+    };
+    let messages = registered_polls(&deps, poll_contracts)?
+        .into_iter()
+        .map(|contract| {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract.to_string(),
+                msg: msg.clone(),
+                funds: vec![],
+            })
+        })
+        .collect::<Vec<CosmosMsg>>();
+
+    Ok(Response::new()
+        .add_attribute(
+            "method",
+            match paused {
+                true => "pause_polls",
+                false => "unpause_polls",
+            },
+        )
+        .add_attribute("num_polls", messages.len().to_string())
+        .add_messages(messages))
+}
+
+pub fn update_poll_pausers(
+    deps: DepsMut,
+    info: MessageInfo,
+    poll_contracts: Vec<String>,
+) -> Result<Response, ContractError> {
+    let pause_info = PauseInfo::load(deps.storage)?;
+    if !Role::ConfigAdmin.is_granted(deps.storage, &info.sender)?
+        && pause_info.governance.as_ref() != Some(&info.sender)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let msg = to_binary(&OpinionPollExecuteMsg::UpdatePausers {
+        guardian: pause_info.guardian.map(|v| v.to_string()),
+        governance: pause_info.governance.map(|v| v.to_string()),
+    })?;
+    let messages = registered_polls(&deps, poll_contracts)?
+        .into_iter()
+        .map(|contract| {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract.to_string(),
+                msg: msg.clone(),
+                funds: vec![],
+            })
+        })
+        .collect::<Vec<CosmosMsg>>();
+
+    Ok(Response::new()
+        .add_attribute("method", "update_poll_pausers")
+        .add_attribute("num_polls", messages.len().to_string())
+        .add_messages(messages))
+}
+
+// only polls owned by the meta-contract are sent its messages
+fn registered_polls(
+    deps: &DepsMut,
+    poll_contracts: Vec<String>,
+) -> Result<Vec<Addr>, ContractError> {
+    poll_contracts
+        .iter()
+        .map(|v| {
+            let contract = deps.api.addr_validate(v)?;
+            if !CONTRACTS.has(deps.storage, &contract) {
+                return Err(ContractError::UnregisteredPoll(contract.to_string()));
+            }
+            Ok(contract)
+        })
+        .collect()
+}
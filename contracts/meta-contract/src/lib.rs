@@ -2,6 +2,7 @@ pub mod entrypoints;
 
 mod error;
 mod executions;
+mod migrations;
 mod queries;
 mod replies;
 mod response;
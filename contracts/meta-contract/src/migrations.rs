@@ -0,0 +1,112 @@
+use config::config::{DepositSplit, PollConfig, PollType};
+use cosmwasm_std::{Addr, Decimal, DepsMut, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::str;
+
+use crate::error::ContractError;
+use messages::meta_contract::execute_msgs::MigrateMsg;
+use messages::meta_contract::state::{Config, Role, ScheduledFinish, CONTRACTS};
+use messages::opinion_poll::query_msgs::QueryMsg as OpinionPollQueryMsg;
+use messages::pause::migrate_pausers;
+
+// Config before 0.2.0, admins had every permission
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct ConfigV0_1 {
+    admins: Vec<Addr>,
+    token_contract: String,
+    creation_deposit: Uint128,
+    reclaimable_threshold: Uint128,
+    minimum_bet_amount: Uint128,
+    tax_percentage: Decimal,
+}
+
+const CONFIG_V0_1: Item<ConfigV0_1> = Item::new("config");
+
+/// Admins are granted every role and the deposit split defaults to burning
+pub fn v0_2(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy = CONFIG_V0_1.load(storage)?;
+
+    for admin in legacy.admins.iter() {
+        for role in Role::all() {
+            role.grant(storage, admin)?;
+        }
+    }
+
+    Config {
+        token_contract: legacy.token_contract,
+        creation_deposit: legacy.creation_deposit,
+        reclaimable_threshold: legacy.reclaimable_threshold,
+        minimum_bet_amount: legacy.minimum_bet_amount,
+        tax_percentage: legacy.tax_percentage,
+        community_contract: None,
+        deposit_split: DepositSplit::burn_all(),
+    }
+    .save(storage)
+}
+
+/// Opinion polls registered before `FinishExpired` are scheduled from their end_time
+pub fn schedule_finishes(deps: DepsMut) -> StdResult<()> {
+    let contracts = CONTRACTS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|k| deps.api.addr_validate(str::from_utf8(&k)?))
+        .collect::<StdResult<Vec<Addr>>>()?;
+
+    for contract in contracts {
+        // a poll that cannot be queried is left to be finished by its resolvers
+        let poll_config: PollConfig = match deps
+            .querier
+            .query_wasm_smart(&contract, &OpinionPollQueryMsg::Config {})
+        {
+            Ok(poll_config) => poll_config,
+            Err(_) => continue,
+        };
+
+        if poll_config.poll_type == PollType::Opinion {
+            ScheduledFinish {
+                contract,
+                end_time: poll_config.end_time,
+            }
+            .save(deps.storage)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn apply_config(deps: DepsMut, msg: MigrateMsg) -> Result<(), ContractError> {
+    let mut config = Config::load(deps.storage)?;
+
+    if let Some(reclaimable_threshold) = msg.reclaimable_threshold {
+        config.reclaimable_threshold = reclaimable_threshold;
+    }
+
+    if let Some(community_contract) = msg.community_contract {
+        config.community_contract = Some(deps.api.addr_validate(&community_contract)?);
+    }
+
+    if let Some(deposit_split) = msg.deposit_split {
+        config.deposit_split = deposit_split;
+    }
+
+    if !config.deposit_split.is_valid()
+        || (config.community_contract.is_none() && !config.deposit_split.community.is_zero())
+    {
+        return Err(ContractError::InvalidDepositSplit {});
+    }
+
+    config.save(deps.storage)?;
+
+    let guardian = msg
+        .guardian
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+    let governance = msg
+        .governance
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+    migrate_pausers(deps.storage, guardian, governance)?;
+
+    Ok(())
+}
@@ -2,8 +2,15 @@ use cosmwasm_std::{Deps, Order, StdResult};
 #[cfg(not(feature = "library"))]
 use std::str;
 
-use messages::meta_contract::query_msgs::{ConfigResponse, ContractsResponse, StateResponse};
-use messages::meta_contract::state::{Config, State, CONTRACTS};
+use messages::meta_contract::query_msgs::{
+    ConfigResponse, ContractsResponse, PollMigrationResponse, ResolutionResponse, RoleInfo,
+    RolesResponse, StateResponse,
+};
+use messages::meta_contract::state::{
+    Config, PollMigration, PollResolvers, Resolution, State, CONTRACTS, ROLES,
+};
+use messages::pause::PauseInfo;
+use messages::utils::{addr_range_option, OrderBy};
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config = Config::load(deps.storage)?;
@@ -12,7 +19,10 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 
 pub fn query_state(deps: Deps) -> StdResult<StateResponse> {
     let state = State::load(deps.storage)?;
-    Ok(state)
+    Ok(StateResponse {
+        num_contract: state.num_contract,
+        pause: PauseInfo::load(deps.storage)?,
+    })
 }
 
 pub fn query_contracts(deps: Deps) -> StdResult<ContractsResponse> {
@@ -28,3 +38,44 @@ pub fn query_contracts(deps: Deps) -> StdResult<ContractsResponse> {
         contracts: contracts.unwrap(),
     })
 }
+
+pub fn query_roles(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    order_by: Option<OrderBy>,
+) -> StdResult<RolesResponse> {
+    let range_option = addr_range_option(start_after, limit, order_by);
+
+    let roles = ROLES
+        .range(
+            deps.storage,
+            range_option.min,
+            range_option.max,
+            range_option.order_by,
+        )
+        .take(range_option.limit)
+        .map(|item| {
+            let (k, roles) = item?;
+            let address = deps.api.addr_validate(str::from_utf8(&k)?)?;
+            Ok(RoleInfo { address, roles })
+        })
+        .collect::<StdResult<Vec<RoleInfo>>>()?;
+
+    Ok(RolesResponse { roles })
+}
+
+pub fn query_resolution(deps: Deps, poll_contract: String) -> StdResult<ResolutionResponse> {
+    let poll_contract = deps.api.addr_validate(&poll_contract)?;
+
+    Ok(ResolutionResponse {
+        resolvers: PollResolvers::may_load(deps.storage, &poll_contract)?,
+        resolution: Resolution::may_load(deps.storage, &poll_contract)?,
+    })
+}
+
+pub fn query_poll_migration(deps: Deps) -> StdResult<PollMigrationResponse> {
+    Ok(PollMigrationResponse {
+        migration: PollMigration::may_load(deps.storage)?,
+    })
+}
@@ -1,15 +1,23 @@
 use crate::error::ContractError;
+use crate::executions::{record_resolution, unregister_poll};
+use config::config::PollType;
 use cosmwasm_std::{
-    to_binary, CosmosMsg, DepsMut, Event, Reply, Response, StdError, Uint128, WasmMsg,
+    to_binary, ContractResult, CosmosMsg, DepsMut, Env, Reply, Response, StdError, WasmMsg,
 };
 
 use cw20::Cw20ExecuteMsg;
 use protobuf::Message;
 
 use crate::response::MsgInstantiateContractResponse;
-use messages::meta_contract::state::{Config, State, CONTRACTS};
+use messages::meta_contract::state::{
+    Config, PendingCreation, PendingFinish, PollMigration, ScheduledFinish, State, CONTRACTS,
+};
 
 pub fn after_poll_init(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let pending =
+        PendingCreation::may_load(deps.storage, msg.id)?.ok_or(ContractError::InvalidReplyId {})?;
+    PendingCreation::remove(deps.storage, msg.id);
+
     let reply_result = msg.result.unwrap();
     let data = reply_result.data.unwrap();
     let res: MsgInstantiateContractResponse =
@@ -24,35 +32,86 @@ pub fn after_poll_init(deps: DepsMut, msg: Reply) -> Result<Response, ContractEr
     state.num_contract += 1;
     state.save(deps.storage)?;
 
-    let event_vec: Vec<Event> = reply_result.events;
-
-    let mut deposit_amount: Option<String> = None;
-    for event in event_vec.iter() {
-        if "wasm".eq_ignore_ascii_case(&event.ty) {
-            for attribute in event.attributes.iter() {
-                if "deposit_amount".eq_ignore_ascii_case(&attribute.key) {
-                    deposit_amount = Some(attribute.value.clone());
-                }
-            }
-        }
+    if let Some(resolvers) = pending.resolvers.as_ref() {
+        resolvers.save(deps.storage, addr)?;
     }
 
-    if deposit_amount.is_none() {
-        return Err(ContractError::InsufficientBalance {});
+    if pending.poll_type == PollType::Opinion {
+        ScheduledFinish {
+            contract: addr.clone(),
+            end_time: pending.end_time,
+        }
+        .save(deps.storage)?;
     }
-    let deposit_amount = Uint128::from(deposit_amount.unwrap().parse::<u128>().unwrap());
 
-    let config: Config = Config::load(deps.storage).unwrap();
+    let config: Config = Config::load(deps.storage)?;
 
     Ok(Response::new()
         .add_attribute("method", "reply")
         .add_attribute("contract_address", contract_address)
+        .add_attribute("creator", pending.creator)
+        .add_attribute("deposit_amount", pending.deposit_amount)
         .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: config.token_contract,
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
                 recipient: contract_address.to_string(),
-                amount: deposit_amount,
+                amount: pending.deposit_amount,
             })?,
             funds: vec![],
         })))
 }
+
+// a finished poll is unregistered and unscheduled,
+// a failed poll is reported and stays scheduled while the rest of the batch goes on
+pub fn after_poll_finish(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let pending =
+        PendingFinish::may_load(deps.storage, msg.id)?.ok_or(ContractError::InvalidReplyId {})?;
+    PendingFinish::remove(deps.storage, msg.id);
+
+    let contract = pending.scheduled.contract.clone();
+    if let ContractResult::Err(error) = msg.result {
+        // the failed poll is retried after the polls already expired,
+        // so that polls which keep failing don't hold the front of the queue
+        pending.scheduled.remove(deps.storage);
+        ScheduledFinish {
+            contract: contract.clone(),
+            end_time: env.block.time.seconds(),
+        }
+        .save(deps.storage)?;
+
+        return Ok(Response::new()
+            .add_attribute("method", "finish_poll_failed")
+            .add_attribute("poll_contract", contract)
+            .add_attribute("error", error));
+    }
+
+    pending.scheduled.remove(deps.storage);
+    unregister_poll(deps.storage, &contract)?;
+    record_resolution(deps.storage, &contract, vec![pending.resolved_by], None)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "finish_poll")
+        .add_attribute("poll_contract", contract))
+}
+
+// a migration fails e.g. for polls whose admin is not the meta-contract
+pub fn after_poll_migrate(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let mut migration =
+        PollMigration::may_load(deps.storage)?.ok_or(ContractError::InvalidReplyId {})?;
+
+    let response = match msg.result {
+        ContractResult::Ok(_) => {
+            migration.num_migrated += 1;
+            Response::new().add_attribute("method", "migrate_poll")
+        }
+        ContractResult::Err(error) => {
+            migration.num_failed += 1;
+            Response::new()
+                .add_attribute("method", "migrate_poll_failed")
+                .add_attribute("error", error)
+        }
+    };
+    migration.save(deps.storage)?;
+
+    Ok(response)
+}
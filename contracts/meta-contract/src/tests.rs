@@ -1,61 +1,173 @@
 #[cfg(test)]
 mod meta_contract_tests {
     use crate::error::ContractError;
-    use messages::meta_contract::state::Config;
+    use messages::meta_contract::query_msgs::{
+        PollMigrationResponse, QueryMsg, ResolutionResponse, RoleInfo, RolesResponse,
+    };
+    use messages::meta_contract::state::{
+        Config, PendingCreation, PollFilter, PollMigration, Resolution, Role, State, CONTRACTS,
+    };
+    use messages::utils::OrderBy;
 
-    use config::config::PollType;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use config::config::{DepositSplit, PollConfig, PollType};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+        MOCK_CONTRACT_ADDR,
+    };
     use cosmwasm_std::{
-        attr, to_binary, Binary, ContractResult, CosmosMsg, Decimal, Event, Reply, SubMsg,
-        SubMsgExecutionResponse, Uint128, WasmMsg,
+        attr, from_binary, from_slice, to_binary, Addr, Binary, ContractResult, CosmosMsg, Decimal,
+        Deps, DepsMut, Empty, Event, OwnedDeps, Querier, QuerierResult, QueryRequest, Reply,
+        Storage, SubMsg, SubMsgExecutionResponse, SystemError, SystemResult, Timestamp, Uint128,
+        WasmMsg, WasmQuery,
     };
+    use cw2::set_contract_version;
     use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
     use protobuf::Message;
 
     use crate::entrypoints;
     use crate::response::MsgInstantiateContractResponse;
-    use messages::meta_contract::execute_msgs::{Cw20HookMsg, ExecuteMsg, InstantiateMsg};
-    use messages::msg::PollInstantiateMsg;
+    use messages::meta_contract::execute_msgs::{
+        Cw20HookMsg, ExecuteMsg, FinishPollEntry, InstantiateMsg, MigrateMsg,
+    };
+    use messages::msg::{PollInstantiateMsg, PollMigrateMsg};
+    use messages::opinion_poll::execute_msgs::ExecuteMsg as OpinionPollExecuteMsg;
+    use messages::pause::PauseOperation;
+    use messages::prediction_poll::execute_msgs::ExecuteMsg as PredictionPollExecuteMsg;
 
     const TOKEN_CONTRACT: &str = "pollterra";
     const DEPOSIT_AMOUNT: Uint128 = Uint128::new(1_000);
     const TEST_CODE_ID: u64 = 1234;
-    const INSTANTIATE_REPLY_ID: u64 = 1;
+    const INSTANTIATE_REPLY_ID: u64 = entrypoints::INSTANTIATE_REPLY_ID_BASE + 1;
     const DEFAULT_RECLAIMABLE_THRESHOLD: Uint128 = Uint128::new(1_000);
+    const END_TIME: u64 = 1653673599;
 
-    #[test]
-    fn after_poll_init() {
-        let mut deps = mock_dependencies(&[]);
+    // answers the config query of poll contracts whose address is their poll type
+    struct PollQuerier {
+        base: MockQuerier<Empty>,
+    }
 
+    impl Querier for PollQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+            match request {
+                QueryRequest::Wasm(WasmQuery::Smart { contract_addr, .. }) => {
+                    let poll_type = match contract_addr.as_str() {
+                        "prediction" => PollType::Prediction,
+                        "opinion" => PollType::Opinion,
+                        _ => {
+                            return SystemResult::Err(SystemError::NoSuchContract {
+                                addr: contract_addr,
+                            })
+                        }
+                    };
+                    let config = PollConfig {
+                        owner: Addr::unchecked(MOCK_CONTRACT_ADDR),
+                        generator: Addr::unchecked("generator"),
+                        token_contract: TOKEN_CONTRACT.to_string(),
+                        reclaimable_threshold: DEFAULT_RECLAIMABLE_THRESHOLD,
+                        poll_name: "test_poll".to_string(),
+                        poll_type,
+                        end_time: END_TIME,
+                        num_side: 2,
+                        resolution_time: 0,
+                        minimum_bet_amount: Uint128::zero(),
+                        tax_percentage: Decimal::zero(),
+                        community_contract: None,
+                        deposit_split: DepositSplit::burn_all(),
+                    };
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&config).unwrap()))
+                }
+                _ => self.base.handle_query(&request),
+            }
+        }
+    }
+
+    // instantiates the meta-contract with the creation deposit of the token contract
+    fn mock_register_token_contract(mut deps: DepsMut) {
         let msg = InstantiateMsg {
             admins: vec!["creator".to_string()],
         };
         let info = mock_info("creator", &[]);
-        let _res = entrypoints::instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let _res = entrypoints::instantiate(deps.branch(), mock_env(), info, msg).unwrap();
 
         let msg = ExecuteMsg::RegisterTokenContract {
             token_contract: TOKEN_CONTRACT.to_string(),
             creation_deposit: DEPOSIT_AMOUNT,
         };
         let info = mock_info("creator", &[]);
-        let _res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-        let mut reply_message = MsgInstantiateContractResponse::default();
-        reply_message.set_contract_address("contract_address".to_string());
+        let _res = entrypoints::execute(deps, mock_env(), info, msg).unwrap();
+    }
 
-        let aa = Message::write_to_bytes(&reply_message).unwrap();
-        let bb = Binary::from(aa);
+    // the deposit of the sender creating a poll of the type
+    fn init_poll_msg(
+        sender: &str,
+        amount: Uint128,
+        poll_type: &str,
+        end_time: u64,
+        resolution_time: Option<u64>,
+    ) -> ExecuteMsg {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: sender.to_string(),
+            amount,
+            msg: to_binary(&Cw20HookMsg::InitPoll {
+                code_id: TEST_CODE_ID,
+                poll_name: "test_poll".to_string(),
+                poll_type: poll_type.to_string(),
+                end_time,
+                resolution_time,
+                poll_admin: None,
+                num_side: None,
+                resolvers: None,
+                resolver_threshold: None,
+            })
+            .unwrap(),
+        })
+    }
 
-        let _reply: Reply = Reply {
-            id: entrypoints::INSTANTIATE_REPLY_ID,
+    // the reply of the poll instantiated at the address
+    fn instantiate_reply(id: u64, contract_address: &str, events: Vec<Event>) -> Reply {
+        let mut reply_message = MsgInstantiateContractResponse::default();
+        reply_message.set_contract_address(contract_address.to_string());
+        Reply {
+            id,
             result: ContractResult::Ok(SubMsgExecutionResponse {
-                // The event type of InstantiateMsg is 'wasm'
-                events: vec![Event::new("wasm").add_attribute("deposit_amount", DEPOSIT_AMOUNT)],
-                data: Some(bb),
+                events,
+                data: Some(Binary::from(
+                    Message::write_to_bytes(&reply_message).unwrap(),
+                )),
             }),
-        };
+        }
+    }
+
+    // instantiates the meta-contract and registers an opinion poll through its reply
+    fn init_opinion_poll(mut deps: DepsMut, contract_address: &str) {
+        mock_register_token_contract(deps.branch());
+
+        let info = mock_info(TOKEN_CONTRACT, &[]);
+        let msg = init_poll_msg("generator", DEPOSIT_AMOUNT, "opinion", END_TIME, None);
+        let _res = entrypoints::execute(deps.branch(), mock_env(), info, msg).unwrap();
 
-        let res = entrypoints::reply(deps.as_mut(), mock_env(), _reply).unwrap();
+        let reply = instantiate_reply(INSTANTIATE_REPLY_ID, contract_address, vec![]);
+        let _res = entrypoints::reply(deps, mock_env(), reply).unwrap();
+    }
+
+    #[test]
+    fn after_poll_init() {
+        let mut deps = mock_dependencies(&[]);
+
+        mock_register_token_contract(deps.as_mut());
+
+        let info = mock_info(TOKEN_CONTRACT, &[]);
+        let msg = init_poll_msg("generator", DEPOSIT_AMOUNT, "opinion", END_TIME, None);
+        let _res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // The event type of InstantiateMsg is 'wasm', its attributes are not trusted
+        let reply = instantiate_reply(
+            INSTANTIATE_REPLY_ID,
+            "contract_address",
+            vec![Event::new("wasm").add_attribute("deposit_amount", "1000000")],
+        );
+        let res = entrypoints::reply(deps.as_mut(), mock_env(), reply).unwrap();
         assert_eq!(res.messages.len(), 1);
         assert_eq!(
             res.messages[0].msg,
@@ -69,47 +181,98 @@ mod meta_contract_tests {
                 funds: vec![],
             })
         );
+        assert!(
+            PendingCreation::may_load(&deps.storage, INSTANTIATE_REPLY_ID)
+                .unwrap()
+                .is_none()
+        );
+
+        // the pending creation is consumed, so the same reply cannot transfer twice
+        let reply = instantiate_reply(INSTANTIATE_REPLY_ID, "contract_address", vec![]);
+        assert!(matches!(
+            entrypoints::reply(deps.as_mut(), mock_env(), reply),
+            Err(ContractError::InvalidReplyId {})
+        ));
     }
 
     #[test]
-    fn proper_poll_init_with_poll_type() {
+    fn refund_excess_deposit() {
         let mut deps = mock_dependencies(&[]);
 
-        let msg = InstantiateMsg {
-            admins: vec!["creator".to_string()],
-        };
-        let info = mock_info("creator", &[]);
-        let _res = entrypoints::instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        mock_register_token_contract(deps.as_mut());
 
-        let msg = ExecuteMsg::RegisterTokenContract {
-            token_contract: TOKEN_CONTRACT.to_string(),
-            creation_deposit: DEPOSIT_AMOUNT,
-        };
-        let info = mock_info("creator", &[]);
-        let _res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info(TOKEN_CONTRACT, &[]);
+        let msg = init_poll_msg(
+            "generator",
+            DEPOSIT_AMOUNT + Uint128::new(500),
+            "opinion",
+            END_TIME,
+            None,
+        );
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "try_init_poll"),
+                attr("refund_amount", "500")
+            ]
+        );
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(res.messages[0].id, INSTANTIATE_REPLY_ID);
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_CONTRACT.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "generator".to_string(),
+                    amount: Uint128::new(500),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // only the creation deposit is kept for the poll
+        assert_eq!(
+            PendingCreation::may_load(&deps.storage, INSTANTIATE_REPLY_ID).unwrap(),
+            Some(PendingCreation {
+                creator: Addr::unchecked("generator"),
+                deposit_amount: DEPOSIT_AMOUNT,
+                poll_type: PollType::Opinion,
+                end_time: END_TIME,
+                resolvers: None,
+            })
+        );
+
+        // the next creation gets its own reply id
+        let info = mock_info(TOKEN_CONTRACT, &[]);
+        let msg = init_poll_msg("generator", DEPOSIT_AMOUNT, "opinion", END_TIME, None);
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].id, INSTANTIATE_REPLY_ID + 1);
+    }
+
+    #[test]
+    fn proper_poll_init_with_poll_type() {
+        let mut deps = mock_dependencies(&[]);
+
+        mock_register_token_contract(deps.as_mut());
 
         // prediction poll type
         let info = mock_info(TOKEN_CONTRACT, &[]);
-        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: TOKEN_CONTRACT.to_string(),
-            amount: Uint128::from(1_000u128),
-            msg: to_binary(&Cw20HookMsg::InitPoll {
-                code_id: TEST_CODE_ID,
-                poll_name: "test_poll".to_string(),
-                poll_type: "prediction".to_string(),
-                end_time: 1653673599,
-                resolution_time: Some(1653673600),
-                poll_admin: None,
-                num_side: None,
-            })
-            .unwrap(),
-        });
+        let msg = init_poll_msg(
+            TOKEN_CONTRACT,
+            DEPOSIT_AMOUNT,
+            "prediction",
+            END_TIME,
+            Some(END_TIME + 1),
+        );
         let res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(res.attributes, vec![attr("method", "try_init_poll"),]);
 
         let info = mock_info(TOKEN_CONTRACT, &[]);
         let msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Instantiate {
-            admin: None,
+            admin: Some(MOCK_CONTRACT_ADDR.to_string()),
             code_id: TEST_CODE_ID,
             msg: to_binary(&PollInstantiateMsg {
                 generator: info.sender,
@@ -118,11 +281,15 @@ mod meta_contract_tests {
                 reclaimable_threshold: DEFAULT_RECLAIMABLE_THRESHOLD,
                 minimum_bet_amount: Some(Uint128::from(1_000u128)),
                 tax_percentage: Some(Decimal::percent(5)),
+                community_contract: None,
+                deposit_split: Some(DepositSplit::burn_all()),
+                guardian: None,
+                governance: None,
                 poll_name: "test_poll".to_string(),
                 poll_type: PollType::Prediction,
-                end_time: 1653673599,
+                end_time: END_TIME,
                 num_side: 2,
-                resolution_time: Some(1653673600),
+                resolution_time: Some(END_TIME + 1),
             })
             .unwrap(),
             funds: vec![],
@@ -134,26 +301,13 @@ mod meta_contract_tests {
 
         // opinion poll type
         let info = mock_info(TOKEN_CONTRACT, &[]);
-        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: TOKEN_CONTRACT.to_string(),
-            amount: Uint128::from(1_000u128),
-            msg: to_binary(&Cw20HookMsg::InitPoll {
-                code_id: TEST_CODE_ID,
-                poll_name: "test_poll".to_string(),
-                poll_type: "opinion".to_string(),
-                end_time: 1653673599,
-                num_side: None,
-                resolution_time: None,
-                poll_admin: None,
-            })
-            .unwrap(),
-        });
+        let msg = init_poll_msg(TOKEN_CONTRACT, DEPOSIT_AMOUNT, "opinion", END_TIME, None);
         let res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(res.attributes, vec![attr("method", "try_init_poll"),]);
 
         let info = mock_info(TOKEN_CONTRACT, &[]);
         let msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Instantiate {
-            admin: None,
+            admin: Some(MOCK_CONTRACT_ADDR.to_string()),
             code_id: TEST_CODE_ID,
             msg: to_binary(&PollInstantiateMsg {
                 generator: info.sender,
@@ -162,9 +316,13 @@ mod meta_contract_tests {
                 reclaimable_threshold: DEFAULT_RECLAIMABLE_THRESHOLD,
                 minimum_bet_amount: Some(Uint128::from(1_000u128)),
                 tax_percentage: Some(Decimal::percent(5)),
+                community_contract: None,
+                deposit_split: Some(DepositSplit::burn_all()),
+                guardian: None,
+                governance: None,
                 poll_name: "test_poll".to_string(),
                 poll_type: PollType::Opinion,
-                end_time: 1653673599,
+                end_time: END_TIME,
                 num_side: 2,
                 resolution_time: None,
             })
@@ -172,43 +330,49 @@ mod meta_contract_tests {
             funds: vec![],
             label: "test_poll".to_string(),
         });
-        let submsg = SubMsg::reply_on_success(msg, INSTANTIATE_REPLY_ID);
+        let submsg = SubMsg::reply_on_success(msg, INSTANTIATE_REPLY_ID + 1);
 
         assert_eq!(res.messages, vec![submsg]);
-    }
-
-    #[test]
-    fn fail_poll_init_with_wrong_poll_type() {
-        let mut deps = mock_dependencies(&[]);
-
-        let msg = InstantiateMsg {
-            admins: vec!["creator".to_string()],
-        };
-        let info = mock_info("creator", &[]);
-        let _res = entrypoints::instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-        let msg = ExecuteMsg::RegisterTokenContract {
-            token_contract: TOKEN_CONTRACT.to_string(),
-            creation_deposit: DEPOSIT_AMOUNT,
-        };
-        let info = mock_info("creator", &[]);
-        let _res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
+        // the creator may keep the poll out of `MigratePolls`
         let info = mock_info(TOKEN_CONTRACT, &[]);
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: TOKEN_CONTRACT.to_string(),
-            amount: Uint128::from(1_000u128),
+            sender: "generator".to_string(),
+            amount: DEPOSIT_AMOUNT,
             msg: to_binary(&Cw20HookMsg::InitPoll {
                 code_id: TEST_CODE_ID,
                 poll_name: "test_poll".to_string(),
-                poll_type: "Wrong Poll Type".to_string(),
-                end_time: 1653673599,
-                resolution_time: Some(1653673600),
-                poll_admin: None,
+                poll_type: "opinion".to_string(),
+                end_time: END_TIME,
+                resolution_time: None,
+                poll_admin: Some("poll_admin".to_string()),
                 num_side: None,
+                resolvers: None,
+                resolver_threshold: None,
             })
             .unwrap(),
         });
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(matches!(
+            &res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Instantiate { admin: Some(admin), .. }) if admin == "poll_admin"
+        ));
+    }
+
+    #[test]
+    fn fail_poll_init_with_wrong_poll_type() {
+        let mut deps = mock_dependencies(&[]);
+
+        mock_register_token_contract(deps.as_mut());
+
+        let info = mock_info(TOKEN_CONTRACT, &[]);
+        let msg = init_poll_msg(
+            TOKEN_CONTRACT,
+            DEPOSIT_AMOUNT,
+            "Wrong Poll Type",
+            END_TIME,
+            Some(END_TIME + 1),
+        );
 
         assert!(matches!(
             entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
@@ -220,85 +384,570 @@ mod meta_contract_tests {
     fn fail_poll_init_with_resolution_time() {
         let mut deps = mock_dependencies(&[]);
 
+        mock_register_token_contract(deps.as_mut());
+
+        let info = mock_info(TOKEN_CONTRACT, &[]);
+        let msg = init_poll_msg(TOKEN_CONTRACT, DEPOSIT_AMOUNT, "prediction", END_TIME, None);
+
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::ShouldHaveResolutionTime {})
+        ));
+
+        let info = mock_info(TOKEN_CONTRACT, &[]);
+        let msg = init_poll_msg(
+            TOKEN_CONTRACT,
+            DEPOSIT_AMOUNT,
+            "prediction",
+            1653673601,
+            Some(END_TIME + 1),
+        );
+
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::ShouldEndBeforeResolution {})
+        ));
+
+        let info = mock_info(TOKEN_CONTRACT, &[]);
+        let msg = init_poll_msg(
+            TOKEN_CONTRACT,
+            DEPOSIT_AMOUNT,
+            "opinion",
+            END_TIME,
+            Some(END_TIME + 1),
+        );
+
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::ShouldNotHaveResolutionTime {})
+        ));
+    }
+
+    #[test]
+    fn update_deposit_split() {
+        let mut deps = mock_dependencies(&[]);
+
         let msg = InstantiateMsg {
             admins: vec!["creator".to_string()],
         };
         let info = mock_info("creator", &[]);
         let _res = entrypoints::instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let msg = ExecuteMsg::RegisterTokenContract {
-            token_contract: TOKEN_CONTRACT.to_string(),
-            creation_deposit: DEPOSIT_AMOUNT,
+        let split = DepositSplit {
+            burn: Decimal::percent(50),
+            community: Decimal::percent(50),
+        };
+
+        // community share without community contract
+        let msg = ExecuteMsg::UpdateConfig {
+            creation_deposit: None,
+            reclaimable_threshold: None,
+            community_contract: None,
+            deposit_split: Some(split.clone()),
+        };
+        let info = mock_info("creator", &[]);
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::InvalidDepositSplit {})
+        ));
+
+        // split over 100%
+        let msg = ExecuteMsg::UpdateConfig {
+            creation_deposit: None,
+            reclaimable_threshold: None,
+            community_contract: Some("community".to_string()),
+            deposit_split: Some(DepositSplit {
+                burn: Decimal::percent(60),
+                community: Decimal::percent(50),
+            }),
+        };
+        let info = mock_info("creator", &[]);
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::InvalidDepositSplit {})
+        ));
+
+        let msg = ExecuteMsg::UpdateConfig {
+            creation_deposit: None,
+            reclaimable_threshold: None,
+            community_contract: Some("community".to_string()),
+            deposit_split: Some(split.clone()),
         };
         let info = mock_info("creator", &[]);
         let _res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let info = mock_info(TOKEN_CONTRACT, &[]);
-        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: TOKEN_CONTRACT.to_string(),
-            amount: Uint128::from(1_000u128),
-            msg: to_binary(&Cw20HookMsg::InitPoll {
-                code_id: TEST_CODE_ID,
-                poll_name: "test_poll".to_string(),
-                poll_type: "prediction".to_string(),
-                end_time: 1653673599,
-                resolution_time: None,
-                poll_admin: None,
-                num_side: None,
-            })
-            .unwrap(),
-        });
+        let config = Config::load(&deps.storage).unwrap();
+        assert_eq!(
+            config.community_contract,
+            Some(Addr::unchecked("community"))
+        );
+        assert_eq!(config.deposit_split, split);
+    }
+
+    #[test]
+    fn finish_expired() {
+        let mut deps = mock_dependencies(&[]);
+        init_opinion_poll(deps.as_mut(), "opinion");
+
+        // not expired yet
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(END_TIME - 1);
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::FinishExpired { limit: None };
+        let res = entrypoints::execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        // anyone can finish an expired opinion poll
+        env.block.time = Timestamp::from_seconds(END_TIME);
+        let finish_msg = SubMsg::reply_always(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "opinion".to_string(),
+                msg: to_binary(&OpinionPollExecuteMsg::FinishPoll {}).unwrap(),
+                funds: vec![],
+            }),
+            entrypoints::FINISH_POLL_REPLY_ID_BASE,
+        );
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::FinishExpired { limit: None };
+        let res = entrypoints::execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.messages, vec![finish_msg.clone()]);
+
+        // the poll stays registered until it has finished
+        assert!(CONTRACTS.has(&deps.storage, &Addr::unchecked("opinion")));
+        assert_eq!(State::load(&deps.storage).unwrap().num_contract, 1);
 
+        // a failed finish doesn't revert the batch and leaves the poll scheduled
+        let reply = Reply {
+            id: entrypoints::FINISH_POLL_REPLY_ID_BASE,
+            result: ContractResult::Err("Poll is not finished".to_string()),
+        };
+        let res = entrypoints::reply(deps.as_mut(), env.clone(), reply).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "finish_poll_failed"),
+                attr("poll_contract", "opinion"),
+                attr("error", "Poll is not finished")
+            ]
+        );
+        assert!(CONTRACTS.has(&deps.storage, &Addr::unchecked("opinion")));
+
+        // the reply of a finish is handled once
+        let reply = Reply {
+            id: entrypoints::FINISH_POLL_REPLY_ID_BASE,
+            result: ContractResult::Err("Poll is not finished".to_string()),
+        };
+        assert!(matches!(
+            entrypoints::reply(deps.as_mut(), env.clone(), reply),
+            Err(ContractError::InvalidReplyId {})
+        ));
+
+        // the failed poll is retried
+        let info = mock_info("retrier", &[]);
+        let msg = ExecuteMsg::FinishExpired { limit: None };
+        let res = entrypoints::execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.messages, vec![finish_msg]);
+
+        // a finished poll is unregistered and its resolution recorded
+        let reply = Reply {
+            id: entrypoints::FINISH_POLL_REPLY_ID_BASE,
+            result: ContractResult::Ok(SubMsgExecutionResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        let res = entrypoints::reply(deps.as_mut(), env.clone(), reply).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "finish_poll"),
+                attr("poll_contract", "opinion")
+            ]
+        );
+        assert!(!CONTRACTS.has(&deps.storage, &Addr::unchecked("opinion")));
+        assert_eq!(State::load(&deps.storage).unwrap().num_contract, 0);
+
+        let msg = QueryMsg::Resolution {
+            poll_contract: "opinion".to_string(),
+        };
+        let res: ResolutionResponse =
+            from_binary(&entrypoints::query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(
+            res,
+            ResolutionResponse {
+                resolvers: None,
+                resolution: Some(Resolution {
+                    resolved_by: vec![Addr::unchecked("retrier")],
+                    winner: None,
+                }),
+            }
+        );
+
+        // the schedule entry is consumed
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::FinishExpired { limit: None };
+        let res = entrypoints::execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+    }
+
+    #[test]
+    fn finish_expired_moves_failing_polls_behind() {
+        let mut deps = mock_dependencies(&[]);
+        mock_register_token_contract(deps.as_mut());
+        for (reply_id, contract) in [
+            (INSTANTIATE_REPLY_ID, "failing"),
+            (INSTANTIATE_REPLY_ID + 1, "opinion"),
+        ] {
+            let info = mock_info(TOKEN_CONTRACT, &[]);
+            let msg = init_poll_msg("generator", DEPOSIT_AMOUNT, "opinion", END_TIME, None);
+            entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+            let reply = instantiate_reply(reply_id, contract, vec![]);
+            entrypoints::reply(deps.as_mut(), mock_env(), reply).unwrap();
+        }
+
+        let finish_msg = |contract: &str| {
+            SubMsg::reply_always(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract.to_string(),
+                    msg: to_binary(&OpinionPollExecuteMsg::FinishPoll {}).unwrap(),
+                    funds: vec![],
+                }),
+                entrypoints::FINISH_POLL_REPLY_ID_BASE,
+            )
+        };
+        let failed_reply = || Reply {
+            id: entrypoints::FINISH_POLL_REPLY_ID_BASE,
+            result: ContractResult::Err("Unauthorized".to_string()),
+        };
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(END_TIME + 10);
+        let msg = ExecuteMsg::FinishExpired { limit: Some(1) };
+        let res = entrypoints::execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg)
+            .unwrap();
+        assert_eq!(res.messages, vec![finish_msg("failing")]);
+        entrypoints::reply(deps.as_mut(), env.clone(), failed_reply()).unwrap();
+
+        // the failing poll is retried after the other expired polls
+        env.block.time = Timestamp::from_seconds(END_TIME + 20);
+        let msg = ExecuteMsg::FinishExpired { limit: Some(1) };
+        let res = entrypoints::execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg)
+            .unwrap();
+        assert_eq!(res.messages, vec![finish_msg("opinion")]);
+        let reply = Reply {
+            id: entrypoints::FINISH_POLL_REPLY_ID_BASE,
+            result: ContractResult::Ok(SubMsgExecutionResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        entrypoints::reply(deps.as_mut(), env.clone(), reply).unwrap();
+        assert!(!CONTRACTS.has(&deps.storage, &Addr::unchecked("opinion")));
+
+        let msg = ExecuteMsg::FinishExpired { limit: Some(1) };
+        let res = entrypoints::execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(res.messages, vec![finish_msg("failing")]);
+    }
+
+    #[test]
+    fn finish_polls() {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: PollQuerier {
+                base: MockQuerier::new(&[]),
+            },
+        };
+        init_opinion_poll(deps.as_mut(), "opinion");
+
+        let entries = vec![
+            FinishPollEntry {
+                poll_contract: "prediction".to_string(),
+                winner: Some(1),
+            },
+            FinishPollEntry {
+                poll_contract: "opinion".to_string(),
+                winner: None,
+            },
+        ];
+
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::FinishPolls {
+            entries: entries.clone(),
+        };
         assert!(matches!(
             entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
-            Err(ContractError::ShouldHaveResolutionTime {})
+            Err(ContractError::Unauthorized {})
         ));
 
-        let info = mock_info(TOKEN_CONTRACT, &[]);
-        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: TOKEN_CONTRACT.to_string(),
-            amount: Uint128::from(1_000u128),
-            msg: to_binary(&Cw20HookMsg::InitPoll {
-                code_id: TEST_CODE_ID,
-                poll_name: "test_poll".to_string(),
-                poll_type: "prediction".to_string(),
-                end_time: 1653673601,
-                resolution_time: Some(1653673600),
-                poll_admin: None,
-                num_side: None,
-            })
-            .unwrap(),
-        });
+        // prediction poll without winner
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::FinishPolls {
+            entries: vec![FinishPollEntry {
+                poll_contract: "prediction".to_string(),
+                winner: None,
+            }],
+        };
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::EmptyWinner {})
+        ));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::FinishPolls { entries };
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "prediction".to_string(),
+                    msg: to_binary(&PredictionPollExecuteMsg::FinishPoll { winner: 1 }).unwrap(),
+                    funds: vec![],
+                })),
+                SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "opinion".to_string(),
+                    msg: to_binary(&OpinionPollExecuteMsg::FinishPoll {}).unwrap(),
+                    funds: vec![],
+                })),
+            ]
+        );
+        assert_eq!(State::load(&deps.storage).unwrap().num_contract, 0);
+
+        // the opinion poll was finished by the admin, so it is skipped once expired
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(END_TIME);
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::FinishExpired { limit: None };
+        let res = entrypoints::execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+    }
+
+    #[test]
+    fn migrate_polls() {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: PollQuerier {
+                base: MockQuerier::new(&[]),
+            },
+        };
+        init_opinion_poll(deps.as_mut(), "opinion");
+        CONTRACTS
+            .save(&mut deps.storage, &Addr::unchecked("prediction"), &())
+            .unwrap();
+
+        let migrate_msg = |contract: &str| {
+            SubMsg::reply_always(
+                CosmosMsg::Wasm(WasmMsg::Migrate {
+                    contract_addr: contract.to_string(),
+                    new_code_id: TEST_CODE_ID + 1,
+                    msg: to_binary(&PollMigrateMsg::default()).unwrap(),
+                }),
+                entrypoints::MIGRATE_POLL_REPLY_ID,
+            )
+        };
 
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::MigratePolls {
+            code_id: TEST_CODE_ID + 1,
+            msg: PollMigrateMsg::default(),
+            filter: None,
+            limit: Some(1),
+        };
         assert!(matches!(
             entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
-            Err(ContractError::ShouldEndBeforeResolution {})
+            Err(ContractError::Unauthorized {})
         ));
 
-        let info = mock_info(TOKEN_CONTRACT, &[]);
-        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: TOKEN_CONTRACT.to_string(),
-            amount: Uint128::from(1_000u128),
-            msg: to_binary(&Cw20HookMsg::InitPoll {
-                code_id: TEST_CODE_ID,
-                poll_name: "test_poll".to_string(),
-                poll_type: "opinion".to_string(),
-                end_time: 1653673599,
-                resolution_time: Some(1653673600),
-                poll_admin: None,
-                num_side: None,
+        // polls are migrated in batches, continuing where the previous one stopped
+        for contract in ["opinion", "prediction"] {
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::MigratePolls {
+                code_id: TEST_CODE_ID + 1,
+                msg: PollMigrateMsg::default(),
+                filter: None,
+                limit: Some(1),
+            };
+            let res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+            assert_eq!(res.messages, vec![migrate_msg(contract)]);
+        }
+
+        // every migration is counted by its reply
+        let reply = Reply {
+            id: entrypoints::MIGRATE_POLL_REPLY_ID,
+            result: ContractResult::Ok(SubMsgExecutionResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        let _res = entrypoints::reply(deps.as_mut(), mock_env(), reply).unwrap();
+        let reply = Reply {
+            id: entrypoints::MIGRATE_POLL_REPLY_ID,
+            result: ContractResult::Err("unauthorized".to_string()),
+        };
+        let _res = entrypoints::reply(deps.as_mut(), mock_env(), reply).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::MigratePolls {
+            code_id: TEST_CODE_ID + 1,
+            msg: PollMigrateMsg::default(),
+            filter: None,
+            limit: Some(1),
+        };
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        let res: PollMigrationResponse = from_binary(
+            &entrypoints::query(deps.as_ref(), mock_env(), QueryMsg::PollMigration {}).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.migration,
+            Some(PollMigration {
+                code_id: TEST_CODE_ID + 1,
+                filter: PollFilter::default(),
+                last_contract: Some(Addr::unchecked("prediction")),
+                num_migrated: 1,
+                num_failed: 1,
+                finished: true,
             })
-            .unwrap(),
-        });
+        );
+
+        // a new filter starts over
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::MigratePolls {
+            code_id: TEST_CODE_ID + 1,
+            msg: PollMigrateMsg::default(),
+            filter: Some(PollFilter {
+                poll_type: Some(PollType::Prediction),
+            }),
+            limit: None,
+        };
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages, vec![migrate_msg("prediction")]);
+    }
+
+    #[test]
+    fn delegated_resolvers() {
+        let mut deps = mock_dependencies(&[]);
+
+        mock_register_token_contract(deps.as_mut());
+
+        let init_poll = |resolver_threshold: Option<u32>| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "generator".to_string(),
+                amount: DEPOSIT_AMOUNT,
+                msg: to_binary(&Cw20HookMsg::InitPoll {
+                    code_id: TEST_CODE_ID,
+                    poll_name: "test_poll".to_string(),
+                    poll_type: "prediction".to_string(),
+                    end_time: END_TIME,
+                    resolution_time: Some(END_TIME + 1),
+                    poll_admin: None,
+                    num_side: None,
+                    resolvers: Some(vec![
+                        "resolver1".to_string(),
+                        "resolver2".to_string(),
+                        "resolver3".to_string(),
+                    ]),
+                    resolver_threshold,
+                })
+                .unwrap(),
+            })
+        };
+
+        let info = mock_info(TOKEN_CONTRACT, &[]);
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, init_poll(Some(4))),
+            Err(ContractError::InvalidResolverThreshold {})
+        ));
+
+        let info = mock_info(TOKEN_CONTRACT, &[]);
+        let _res =
+            entrypoints::execute(deps.as_mut(), mock_env(), info, init_poll(Some(2))).unwrap();
 
+        let reply = instantiate_reply(INSTANTIATE_REPLY_ID, "prediction", vec![]);
+        let _res = entrypoints::reply(deps.as_mut(), mock_env(), reply).unwrap();
+
+        let finish_poll = |winner: u64| ExecuteMsg::FinishPoll {
+            poll_contract: "prediction".to_string(),
+            poll_type: "prediction".to_string(),
+            winner: Some(winner),
+        };
+
+        // resolvers are delegated for their poll only
+        let info = mock_info("resolver1", &[]);
+        let msg = ExecuteMsg::FinishPoll {
+            poll_contract: "other".to_string(),
+            poll_type: "prediction".to_string(),
+            winner: Some(0),
+        };
         assert!(matches!(
             entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
-            Err(ContractError::ShouldNotHaveResolutionTime {})
+            Err(ContractError::Unauthorized {})
+        ));
+
+        let info = mock_info("resolver1", &[]);
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, finish_poll(0)).unwrap();
+        assert_eq!(res.messages.len(), 0);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "approve_resolution"),
+                attr("resolver", "resolver1"),
+                attr("approvals", "1")
+            ]
+        );
+
+        // approvals of another winner don't add up
+        let info = mock_info("resolver2", &[]);
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, finish_poll(1)).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        let info = mock_info("resolver3", &[]);
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, finish_poll(0)).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "prediction".to_string(),
+                msg: to_binary(&PredictionPollExecuteMsg::FinishPoll { winner: 0 }).unwrap(),
+                funds: vec![],
+            }))]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "finish_poll"),
+                attr("resolved_by", "resolver1,resolver3")
+            ]
+        );
+
+        let msg = QueryMsg::Resolution {
+            poll_contract: "prediction".to_string(),
+        };
+        let res: ResolutionResponse =
+            from_binary(&entrypoints::query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(
+            res,
+            ResolutionResponse {
+                resolvers: None,
+                resolution: Some(Resolution {
+                    resolved_by: vec![Addr::unchecked("resolver1"), Addr::unchecked("resolver3")],
+                    winner: Some(0),
+                }),
+            }
+        );
+
+        // the delegation ends with the resolution
+        let info = mock_info("resolver2", &[]);
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, finish_poll(0)),
+            Err(ContractError::Unauthorized {})
         ));
     }
 
     #[test]
-    fn is_admin_test() {
+    fn roles_test() {
         let mut deps = mock_dependencies(&[]);
 
         let msg = InstantiateMsg {
@@ -307,18 +956,323 @@ mod meta_contract_tests {
         let info = mock_info("creator", &[]);
         let _res = entrypoints::instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
+        // admins are granted every role
+        for role in Role::all() {
+            assert!(!role
+                .is_granted(&deps.storage, &Addr::unchecked("creator"))
+                .unwrap());
+            assert!(role
+                .is_granted(&deps.storage, &Addr::unchecked("admin1"))
+                .unwrap());
+            assert!(role
+                .is_granted(&deps.storage, &Addr::unchecked("admin2"))
+                .unwrap());
+        }
+
+        // only config admins grant roles
+        let info = mock_info("resolver", &[]);
+        let msg = ExecuteMsg::GrantRole {
+            address: "resolver".to_string(),
+            role: Role::Resolver,
+        };
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::Unauthorized {})
+        ));
+
+        let info = mock_info("admin1", &[]);
+        let msg = ExecuteMsg::GrantRole {
+            address: "resolver".to_string(),
+            role: Role::Resolver,
+        };
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "grant_role"),
+                attr("address", "resolver"),
+                attr("role", "resolver")
+            ]
+        );
+
+        // a resolver finishes polls but cannot move funds
+        let info = mock_info("resolver", &[]);
+        let msg = ExecuteMsg::FinishPoll {
+            poll_contract: "prediction".to_string(),
+            poll_type: "prediction".to_string(),
+            winner: Some(0),
+        };
+        let _res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("resolver", &[]);
+        let msg = ExecuteMsg::Transfer {
+            recipient: "resolver".to_string(),
+            amount: Uint128::new(1),
+        };
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::Unauthorized {})
+        ));
+
+        let res = query_roles(deps.as_ref());
+        assert_eq!(
+            res.roles,
+            vec![
+                RoleInfo {
+                    address: Addr::unchecked("admin1"),
+                    roles: Role::all(),
+                },
+                RoleInfo {
+                    address: Addr::unchecked("admin2"),
+                    roles: Role::all(),
+                },
+                RoleInfo {
+                    address: Addr::unchecked("resolver"),
+                    roles: vec![Role::Resolver],
+                },
+            ]
+        );
+
+        // revoking the last role removes the address
+        let info = mock_info("admin1", &[]);
+        let msg = ExecuteMsg::RevokeRole {
+            address: "resolver".to_string(),
+            role: Role::Resolver,
+        };
+        let _res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(query_roles(deps.as_ref()).roles.len(), 2);
+
+        let info = mock_info("admin1", &[]);
+        let msg = ExecuteMsg::RevokeRole {
+            address: "admin2".to_string(),
+            role: Role::ConfigAdmin,
+        };
+        let _res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // the last config admin stays
+        let info = mock_info("admin1", &[]);
+        let msg = ExecuteMsg::RevokeRole {
+            address: "admin1".to_string(),
+            role: Role::ConfigAdmin,
+        };
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::LastConfigAdmin {})
+        ));
+    }
+
+    #[test]
+    fn migrate_legacy_config() {
+        let mut deps = mock_dependencies(&[]);
+
+        // state of 0.1.0, before roles and the deposit split
+        deps.storage.set(
+            b"config",
+            br#"{"admins":["admin"],"token_contract":"pollterra","creation_deposit":"1000","reclaimable_threshold":"1000","minimum_bet_amount":"1000","tax_percentage":"0.05"}"#,
+        );
+        State { num_contract: 0 }.save(&mut deps.storage).unwrap();
+        set_contract_version(&mut deps.storage, "crates.io:meta-contract", "0.1.0").unwrap();
+
+        // foreign contracts are refused
+        set_contract_version(&mut deps.storage, "crates.io:opinion-poll", "0.1.0").unwrap();
+        let msg = MigrateMsg {
+            reclaimable_threshold: None,
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
+        };
+        assert!(entrypoints::migrate(deps.as_mut(), mock_env(), msg).is_err());
+        set_contract_version(&mut deps.storage, "crates.io:meta-contract", "0.1.0").unwrap();
+
+        let msg = MigrateMsg {
+            reclaimable_threshold: Some(Uint128::new(2_000)),
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
+        };
+        let res = entrypoints::migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "migrate"),
+                attr("from_version", "0.1.0"),
+                attr("to_version", env!("CARGO_PKG_VERSION")),
+            ]
+        );
+
         let config = Config::load(&deps.storage).unwrap();
+        assert_eq!(config.token_contract, TOKEN_CONTRACT);
+        assert_eq!(config.creation_deposit, DEPOSIT_AMOUNT);
+        assert_eq!(config.reclaimable_threshold, Uint128::new(2_000));
+        assert_eq!(config.community_contract, None);
+        assert_eq!(config.deposit_split, DepositSplit::burn_all());
+        assert_eq!(
+            query_roles(deps.as_ref()).roles,
+            vec![RoleInfo {
+                address: Addr::unchecked("admin"),
+                roles: Role::all(),
+            }]
+        );
+
+        // downgrades are refused
+        set_contract_version(&mut deps.storage, "crates.io:meta-contract", "9.0.0").unwrap();
+        let msg = MigrateMsg {
+            reclaimable_threshold: None,
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
+        };
+        assert!(entrypoints::migrate(deps.as_mut(), mock_env(), msg).is_err());
+    }
+
+    #[test]
+    fn migrate_schedules_registered_polls() {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: PollQuerier {
+                base: MockQuerier::new(&[]),
+            },
+        };
+
+        // polls of 0.1.0 were never scheduled
+        deps.storage.set(
+            b"config",
+            br#"{"admins":["admin"],"token_contract":"pollterra","creation_deposit":"1000","reclaimable_threshold":"1000","minimum_bet_amount":"1000","tax_percentage":"0.05"}"#,
+        );
+        State { num_contract: 3 }.save(&mut deps.storage).unwrap();
+        for contract in ["opinion", "prediction", "unknown"].iter() {
+            CONTRACTS
+                .save(&mut deps.storage, &Addr::unchecked(*contract), &())
+                .unwrap();
+        }
+        set_contract_version(&mut deps.storage, "crates.io:meta-contract", "0.1.0").unwrap();
+
+        let msg = MigrateMsg {
+            reclaimable_threshold: None,
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
+        };
+        let _res = entrypoints::migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+        // only the opinion poll is finished once expired
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(END_TIME);
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::FinishExpired { limit: None };
+        let res = entrypoints::execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_always(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "opinion".to_string(),
+                    msg: to_binary(&OpinionPollExecuteMsg::FinishPoll {}).unwrap(),
+                    funds: vec![],
+                }),
+                entrypoints::FINISH_POLL_REPLY_ID_BASE,
+            )]
+        );
+    }
+
+    #[test]
+    fn pause_polls() {
+        let mut deps = mock_dependencies(&[]);
+        init_opinion_poll(deps.as_mut(), "opinion");
 
         let info = mock_info("creator", &[]);
-        assert!(!config.is_admin(&info.sender));
+        let msg = ExecuteMsg::UpdatePausers {
+            guardian: Some("guardian".to_string()),
+            governance: Some("governance".to_string()),
+        };
+        let _res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let info = mock_info("not-admin", &[]);
-        assert!(!config.is_admin(&info.sender));
+        let pause_msg = ExecuteMsg::PausePolls {
+            poll_contracts: vec!["opinion".to_string()],
+            operations: Some(vec![PauseOperation::Betting]),
+        };
+        let info = mock_info("anyone", &[]);
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, pause_msg.clone()),
+            Err(ContractError::Unauthorized {})
+        ));
 
-        let info = mock_info("admin1", &[]);
-        assert!(config.is_admin(&info.sender));
+        // only registered polls are sent the pause
+        let info = mock_info("guardian", &[]);
+        let msg = ExecuteMsg::PausePolls {
+            poll_contracts: vec!["opinion".to_string(), "unknown".to_string()],
+            operations: None,
+        };
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::UnregisteredPoll(_))
+        ));
+
+        let info = mock_info("guardian", &[]);
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, pause_msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "opinion".to_string(),
+                msg: to_binary(&OpinionPollExecuteMsg::Pause {
+                    operations: Some(vec![PauseOperation::Betting]),
+                })
+                .unwrap(),
+                funds: vec![],
+            }))]
+        );
+
+        let info = mock_info("governance", &[]);
+        let msg = ExecuteMsg::UnpausePolls {
+            poll_contracts: vec!["opinion".to_string()],
+            operations: None,
+        };
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "opinion".to_string(),
+                msg: to_binary(&OpinionPollExecuteMsg::Unpause { operations: None }).unwrap(),
+                funds: vec![],
+            }))]
+        );
 
-        let info = mock_info("admin2", &[]);
-        assert!(config.is_admin(&info.sender));
+        // the guardian cannot hand itself down to the polls
+        let msg = ExecuteMsg::UpdatePollPausers {
+            poll_contracts: vec!["opinion".to_string()],
+        };
+        let info = mock_info("guardian", &[]);
+        assert!(matches!(
+            entrypoints::execute(deps.as_mut(), mock_env(), info, msg.clone()),
+            Err(ContractError::Unauthorized {})
+        ));
+
+        let info = mock_info("creator", &[]);
+        let res = entrypoints::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "opinion".to_string(),
+                msg: to_binary(&OpinionPollExecuteMsg::UpdatePausers {
+                    guardian: Some("guardian".to_string()),
+                    governance: Some("governance".to_string()),
+                })
+                .unwrap(),
+                funds: vec![],
+            }))]
+        );
+    }
+
+    fn query_roles(deps: Deps) -> RolesResponse {
+        let msg = QueryMsg::Roles {
+            start_after: None,
+            limit: None,
+            order_by: Some(OrderBy::Asc),
+        };
+        from_binary(&entrypoints::query(deps, mock_env(), msg).unwrap()).unwrap()
     }
 }
@@ -6,9 +6,14 @@ use cw2::set_contract_version;
 
 use crate::error::ContractError;
 use crate::{executions, queries};
+use config::config::DepositSplit;
+use messages::migration::{migrate_poll_config_v0_2, migrate_version, Version};
 use messages::opinion_poll::execute_msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg};
 use messages::opinion_poll::query_msgs::QueryMsg;
-use messages::opinion_poll::state::{store_config, store_state, BetStatus, Config, State};
+use messages::opinion_poll::state::{
+    read_config, store_config, store_state, BetStatus, Config, State,
+};
+use messages::pause::{migrate_pausers, set_pausers};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:opinion-poll";
@@ -21,6 +26,15 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    let guardian = msg
+        .guardian
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+    let governance = msg
+        .governance
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+
     let config = Config {
         owner: info.sender.clone(),
         generator: msg.generator,
@@ -34,7 +48,15 @@ pub fn instantiate(
         resolution_time: 0u64,
         minimum_bet_amount: Uint128::zero(),
         tax_percentage: Decimal::zero(),
+        community_contract: msg
+            .community_contract
+            .map(|v| deps.api.addr_validate(&v))
+            .transpose()?,
+        deposit_split: msg.deposit_split.unwrap_or_else(DepositSplit::burn_all),
     };
+    if !config.has_valid_deposit_split() {
+        return Err(ContractError::InvalidDepositSplit {});
+    }
     let state = State {
         deposit_amount: msg.deposit_amount,
         deposit_reclaimed: false,
@@ -46,6 +68,7 @@ pub fn instantiate(
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     store_state(deps.storage, &state)?;
     store_config(deps.storage, &config)?;
+    set_pausers(deps.storage, guardian, governance)?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
@@ -69,12 +92,53 @@ pub fn execute(
         ExecuteMsg::TransferOwner { new_owner } => {
             executions::transfer_owner(deps, info, new_owner)
         }
+        ExecuteMsg::Pause { operations } => executions::pause(deps, info, true, operations),
+        ExecuteMsg::Unpause { operations } => executions::pause(deps, info, false, operations),
+        ExecuteMsg::UpdatePausers {
+            guardian,
+            governance,
+        } => executions::update_pausers(deps, info, guardian, governance),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let from = migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let mut config = if from < Version::new(0, 2, 0) {
+        migrate_poll_config_v0_2(deps.storage)?
+    } else {
+        read_config(deps.storage)?
+    };
+
+    if let Some(community_contract) = msg.community_contract {
+        config.community_contract = Some(deps.api.addr_validate(&community_contract)?);
+    }
+
+    if let Some(deposit_split) = msg.deposit_split {
+        config.deposit_split = deposit_split;
+    }
+
+    if !config.has_valid_deposit_split() {
+        return Err(ContractError::InvalidDepositSplit {});
+    }
+
+    store_config(deps.storage, &config)?;
+
+    let guardian = msg
+        .guardian
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+    let governance = msg
+        .governance
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+    migrate_pausers(deps.storage, guardian, governance)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", from.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -35,6 +35,9 @@ pub enum ContractError {
 
     #[error("Not enough total amount to reclaim the deposit, {0} is less than {1}")]
     InsufficientReclaimableThreshold(Uint128, Uint128),
+
+    #[error("Deposit split exceeds the deposit, or sends to an unset community contract")]
+    InvalidDepositSplit {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
@@ -10,6 +10,8 @@ use std::convert::TryInto;
 use messages::opinion_poll::state::{
     read_config, read_state, store_config, store_state, BetStatus, SIDES, VOTES,
 };
+use messages::pause::{set_paused, set_pausers, PauseInfo, PauseOperation};
+use messages::utils::split_deposit;
 
 pub fn vote(
     deps: DepsMut,
@@ -17,6 +19,8 @@ pub fn vote(
     info: MessageInfo,
     side: u64,
 ) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Voting)?;
+
     let config = read_config(deps.storage)?;
 
     // current block time is less than start time or larger than bet end time
@@ -133,29 +137,28 @@ pub fn finish_poll(
     state.winning_side = Some(winning_sides);
     state.status = BetStatus::Closed;
 
-    let mut cw20_msg = Cw20ExecuteMsg::Transfer {
-        recipient: config.generator.to_string(),
-        amount: state.deposit_amount,
-    };
-    if state.total_amount < config.reclaimable_threshold {
-        // TODO : transfer 50% to the community fund
-        cw20_msg = Cw20ExecuteMsg::Burn {
-            amount: state.deposit_amount,
-        };
-    }
     state.deposit_reclaimed = true;
     store_state(deps.storage, &state)?;
 
-    Ok(Response::new()
-        .add_attribute("method", "finish_poll")
-        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: config.token_contract,
-            msg: to_binary(&cw20_msg)?,
-            funds: vec![],
-        })))
+    let response = Response::new().add_attribute("method", "finish_poll");
+
+    if state.total_amount < config.reclaimable_threshold {
+        return Ok(split_deposit(response, &config, state.deposit_amount)?);
+    }
+
+    Ok(response.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: config.token_contract,
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: config.generator.to_string(),
+            amount: state.deposit_amount,
+        })?,
+        funds: vec![],
+    })))
 }
 
 pub fn reclaim_deposit(deps: DepsMut) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Claiming)?;
+
     let config = read_config(deps.storage)?;
     let mut state = read_state(deps.storage)?;
     if state.deposit_reclaimed {
@@ -199,3 +202,34 @@ pub fn transfer_owner(
 
     Ok(Response::new().add_attribute("method", "try_transfer_owner"))
 }
+
+pub fn pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+    operations: Option<Vec<PauseOperation>>,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if info.sender != config.owner && !PauseInfo::load(deps.storage)?.is_pauser(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(set_paused(deps.storage, paused, operations)?)
+}
+
+pub fn update_pausers(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<String>,
+    governance: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let guardian = guardian.map(|v| deps.api.addr_validate(&v)).transpose()?;
+    let governance = governance.map(|v| deps.api.addr_validate(&v)).transpose()?;
+
+    Ok(set_pausers(deps.storage, guardian, governance)?)
+}
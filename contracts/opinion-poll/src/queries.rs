@@ -5,6 +5,7 @@ use messages::opinion_poll::query_msgs::{
     VoteLiveResponse, VotePerSideResponse,
 };
 use messages::opinion_poll::state::{read_config, read_state, SIDES, VOTES};
+use messages::pause::PauseInfo;
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config = read_config(deps.storage)?;
@@ -13,7 +14,14 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 
 pub fn query_state(deps: Deps) -> StdResult<StateResponse> {
     let state = read_state(deps.storage)?;
-    Ok(state)
+    Ok(StateResponse {
+        deposit_amount: state.deposit_amount,
+        deposit_reclaimed: state.deposit_reclaimed,
+        status: state.status,
+        total_amount: state.total_amount,
+        winning_side: state.winning_side,
+        pause: PauseInfo::load(deps.storage)?,
+    })
 }
 
 pub fn query_poll_status(deps: Deps) -> StdResult<PollStatusResponse> {
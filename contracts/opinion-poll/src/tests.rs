@@ -1,14 +1,20 @@
 #[cfg(test)]
 mod prediction_poll_tests {
-    use crate::entrypoints::{execute, instantiate, query};
+    use crate::entrypoints::{execute, instantiate, migrate, query};
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
 
     use crate::error::ContractError;
-    use config::config::PollType;
-    use cosmwasm_std::{from_binary, Addr, Timestamp, Uint128};
-    use messages::opinion_poll::execute_msgs::{ExecuteMsg, InstantiateMsg};
+    use config::config::{DepositSplit, PollType};
+    use cosmwasm_std::{
+        attr, from_binary, to_binary, Addr, CosmosMsg, Decimal, Storage, Timestamp, Uint128,
+        WasmMsg,
+    };
+    use cw2::set_contract_version;
+    use cw20::Cw20ExecuteMsg;
+    use messages::opinion_poll::execute_msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg};
     use messages::opinion_poll::query_msgs::{QueryMsg, UserVoteResponse};
     use messages::opinion_poll::state::{Config, State};
+    use messages::pause::PauseInfo;
 
     const DEFAULT_RECLAIMABLE_THRESHOLD: Uint128 = Uint128::new(100);
     const DEPOSIT_AMOUNT: Uint128 = Uint128::new(1_000);
@@ -29,6 +35,10 @@ mod prediction_poll_tests {
             resolution_time: None,
             minimum_bet_amount: None,
             tax_percentage: None,
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg);
@@ -61,6 +71,10 @@ mod prediction_poll_tests {
             resolution_time: None,
             minimum_bet_amount: None,
             tax_percentage: None,
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -111,6 +125,10 @@ mod prediction_poll_tests {
             resolution_time: None,
             minimum_bet_amount: None,
             tax_percentage: None,
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -172,6 +190,10 @@ mod prediction_poll_tests {
             resolution_time: None,
             minimum_bet_amount: None,
             tax_percentage: None,
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -217,6 +239,10 @@ mod prediction_poll_tests {
             resolution_time: None,
             minimum_bet_amount: None,
             tax_percentage: None,
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -248,6 +274,102 @@ mod prediction_poll_tests {
         assert_eq!(vec![0u64, 1u64], value.winning_side.unwrap());
     }
 
+    #[test]
+    fn finish_with_deposit_split() {
+        let mut deps = mock_dependencies(&[]);
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1649673600);
+
+        let msg = InstantiateMsg {
+            generator: Addr::unchecked("generator"),
+            token_contract: "terra1pollterratoken".to_string(),
+            deposit_amount: DEPOSIT_AMOUNT,
+            reclaimable_threshold: DEFAULT_RECLAIMABLE_THRESHOLD,
+            poll_name: "test_poll".to_string(),
+            poll_type: PollType::Opinion,
+            end_time: 1653673600,
+            num_side: 2,
+            resolution_time: None,
+            minimum_bet_amount: None,
+            tax_percentage: None,
+            community_contract: Some("community".to_string()),
+            deposit_split: Some(DepositSplit {
+                burn: Decimal::percent(30),
+                community: Decimal::percent(50),
+            }),
+            guardian: None,
+            governance: None,
+        };
+
+        // the community share needs a community contract
+        let info = mock_info("creator", &[]);
+        let invalid_msg = InstantiateMsg {
+            community_contract: None,
+            ..msg.clone()
+        };
+        match instantiate(deps.as_mut(), mock_env(), info, invalid_msg) {
+            Err(ContractError::InvalidDepositSplit {}) => (),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::Vote { side: 0 };
+        let info = mock_info("user1", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(2000000000);
+
+        // 1 vote doesn't reach the reclaimable threshold
+        let msg = ExecuteMsg::FinishPoll {};
+        let info = mock_info("creator", &[]);
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "finish_poll"),
+                attr("burn_amount", "300"),
+                attr("community_amount", "500"),
+                attr("creator_amount", "200"),
+            ]
+        );
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|m| m.msg)
+                .collect::<Vec<CosmosMsg>>(),
+            vec![
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "terra1pollterratoken".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Burn {
+                        amount: Uint128::new(300),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "terra1pollterratoken".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: "community".to_string(),
+                        amount: Uint128::new(500),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "terra1pollterratoken".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: "generator".to_string(),
+                        amount: Uint128::new(200),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ]
+        );
+    }
+
     #[test]
     fn change_config() {
         let mut deps = mock_dependencies(&[]);
@@ -266,6 +388,10 @@ mod prediction_poll_tests {
             resolution_time: None,
             minimum_bet_amount: None,
             tax_percentage: None,
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
 
         let info = mock_info("creator", &[]);
@@ -289,4 +415,63 @@ mod prediction_poll_tests {
         let res = query(deps.as_ref(), env, msg).unwrap();
         assert_eq!("user1", from_binary::<Config>(&res).unwrap().owner.as_str());
     }
+
+    #[test]
+    fn migrate_legacy_config() {
+        let mut deps = mock_dependencies(&[]);
+
+        // config of 0.1.0, before the deposit split
+        deps.storage.set(
+            b"config",
+            br#"{"owner":"creator","generator":"generator","token_contract":"terra1pollterratoken","reclaimable_threshold":"100","poll_name":"test_poll","poll_type":"Opinion","end_time":1653673600,"num_side":2,"resolution_time":0,"minimum_bet_amount":"0","tax_percentage":"0"}"#,
+        );
+        set_contract_version(&mut deps.storage, "crates.io:opinion-poll", "0.1.0").unwrap();
+
+        // the community share needs a community contract
+        let msg = MigrateMsg {
+            community_contract: None,
+            deposit_split: Some(DepositSplit {
+                burn: Decimal::zero(),
+                community: Decimal::one(),
+            }),
+            guardian: None,
+            governance: None,
+        };
+        assert!(matches!(
+            migrate(deps.as_mut(), mock_env(), msg),
+            Err(ContractError::InvalidDepositSplit {})
+        ));
+        set_contract_version(&mut deps.storage, "crates.io:opinion-poll", "0.1.0").unwrap();
+
+        let msg = MigrateMsg {
+            community_contract: Some("community".to_string()),
+            deposit_split: None,
+            guardian: None,
+            governance: Some("governance".to_string()),
+        };
+        let res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "migrate"),
+                attr("from_version", "0.1.0"),
+                attr("to_version", env!("CARGO_PKG_VERSION")),
+            ]
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let config: Config = from_binary(&res).unwrap();
+        assert_eq!("test_poll", config.poll_name);
+        assert_eq!(DEFAULT_RECLAIMABLE_THRESHOLD, config.reclaimable_threshold);
+        assert_eq!(
+            Some(Addr::unchecked("community")),
+            config.community_contract
+        );
+        assert_eq!(DepositSplit::burn_all(), config.deposit_split);
+
+        // polls of 0.1.0 had no pausers
+        let pause_info = PauseInfo::load(&deps.storage).unwrap();
+        assert_eq!(None, pause_info.guardian);
+        assert_eq!(Some(Addr::unchecked("governance")), pause_info.governance);
+    }
 }
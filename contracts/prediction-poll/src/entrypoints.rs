@@ -6,9 +6,14 @@ use cw2::set_contract_version;
 
 use crate::error::ContractError;
 use crate::{executions, queries};
+use config::config::DepositSplit;
+use messages::migration::{migrate_poll_config_v0_2, migrate_version, Version};
+use messages::pause::{migrate_pausers, set_pausers};
 use messages::prediction_poll::execute_msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg};
 use messages::prediction_poll::query_msgs::QueryMsg;
-use messages::prediction_poll::state::{store_config, store_state, BetStatus, Config, State};
+use messages::prediction_poll::state::{
+    read_config, store_config, store_state, BetStatus, Config, State,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:prediction-poll";
@@ -21,6 +26,15 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    let guardian = msg
+        .guardian
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+    let governance = msg
+        .governance
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+
     let config = Config {
         owner: info.sender.clone(),
         generator: msg.generator,
@@ -33,7 +47,15 @@ pub fn instantiate(
         resolution_time: msg.resolution_time.unwrap(),
         minimum_bet_amount: msg.minimum_bet_amount.unwrap(),
         tax_percentage: msg.tax_percentage.unwrap(),
+        community_contract: msg
+            .community_contract
+            .map(|v| deps.api.addr_validate(&v))
+            .transpose()?,
+        deposit_split: msg.deposit_split.unwrap_or_else(DepositSplit::burn_all),
     };
+    if !config.has_valid_deposit_split() {
+        return Err(ContractError::InvalidDepositSplit {});
+    }
     if config.end_time >= config.resolution_time {
         return Err(ContractError::ShouldEndBeforeResolution {});
     }
@@ -49,6 +71,7 @@ pub fn instantiate(
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     store_state(deps.storage, &state)?;
     store_config(deps.storage, &config)?;
+    set_pausers(deps.storage, guardian, governance)?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
@@ -81,12 +104,53 @@ pub fn execute(
         ExecuteMsg::SetMinimumBet { amount } => {
             executions::try_set_minimun_bet_amount(deps, info, amount)
         }
+        ExecuteMsg::Pause { operations } => executions::try_pause(deps, info, true, operations),
+        ExecuteMsg::Unpause { operations } => executions::try_pause(deps, info, false, operations),
+        ExecuteMsg::UpdatePausers {
+            guardian,
+            governance,
+        } => executions::try_update_pausers(deps, info, guardian, governance),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let from = migrate_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let mut config = if from < Version::new(0, 2, 0) {
+        migrate_poll_config_v0_2(deps.storage)?
+    } else {
+        read_config(deps.storage)?
+    };
+
+    if let Some(community_contract) = msg.community_contract {
+        config.community_contract = Some(deps.api.addr_validate(&community_contract)?);
+    }
+
+    if let Some(deposit_split) = msg.deposit_split {
+        config.deposit_split = deposit_split;
+    }
+
+    if !config.has_valid_deposit_split() {
+        return Err(ContractError::InvalidDepositSplit {});
+    }
+
+    store_config(deps.storage, &config)?;
+
+    let guardian = msg
+        .guardian
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+    let governance = msg
+        .governance
+        .map(|v| deps.api.addr_validate(&v))
+        .transpose()?;
+    migrate_pausers(deps.storage, guardian, governance)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", from.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
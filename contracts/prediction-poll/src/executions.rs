@@ -6,10 +6,12 @@ use cosmwasm_std::{
 use cw20::Cw20ExecuteMsg;
 use std::str;
 
+use messages::pause::{set_paused, set_pausers, PauseInfo, PauseOperation};
 use messages::prediction_poll::state::{
     read_config, read_state, store_config, store_state, BetStatus, BETS, REWARDS,
     SIDE_TOTAL_AMOUNT, USER_TOTAL_AMOUNT,
 };
+use messages::utils::split_deposit;
 
 const DENOM: &str = "uusd";
 
@@ -19,6 +21,8 @@ pub fn try_bet(
     info: MessageInfo,
     side: u64,
 ) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Betting)?;
+
     let addr = info.sender.clone();
 
     let config = read_config(deps.storage)?;
@@ -192,23 +196,19 @@ pub fn try_finish_poll(
     state.status = BetStatus::Reward;
     state.winning_side = Some(vec![winner]);
 
-    let mut cw20_msg = Cw20ExecuteMsg::Transfer {
-        recipient: config.generator.to_string(),
-        amount: state.deposit_amount,
-    };
-    if state.total_amount < config.reclaimable_threshold {
-        // TODO : transfer 50% to the community fund
-        cw20_msg = Cw20ExecuteMsg::Burn {
-            amount: state.deposit_amount,
-        };
-    }
-
     state.deposit_reclaimed = true;
     store_state(deps.storage, &state)?;
 
+    if state.total_amount < config.reclaimable_threshold {
+        return Ok(split_deposit(response, &config, state.deposit_amount)?);
+    }
+
     Ok(response.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: config.token_contract,
-        msg: to_binary(&cw20_msg)?,
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: config.generator.to_string(),
+            amount: state.deposit_amount,
+        })?,
         funds: vec![],
     })))
 }
@@ -261,6 +261,8 @@ pub fn try_revert_poll(deps: DepsMut, info: MessageInfo) -> Result<Response, Con
 }
 
 pub fn try_claim(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Claiming)?;
+
     let addr = info.sender;
     let state = read_state(deps.storage)?;
 
@@ -292,6 +294,8 @@ pub fn try_claim(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractE
 }
 
 pub fn try_reclaim_deposit(deps: DepsMut) -> Result<Response, ContractError> {
+    PauseInfo::assert_active(deps.storage, PauseOperation::Claiming)?;
+
     let config = read_config(deps.storage)?;
     let mut state = read_state(deps.storage)?;
     if state.deposit_reclaimed {
@@ -350,3 +354,34 @@ pub fn try_set_minimun_bet_amount(
 
     Ok(Response::new().add_attribute("method", "try_set_minimun_amount"))
 }
+
+pub fn try_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+    operations: Option<Vec<PauseOperation>>,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if info.sender != config.owner && !PauseInfo::load(deps.storage)?.is_pauser(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(set_paused(deps.storage, paused, operations)?)
+}
+
+pub fn try_update_pausers(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<String>,
+    governance: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let guardian = guardian.map(|v| deps.api.addr_validate(&v)).transpose()?;
+    let governance = governance.map(|v| deps.api.addr_validate(&v)).transpose()?;
+
+    Ok(set_pausers(deps.storage, guardian, governance)?)
+}
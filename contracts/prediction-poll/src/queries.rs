@@ -1,5 +1,6 @@
 use cosmwasm_std::{Deps, Env, StdResult, Timestamp, Uint128};
 
+use messages::pause::PauseInfo;
 use messages::prediction_poll::query_msgs::{
     BetLiveResponse, BetStatusResponse, ConfigResponse, RewardLiveResponse, StateResponse,
     UserBetResponse, UserRewardsResponse, VotePerSideResponse,
@@ -15,7 +16,14 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 
 pub fn query_state(deps: Deps) -> StdResult<StateResponse> {
     let state = read_state(deps.storage)?;
-    Ok(state)
+    Ok(StateResponse {
+        deposit_amount: state.deposit_amount,
+        deposit_reclaimed: state.deposit_reclaimed,
+        status: state.status,
+        total_amount: state.total_amount,
+        winning_side: state.winning_side,
+        pause: PauseInfo::load(deps.storage)?,
+    })
 }
 
 pub fn query_bet_status(deps: Deps) -> StdResult<BetStatusResponse> {
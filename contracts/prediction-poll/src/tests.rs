@@ -4,12 +4,16 @@ mod prediction_poll_tests {
     use crate::error::ContractError;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
 
-    use config::config::PollType;
+    use config::config::{DepositSplit, PollType};
     use cosmwasm_std::{
-        coins, from_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Timestamp, Uint128,
+        attr, coins, from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, StdError,
+        Timestamp, Uint128, WasmMsg,
     };
+    use cw20::Cw20ExecuteMsg;
     use messages::prediction_poll::execute_msgs::{ExecuteMsg, InstantiateMsg};
-    use messages::prediction_poll::query_msgs::{QueryMsg, UserBetResponse, UserRewardsResponse};
+    use messages::prediction_poll::query_msgs::{
+        QueryMsg, StateResponse, UserBetResponse, UserRewardsResponse,
+    };
     use messages::prediction_poll::state::{Config, State};
 
     const DENOM: &str = "uusd";
@@ -33,6 +37,10 @@ mod prediction_poll_tests {
             resolution_time: Some(1653673600),
             minimum_bet_amount: Some(DEFAULT_MINIMUM_BET),
             tax_percentage: Some(Decimal::zero()),
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg);
@@ -47,6 +55,64 @@ mod prediction_poll_tests {
         assert_eq!(DEPOSIT_AMOUNT, state.deposit_amount);
     }
 
+    #[test]
+    fn bet_paused() {
+        let mut deps = mock_dependencies(&[]);
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1649673600);
+
+        let msg = InstantiateMsg {
+            generator: Addr::unchecked("generator"),
+            token_contract: "terra1pollterratoken".to_string(),
+            deposit_amount: DEPOSIT_AMOUNT,
+            reclaimable_threshold: DEFAULT_RECLAIMABLE_THRESHOLD,
+            poll_name: "test_poll".to_string(),
+            poll_type: PollType::Prediction,
+            end_time: 1653673599,
+            num_side: 2,
+            resolution_time: Some(1653673600),
+            minimum_bet_amount: Some(DEFAULT_MINIMUM_BET),
+            tax_percentage: Some(Decimal::zero()),
+            community_contract: None,
+            deposit_split: None,
+            guardian: Some("guardian".to_string()),
+            governance: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("user", &[]);
+        let msg = ExecuteMsg::Pause { operations: None };
+        assert!(matches!(
+            execute(deps.as_mut(), env.clone(), info, msg),
+            Err(ContractError::Unauthorized {})
+        ));
+
+        let info = mock_info("guardian", &[]);
+        let msg = ExecuteMsg::Pause { operations: None };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+        let state: StateResponse = from_binary(&res).unwrap();
+        assert!(state.pause.global);
+
+        let info = mock_info("user", &coins(1_000_000, DENOM));
+        let msg = ExecuteMsg::Bet { side: 0 };
+        assert!(matches!(
+            execute(deps.as_mut(), env.clone(), info, msg),
+            Err(ContractError::Std(StdError::GenericErr { .. }))
+        ));
+
+        // the owner can unpause as well
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Unpause { operations: None };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let info = mock_info("user", &coins(1_000_000, DENOM));
+        let msg = ExecuteMsg::Bet { side: 0 };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
     #[test]
     fn init_failed_end_time() {
         let mut deps = mock_dependencies(&[]);
@@ -63,6 +129,10 @@ mod prediction_poll_tests {
             resolution_time: Some(1653673600),
             minimum_bet_amount: Some(DEFAULT_MINIMUM_BET),
             tax_percentage: Some(Decimal::zero()),
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
         let info = mock_info("creator", &[]);
         assert!(matches!(
@@ -89,6 +159,10 @@ mod prediction_poll_tests {
             resolution_time: Some(1653673600),
             minimum_bet_amount: Some(DEFAULT_MINIMUM_BET),
             tax_percentage: Some(Decimal::zero()),
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -133,6 +207,10 @@ mod prediction_poll_tests {
             resolution_time: Some(1653673600),
             minimum_bet_amount: Some(DEFAULT_MINIMUM_BET),
             tax_percentage: Some(Decimal::zero()),
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -184,6 +262,10 @@ mod prediction_poll_tests {
             resolution_time: Some(1653673600),
             minimum_bet_amount: Some(DEFAULT_MINIMUM_BET),
             tax_percentage: Some(Decimal::percent(1_u64)),
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -227,6 +309,103 @@ mod prediction_poll_tests {
         assert_eq!(Uint128::new(2_980_000), value.reward);
     }
 
+    #[test]
+    fn finish_with_deposit_split() {
+        let mut deps = mock_dependencies(&[]);
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1649673600);
+
+        let instantiate_msg =
+            |community_contract: Option<&str>, burn: u64, community: u64| InstantiateMsg {
+                generator: Addr::unchecked("generator"),
+                token_contract: "terra1pollterratoken".to_string(),
+                deposit_amount: DEPOSIT_AMOUNT,
+                reclaimable_threshold: Uint128::new(10_000_000),
+                poll_name: "test_poll".to_string(),
+                poll_type: PollType::Prediction,
+                end_time: 1653673599,
+                num_side: 2,
+                resolution_time: Some(1653673600),
+                minimum_bet_amount: Some(DEFAULT_MINIMUM_BET),
+                tax_percentage: Some(Decimal::percent(1_u64)),
+                community_contract: community_contract.map(|v| v.to_string()),
+                deposit_split: Some(DepositSplit {
+                    burn: Decimal::percent(burn),
+                    community: Decimal::percent(community),
+                }),
+                guardian: None,
+                governance: None,
+            };
+
+        // the split cannot exceed the deposit or send to no community contract
+        for msg in [
+            instantiate_msg(Some("community"), 60, 50),
+            instantiate_msg(None, 30, 50),
+        ] {
+            let info = mock_info("creator", &[]);
+            match instantiate(deps.as_mut(), mock_env(), info, msg) {
+                Err(ContractError::InvalidDepositSplit {}) => (),
+                res => panic!("Unexpected result: {:?}", res),
+            }
+        }
+
+        let info = mock_info("creator", &[]);
+        let msg = instantiate_msg(Some("community"), 30, 50);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::Bet { side: 0 };
+        let info = mock_info("user1", &coins(1_000_000, DENOM));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(2000000000);
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(1_000_000, DENOM));
+
+        // the bets don't reach the reclaimable threshold
+        let msg = ExecuteMsg::FinishPoll { winner: 0 };
+        let info = mock_info("creator", &[]);
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert!(res.attributes.ends_with(&[
+            attr("burn_amount", "300"),
+            attr("community_amount", "500"),
+            attr("creator_amount", "200"),
+        ]));
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|m| m.msg)
+                .collect::<Vec<CosmosMsg>>(),
+            vec![
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "terra1pollterratoken".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Burn {
+                        amount: Uint128::new(300),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "terra1pollterratoken".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: "community".to_string(),
+                        amount: Uint128::new(500),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "terra1pollterratoken".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: "generator".to_string(),
+                        amount: Uint128::new(200),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ]
+        );
+    }
+
     #[test]
     fn proper_claim() {
         let mut deps = mock_dependencies(&[]);
@@ -245,6 +424,10 @@ mod prediction_poll_tests {
             resolution_time: Some(1653673600),
             minimum_bet_amount: Some(DEFAULT_MINIMUM_BET),
             tax_percentage: Some(Decimal::percent(1_u64)),
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -320,6 +503,10 @@ mod prediction_poll_tests {
             resolution_time: Some(6400000),
             minimum_bet_amount: Some(DEFAULT_MINIMUM_BET),
             tax_percentage: Some(Decimal::zero()),
+            community_contract: None,
+            deposit_split: None,
+            guardian: None,
+            governance: None,
         };
 
         let info = mock_info("creator", &[]);
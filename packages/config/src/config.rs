@@ -17,6 +17,49 @@ pub struct PollConfig {
     pub resolution_time: u64,
     pub minimum_bet_amount: Uint128,
     pub tax_percentage: Decimal,
+    // where the deposit goes when the poll doesn't reach the reclaimable threshold
+    pub community_contract: Option<Addr>,
+    pub deposit_split: DepositSplit,
+}
+
+impl PollConfig {
+    /// the deposit split is within the deposit and only sends to a configured community contract
+    pub fn has_valid_deposit_split(&self) -> bool {
+        self.deposit_split.is_valid()
+            && (self.community_contract.is_some() || self.deposit_split.community.is_zero())
+    }
+}
+
+/// Split of an unreclaimable creation deposit.
+/// `burn` and `community` are ratios of the deposit, the creator gets the rest.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositSplit {
+    pub burn: Decimal,
+    pub community: Decimal,
+}
+
+impl DepositSplit {
+    pub fn burn_all() -> Self {
+        DepositSplit {
+            burn: Decimal::one(),
+            community: Decimal::zero(),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.burn + self.community <= Decimal::one()
+    }
+
+    /// returns (burn, community, creator) amounts of the deposit
+    pub fn split(&self, amount: Uint128) -> (Uint128, Uint128, Uint128) {
+        let burn_amount = amount * self.burn;
+        let community_amount = amount * self.community;
+        let creator_amount = amount
+            .checked_sub(burn_amount + community_amount)
+            .unwrap_or_else(|_| Uint128::zero());
+
+        (burn_amount, community_amount, creator_amount)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -1,15 +1,24 @@
+use crate::pause::PauseOperation;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::Uint128;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    pub admins: Option<Vec<String>>,
+    // able to pause the contract, kept when unset
+    pub guardian: Option<String>,
+    pub governance: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub admins: Vec<String>,
     pub managing_token: String,
+    // able to pause the contract
+    pub guardian: Option<String>,
+    pub governance: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -30,4 +39,14 @@ pub enum ExecuteMsg {
         recipient: String,
         amount: Uint128,
     },
+    Pause {
+        operations: Option<Vec<PauseOperation>>,
+    },
+    Unpause {
+        operations: Option<Vec<PauseOperation>>,
+    },
+    UpdatePausers {
+        guardian: Option<String>,
+        governance: Option<String>,
+    },
 }
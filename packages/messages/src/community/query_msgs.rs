@@ -2,6 +2,7 @@ use cosmwasm_std::Uint128;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::pause::PauseInfo;
 use crate::utils::OrderBy;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -23,6 +24,7 @@ pub enum QueryMsg {
 pub struct ContractConfigResponse {
     pub admins: Vec<String>,
     pub managing_token: String,
+    pub pause: PauseInfo,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
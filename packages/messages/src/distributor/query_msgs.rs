@@ -1,3 +1,4 @@
+use crate::pause::PauseInfo;
 use cosmwasm_std::Uint128;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -14,6 +15,7 @@ pub enum QueryMsg {
 pub struct ContractConfigResponse {
     pub admins: Vec<String>,
     pub managing_token: String,
+    pub pause: PauseInfo,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
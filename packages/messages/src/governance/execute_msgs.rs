@@ -1,25 +1,50 @@
-use super::state::VoteOption;
+use super::state::{PollParams, StakeAssetMsg, TargetPollParamsMsg, VoteOption};
+use crate::pause::PauseOperation;
 use cosmwasm_std::{Decimal, Uint128};
+use cw0::Duration;
 use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    pub quorum: Option<Decimal>,
+    pub threshold: Option<Decimal>,
+    pub veto_threshold: Option<Decimal>,
+    pub voting_period: Option<Duration>,
+    pub timelock_period: Option<Duration>,
+    pub expiration_period: Option<Duration>,
+    pub proposal_deposit: Option<Uint128>,
+    pub snapshot_period: Option<Duration>,
+    pub unbonding_period: Option<u64>,
+    // the contracts param changes can target, kept when unset
+    pub meta_contract: Option<String>,
+    pub community_contract: Option<String>,
+    pub distributor_contract: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub quorum: Decimal,
     pub threshold: Decimal,
-    pub voting_period: u64,
-    pub timelock_period: u64,
-    pub expiration_period: u64,
+    // share of NoWithVeto votes above which a poll is rejected and its deposit burnt
+    pub veto_threshold: Decimal,
+    // the periods are all measured either in blocks or in seconds
+    pub voting_period: Duration,
+    pub timelock_period: Duration,
+    pub expiration_period: Duration,
     pub proposal_deposit: Uint128,
-    pub snapshot_period: u64,
+    pub snapshot_period: Duration,
+    // blocks withdrawn tokens wait before they can be claimed
+    pub unbonding_period: u64,
+    // pauses the contract and vetoes passed polls during their timelock period,
+    // replaced through `UpdatePausers`
+    pub guardian: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
     CastVote {
@@ -27,12 +52,29 @@ pub enum ExecuteMsg {
         vote: VoteOption,
         amount: Uint128,
     },
+    // the delegate votes with the whole stake of the sender unless the sender votes itself
+    Delegate {
+        delegate: String,
+    },
+    Undelegate {},
     EndPoll {
         poll_id: u64,
     },
+    // the creator cancels the poll and gets the deposit back while it has no votes
+    CancelPoll {
+        poll_id: u64,
+    },
+    // the pause guardian vetoes a passed poll before its timelock period is over
+    VetoPoll {
+        poll_id: u64,
+    },
     ExecutePoll {
         poll_id: u64,
     },
+    // internal, sends the messages of a poll executed by the contract itself
+    ExecutePollMsgs {
+        poll_id: u64,
+    },
     ExpirePoll {
         poll_id: u64,
     },
@@ -45,14 +87,55 @@ pub enum ExecuteMsg {
     WithdrawVotingTokens {
         amount: Option<Uint128>,
     },
+    // withdraws a stake asset other than POL, by default all of it
+    WithdrawAssetTokens {
+        token: String,
+        amount: Option<Uint128>,
+    },
+    // moves the end of the lock of the sender the weeks later
+    ExtendLock {
+        duration: u64,
+    },
+    // sends the POL of the lock of the sender once it has ended
+    WithdrawExpired {},
+    // sends the withdrawn tokens whose unbonding period is over
+    ClaimUnbonded {},
+    ClaimRewards {},
+    // stakes the POL rewards, uusd rewards are left to be claimed
+    CompoundRewards {},
+    // distributes the sent uusd and any uusd transferred since the last deposit, such as poll fees
+    DepositReward {},
     UpdateConfig {
         owner: Option<String>,
         quorum: Option<Decimal>,
         threshold: Option<Decimal>,
-        voting_period: Option<u64>,
-        timelock_period: Option<u64>,
-        expiration_period: Option<u64>,
+        veto_threshold: Option<Decimal>,
+        voting_period: Option<Duration>,
+        timelock_period: Option<Duration>,
+        expiration_period: Option<Duration>,
         proposal_deposit: Option<Uint128>,
-        snapshot_period: Option<u64>,
+        snapshot_period: Option<Duration>,
+        unbonding_period: Option<u64>,
+        text_poll_params: Option<PollParams>,
+        // replaces all the requirements per target contract
+        target_poll_params: Option<Vec<TargetPollParamsMsg>>,
+        deposit_period: Option<Duration>,
+        // replaces all the other assets which can be staked and their weights
+        stake_assets: Option<Vec<StakeAssetMsg>>,
+        max_lock_weeks: Option<u64>,
+        // the contracts param changes can target
+        meta_contract: Option<String>,
+        community_contract: Option<String>,
+        distributor_contract: Option<String>,
+    },
+    Pause {
+        operations: Option<Vec<PauseOperation>>,
+    },
+    Unpause {
+        operations: Option<Vec<PauseOperation>>,
+    },
+    UpdatePausers {
+        guardian: Option<String>,
+        governance: Option<String>,
     },
 }
@@ -1,3 +1,4 @@
 pub mod execute_msgs;
+pub mod param_change;
 pub mod query_msgs;
 pub mod state;
@@ -0,0 +1,153 @@
+use config::config::DepositSplit;
+use cosmwasm_std::{to_binary, Binary, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg};
+use cw0::Duration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::execute_msgs::ExecuteMsg as GovernanceExecuteMsg;
+use super::state::{PollExecuteMsg, PollParams, StakeAssetMsg, TargetPollParamsMsg};
+use crate::community::execute_msgs::ExecuteMsg as CommunityExecuteMsg;
+use crate::distributor::execute_msgs::ExecuteMsg as DistributorExecuteMsg;
+use crate::meta_contract::execute_msgs::ExecuteMsg as MetaContractExecuteMsg;
+use crate::meta_contract::state::Role;
+
+/// A config change of a contract, executed when the poll proposing it passes.
+/// The target is governance itself or one of the contracts known to it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParamChangeMsg {
+    pub order: u64,
+    pub target: String,
+    pub params: ParamChange,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum ParamChange {
+    // the target is governance itself
+    Governance {
+        owner: Option<String>,
+        quorum: Option<Decimal>,
+        threshold: Option<Decimal>,
+        veto_threshold: Option<Decimal>,
+        voting_period: Option<Duration>,
+        timelock_period: Option<Duration>,
+        expiration_period: Option<Duration>,
+        proposal_deposit: Option<Uint128>,
+        snapshot_period: Option<Duration>,
+        unbonding_period: Option<u64>,
+        text_poll_params: Option<PollParams>,
+        target_poll_params: Option<Vec<TargetPollParamsMsg>>,
+        deposit_period: Option<Duration>,
+        stake_assets: Option<Vec<StakeAssetMsg>>,
+        max_lock_weeks: Option<u64>,
+        meta_contract: Option<String>,
+        community_contract: Option<String>,
+        distributor_contract: Option<String>,
+    },
+    MetaContract {
+        creation_deposit: Option<Uint128>,
+        reclaimable_threshold: Option<Uint128>,
+        community_contract: Option<String>,
+        deposit_split: Option<DepositSplit>,
+    },
+    // grants the role when `granted`, revokes it otherwise
+    MetaContractRole {
+        address: String,
+        role: Role,
+        granted: bool,
+    },
+    Community {
+        admins: Vec<String>,
+    },
+    Distributor {
+        admins: Vec<String>,
+    },
+}
+
+impl ParamChangeMsg {
+    /// The poll message executing the change on the target
+    pub fn to_poll_execute_msg(&self) -> StdResult<PollExecuteMsg> {
+        Ok(PollExecuteMsg {
+            order: self.order,
+            msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: self.target.clone(),
+                msg: self.params.to_binary()?,
+                funds: vec![],
+            }),
+        })
+    }
+}
+
+impl ParamChange {
+    fn to_binary(&self) -> StdResult<Binary> {
+        match self.clone() {
+            ParamChange::Governance {
+                owner,
+                quorum,
+                threshold,
+                veto_threshold,
+                voting_period,
+                timelock_period,
+                expiration_period,
+                proposal_deposit,
+                snapshot_period,
+                unbonding_period,
+                text_poll_params,
+                target_poll_params,
+                deposit_period,
+                stake_assets,
+                max_lock_weeks,
+                meta_contract,
+                community_contract,
+                distributor_contract,
+            } => to_binary(&GovernanceExecuteMsg::UpdateConfig {
+                owner,
+                quorum,
+                threshold,
+                veto_threshold,
+                voting_period,
+                timelock_period,
+                expiration_period,
+                proposal_deposit,
+                snapshot_period,
+                unbonding_period,
+                text_poll_params,
+                target_poll_params,
+                deposit_period,
+                stake_assets,
+                max_lock_weeks,
+                meta_contract,
+                community_contract,
+                distributor_contract,
+            }),
+            ParamChange::MetaContract {
+                creation_deposit,
+                reclaimable_threshold,
+                community_contract,
+                deposit_split,
+            } => to_binary(&MetaContractExecuteMsg::UpdateConfig {
+                creation_deposit,
+                reclaimable_threshold,
+                community_contract,
+                deposit_split,
+            }),
+            ParamChange::MetaContractRole {
+                address,
+                role,
+                granted,
+            } => match granted {
+                true => to_binary(&MetaContractExecuteMsg::GrantRole { address, role }),
+                false => to_binary(&MetaContractExecuteMsg::RevokeRole { address, role }),
+            },
+            ParamChange::Community { admins } => to_binary(&CommunityExecuteMsg::UpdateAdmins {
+                admins: Some(admins),
+            }),
+            ParamChange::Distributor { admins } => {
+                to_binary(&DistributorExecuteMsg::UpdateAdmins {
+                    admins: Some(admins),
+                })
+            }
+        }
+    }
+}
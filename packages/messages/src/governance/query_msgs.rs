@@ -13,12 +13,22 @@ pub enum QueryMsg {
     Staker {
         address: String,
     },
+    // Unbondings returns the withdrawals of the address waiting to be claimed
+    Unbondings {
+        address: String,
+    },
+    // StakerRewards returns the rewards the address can claim
+    StakerRewards {
+        address: String,
+    },
     // Poll returns the information related to a Poll if that poll exists
     Poll {
         poll_id: u64,
     },
+    // Polls returns a range of polls, only those with the status or of the creator when given
     Polls {
         filter: Option<PollStatus>,
+        creator: Option<String>,
         start_after: Option<u64>,
         limit: Option<u32>,
         order_by: Option<OrderBy>,
@@ -30,4 +40,31 @@ pub enum QueryMsg {
         limit: Option<u32>,
         order_by: Option<OrderBy>,
     },
+    // Delegation returns the delegate of the address and the delegators voting through it
+    Delegation {
+        address: String,
+    },
+    // VoterHistory returns the votes of the address by poll, the latest polls first
+    VoterHistory {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    // Tally returns the votes of a poll against the quorum and thresholds it must reach
+    Tally {
+        poll_id: u64,
+    },
+    // LockedPolls returns the polls in progress locking the stake of the address
+    LockedPolls {
+        address: String,
+    },
+    // VeLock returns the lock of the address and its voting power at the height, by default the current one
+    VeLock {
+        address: String,
+        height: Option<u64>,
+    },
+    // TotalVePower returns the voting power of all the locks at the height, by default the current one
+    TotalVePower {
+        height: Option<u64>,
+    },
 }
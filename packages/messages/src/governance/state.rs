@@ -1,68 +1,334 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Binary, CanonicalAddr, Decimal, Order, StdResult, Storage, Uint128};
-use cosmwasm_storage::{
-    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
-    Singleton,
+use super::param_change::ParamChangeMsg;
+use crate::pause::PauseInfo;
+pub use crate::utils::OrderBy;
+use crate::utils::{addr_range_option, id_range_option};
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, Order, StdError, StdResult, Storage, Uint128};
+use cw0::{Duration, Expiration};
+use cw_storage_plus::{
+    Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, SnapshotMap, Strategy, U64Key,
 };
+use std::convert::TryInto;
 use std::fmt;
 
 use std::cmp::Ordering;
 
-static KEY_CONFIG: &[u8] = b"config";
-static KEY_STATE: &[u8] = b"state";
-
-static PREFIX_POLL_INDEXER: &[u8] = b"poll_indexer";
-static PREFIX_POLL_VOTER: &[u8] = b"poll_voter";
-static PREFIX_POLL: &[u8] = b"poll";
-static PREFIX_BANK: &[u8] = b"bank";
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const STATE: Item<State> = Item::new("state");
+pub const STAKERS: Map<&Addr, TokenManager> = Map::new("stakers");
+// delegator: delegate voting on its behalf
+pub const DELEGATIONS: Map<&Addr, Addr> = Map::new("delegation");
+// delegate: delegators it votes for
+pub const DELEGATORS: Map<&Addr, Vec<Addr>> = Map::new("delegators");
+pub const UNBONDINGS: Map<&Addr, Vec<Unbonding>> = Map::new("unbonding");
+// (poll_id, voter): vote, removed once the poll ended and the voter unlocked the stake
+pub const POLL_VOTERS: Map<(U64Key, &Addr), VoterInfo> = Map::new("poll_voters");
+// (voter, poll_id): vote, kept after the poll ends
+pub const VOTER_HISTORY: Map<(&Addr, U64Key), VoterInfo> = Map::new("voter_history");
+
+// shares of each staker and of all stakers, checkpointed to weight votes by the shares at poll start
+const STAKED_SHARES: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "staked_shares",
+    "staked_shares__checkpoints",
+    "staked_shares__changelog",
+    Strategy::EveryBlock,
+);
+const TOTAL_SHARE: SnapshotMap<&str, Uint128> = SnapshotMap::new(
+    "total_share",
+    "total_share__checkpoints",
+    "total_share__changelog",
+    Strategy::EveryBlock,
+);
+const TOTAL_SHARE_KEY: &str = "total";
+// staked amounts of the other stake assets by staker and in total, checkpointed like the shares
+const STAKED_ASSETS: SnapshotMap<(&Addr, &Addr), Uint128> = SnapshotMap::new(
+    "staked_assets",
+    "staked_assets__checkpoints",
+    "staked_assets__changelog",
+    Strategy::EveryBlock,
+);
+const TOTAL_ASSETS: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "total_assets",
+    "total_assets__checkpoints",
+    "total_assets__changelog",
+    Strategy::EveryBlock,
+);
+
+pub struct PollIndexes<'a> {
+    // (status, poll_id)
+    pub status: MultiIndex<'a, (String, Vec<u8>), Poll>,
+    // (creator, poll_id)
+    pub creator: MultiIndex<'a, (Addr, Vec<u8>), Poll>,
+}
+
+impl<'a> IndexList<Poll> for PollIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Poll>> + '_> {
+        let v: Vec<&dyn Index<Poll>> = vec![&self.status, &self.creator];
+        Box::new(v.into_iter())
+    }
+}
 
-const MAX_LIMIT: u32 = 30;
-const DEFAULT_LIMIT: u32 = 10;
+// locks end at the beginning of a week of blocks, at about 6 seconds a block
+pub const LOCK_WEEK_BLOCKS: u64 = 100_800;
+// POL locked for this long votes as much as staked POL, more when locked for longer
+const LOCK_POWER_WEEKS: u64 = 52;
+
+// POL locked by each staker, checkpointed to weight votes by the locks at poll start
+const VE_LOCKS: SnapshotMap<&Addr, VeLock> = SnapshotMap::new(
+    "ve_locks",
+    "ve_locks__checkpoints",
+    "ve_locks__changelog",
+    Strategy::EveryBlock,
+);
+// sums of the locks voting at the height of the checkpoint
+const VE_TOTALS: SnapshotMap<&str, VeTotals> = SnapshotMap::new(
+    "ve_totals",
+    "ve_totals__checkpoints",
+    "ve_totals__changelog",
+    Strategy::EveryBlock,
+);
+const VE_TOTALS_KEY: &str = "total";
+// end height: amount of the locks ending then, which stop voting from then on
+const VE_LOCK_ENDS: Map<U64Key, Uint128> = Map::new("ve_lock_ends");
+
+// the total voting power of the locks at a later height h is
+// (weighted_end - h * amount) / (LOCK_WEEK_BLOCKS * LOCK_POWER_WEEKS),
+// once the locks ending until h are taken out
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct VeTotals {
+    height: u64,
+    amount: Uint128,
+    weighted_end: Uint128,
+}
+
+impl VeTotals {
+    fn add(&mut self, lock: &VeLock) -> StdResult<()> {
+        self.amount = self.amount.checked_add(lock.amount)?;
+        self.weighted_end = self
+            .weighted_end
+            .checked_add(lock.amount.checked_mul(lock.end_height.into())?)?;
+        Ok(())
+    }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum OrderBy {
-    Asc,
-    Desc,
-}
+    fn sub(&mut self, lock: &VeLock) -> StdResult<()> {
+        self.amount = self.amount.checked_sub(lock.amount)?;
+        self.weighted_end = self
+            .weighted_end
+            .checked_sub(lock.amount.checked_mul(lock.end_height.into())?)?;
+        Ok(())
+    }
 
-impl From<OrderBy> for Order {
-    fn from(o: OrderBy) -> Order {
-        if o == OrderBy::Asc {
-            Order::Ascending
-        } else {
-            Order::Descending
+    // takes out the locks ending after the totals were checkpointed until the height
+    fn end_locks(&mut self, storage: &dyn Storage, height: u64) -> StdResult<()> {
+        if height <= self.height {
+            return Ok(());
         }
+        let ends = VE_LOCK_ENDS
+            .range(
+                storage,
+                Some(Bound::exclusive_int(self.height)),
+                Some(Bound::inclusive_int(height)),
+                Order::Ascending,
+            )
+            .collect::<StdResult<Vec<(Vec<u8>, Uint128)>>>()?;
+        for (key, amount) in ends {
+            self.sub(&VeLock {
+                amount,
+                end_height: u64_from_key(&key)?,
+            })?;
+        }
+        self.height = height;
+        Ok(())
     }
 }
 
+/// Polls by id, indexed by status and creator
+pub fn polls<'a>() -> IndexedMap<'a, U64Key, Poll, PollIndexes<'a>> {
+    let indexes = PollIndexes {
+        status: MultiIndex::new(
+            |poll, pk| (poll.status.to_string(), pk),
+            "polls",
+            "polls__status",
+        ),
+        creator: MultiIndex::new(
+            |poll, pk| (poll.creator.clone(), pk),
+            "polls",
+            "polls__creator",
+        ),
+    };
+    IndexedMap::new("polls", indexes)
+}
+
 #[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TokenManager {
     pub share: Uint128,                        // total staked balance
     pub locked_balance: Vec<(u64, VoterInfo)>, // maps poll_id to weight voted
+    // reward indexes of the last accrual and the rewards accrued until then
+    #[serde(default)]
+    pub reward_index: Decimal,
+    #[serde(default)]
+    pub pending_reward: Uint128,
+    #[serde(default)]
+    pub native_reward_index: Decimal,
+    #[serde(default)]
+    pub pending_native_reward: Uint128,
+    // amounts of the other stake assets, which vote by their weight but earn no rewards
+    #[serde(default)]
+    pub assets: Vec<(Addr, Uint128)>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
-    pub owner: CanonicalAddr,
-    pub pollterra_token: CanonicalAddr,
+    pub owner: Addr,
+    pub pollterra_token: Addr,
     pub quorum: Decimal,
     pub threshold: Decimal,
-    pub voting_period: u64,
-    pub timelock_period: u64,
-    pub expiration_period: u64,
+    pub veto_threshold: Decimal,
+    // the poll periods are all measured either in blocks or in seconds,
+    // their unit is kept while polls are live
+    pub voting_period: Duration,
+    pub timelock_period: Duration,
+    pub expiration_period: Duration,
     pub proposal_deposit: Uint128,
-    pub snapshot_period: u64,
+    pub snapshot_period: Duration,
+    // always in blocks unlike the poll periods, withdrawn tokens wait this long to be claimed
+    pub unbonding_period: u64,
+    // the deposit, quorum and threshold above apply to the polls with messages,
+    // polls without messages use their own ones when they are set
+    #[serde(default)]
+    pub text_poll_params: Option<PollParams>,
+    // stricter requirements of the polls with messages sent to some contracts
+    #[serde(default)]
+    pub target_poll_params: Vec<TargetPollParams>,
+    // polls created with a partial deposit wait this long for the rest of it,
+    // the whole deposit is required at creation when it is not set
+    #[serde(default)]
+    pub deposit_period: Option<Duration>,
+    // cw20 tokens which can be staked besides POL, such as POL-UST LP tokens
+    #[serde(default)]
+    pub stake_assets: Vec<StakeAsset>,
+    // the longest POL can be locked for, no lock can be made when zero
+    #[serde(default)]
+    pub max_lock_weeks: u64,
+    // the contracts param changes can target besides governance, none of them when unset
+    #[serde(default)]
+    pub meta_contract: Option<Addr>,
+    #[serde(default)]
+    pub community_contract: Option<Addr>,
+    #[serde(default)]
+    pub distributor_contract: Option<Addr>,
+}
+
+/// Deposit and vote requirements of a poll
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollParams {
+    pub proposal_deposit: Uint128,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TargetPollParams {
+    pub contract: Addr,
+    pub params: PollParams,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TargetPollParamsMsg {
+    pub contract: String,
+    pub params: PollParams,
+}
+
+/// A token staked for voting besides POL, a staked token votes with the weight
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakeAsset {
+    pub token: Addr,
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakeAssetMsg {
+    pub token: String,
+    pub weight: Decimal,
+}
+
+impl Config {
+    /// Requirements of a poll sending messages to the targets, or of a text poll without messages.
+    /// Among the requirements applying to the targets, the highest ones are kept.
+    pub fn poll_params(&self, targets: Option<Vec<Addr>>) -> PollParams {
+        let mut params = PollParams {
+            proposal_deposit: self.proposal_deposit,
+            quorum: self.quorum,
+            threshold: self.threshold,
+        };
+
+        let targets = match targets {
+            Some(targets) => targets,
+            None => return self.text_poll_params.clone().unwrap_or(params),
+        };
+
+        for target in self.target_poll_params.iter() {
+            if targets.contains(&target.contract) {
+                params.proposal_deposit =
+                    params.proposal_deposit.max(target.params.proposal_deposit);
+                params.quorum = params.quorum.max(target.params.quorum);
+                params.threshold = params.threshold.max(target.params.threshold);
+            }
+        }
+
+        params
+    }
+
+    /// Voting weight of a stake asset, none when it cannot be staked
+    pub fn asset_weight(&self, token: &Addr) -> Option<Decimal> {
+        self.stake_assets
+            .iter()
+            .find(|asset| &asset.token == token)
+            .map(|asset| asset.weight)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
-    pub contract_addr: CanonicalAddr,
+    pub contract_addr: Addr,
     pub poll_count: u64,
     pub total_share: Uint128,
     pub total_deposit: Uint128,
+    // rewards per share deposited since the rewards are accounted
+    #[serde(default)]
+    pub reward_index: Decimal,
+    // POL held for rewards not claimed yet, not part of the staked balance
+    #[serde(default)]
+    pub pending_reward: Uint128,
+    #[serde(default)]
+    pub native_reward_index: Decimal,
+    // uusd held for rewards not claimed yet
+    #[serde(default)]
+    pub native_reward_balance: Uint128,
+    // POL withdrawn but not claimed yet
+    #[serde(default)]
+    pub total_unbonding: Uint128,
+    // POL locked for voting power, until withdrawn
+    #[serde(default)]
+    pub total_locked: Uint128,
+}
+
+impl State {
+    /// POL held by governance which is not staked
+    pub fn unstaked_balance(&self) -> Uint128 {
+        self.total_deposit + self.pending_reward + self.total_unbonding + self.total_locked
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Unbonding {
+    pub amount: Uint128,
+    pub release_height: u64,
+    // the stake asset withdrawn, POL when none
+    #[serde(default)]
+    pub token: Option<Addr>,
 }
 
 // State related to Poll Execution
@@ -70,15 +336,14 @@ pub struct State {
 #[serde(rename_all = "snake_case")]
 pub struct PollExecuteMsg {
     pub order: u64,
-    pub contract: String,
-    pub msg: Binary,
+    // bank sends and wasm executions, migrations and admin updates
+    pub msg: CosmosMsg,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct ExecuteData {
     pub order: u64,
-    pub contract: CanonicalAddr,
-    pub msg: Binary,
+    pub msg: CosmosMsg,
 }
 
 impl Eq for ExecuteData {}
@@ -105,11 +370,19 @@ impl PartialEq for ExecuteData {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Poll {
     pub id: u64,
-    pub creator: CanonicalAddr,
+    pub creator: Addr,
     pub status: PollStatus,
     pub yes_votes: Uint128,
     pub no_votes: Uint128,
-    pub end_height: u64,
+    // polls created before abstain and veto votes have none of them
+    #[serde(default)]
+    pub abstain_votes: Uint128,
+    #[serde(default)]
+    pub veto_votes: Uint128,
+    // votes are weighted by the shares staked at the start height
+    #[serde(default)]
+    pub start_height: u64,
+    pub end: Expiration,
     pub title: String,
     pub description: String,
     pub link: Option<String>,
@@ -118,6 +391,12 @@ pub struct Poll {
     /// Total balance at the end poll
     pub total_balance_at_end_poll: Option<Uint128>,
     pub staked_amount: Option<Uint128>,
+    // the error of the last failed execution, cleared on a re-attempt
+    #[serde(default)]
+    pub error: Option<String>,
+    // contributions to the deposit, refunded pro rata
+    #[serde(default)]
+    pub deposits: Vec<(Addr, Uint128)>,
 }
 
 // State objects here are good candidates to move to the packages module
@@ -129,6 +408,11 @@ pub enum PollStatus {
     Rejected,
     Executed,
     Expired,
+    Failed,
+    Cancelled,
+    Vetoed,
+    // waiting for the rest of the deposit before the voting starts
+    Deposit,
 }
 
 impl fmt::Display for PollStatus {
@@ -142,14 +426,17 @@ impl fmt::Display for PollStatus {
 pub enum VoteOption {
     Yes,
     No,
+    Abstain,
+    NoWithVeto,
 }
 
 impl fmt::Display for VoteOption {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if *self == VoteOption::Yes {
-            write!(f, "yes")
-        } else {
-            write!(f, "no")
+        match self {
+            VoteOption::Yes => write!(f, "yes"),
+            VoteOption::No => write!(f, "no"),
+            VoteOption::Abstain => write!(f, "abstain"),
+            VoteOption::NoWithVeto => write!(f, "no_with_veto"),
         }
     }
 }
@@ -159,16 +446,20 @@ pub struct PollResponse {
     pub id: u64,
     pub creator: String,
     pub status: PollStatus,
-    pub end_height: u64,
+    pub start_height: u64,
+    pub end: Expiration,
     pub title: String,
     pub description: String,
     pub link: Option<String>,
     pub deposit_amount: Uint128,
     pub execute_data: Option<Vec<PollExecuteMsg>>,
-    pub yes_votes: Uint128, // balance
-    pub no_votes: Uint128,  // balance
+    pub yes_votes: Uint128,     // balance
+    pub no_votes: Uint128,      // balance
+    pub abstain_votes: Uint128, // balance
+    pub veto_votes: Uint128,    // balance
     pub staked_amount: Option<Uint128>,
     pub total_balance_at_end_poll: Option<Uint128>,
+    pub error: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -186,6 +477,60 @@ pub struct StakerResponse {
     pub balance: Uint128,
     pub share: Uint128,
     pub locked_balance: Vec<(u64, VoterInfo)>,
+    pub assets: Vec<StakerAssetResponse>,
+    // the balance and the staked assets by their weight
+    pub voting_power: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct StakerAssetResponse {
+    pub token: String,
+    pub amount: Uint128,
+    // zero once the asset cannot be staked anymore
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct StakerRewardsResponse {
+    pub pending_reward: Uint128,
+    pub pending_native_reward: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct UnbondingsResponse {
+    pub unbondings: Vec<Unbonding>,
+}
+
+/// POL locked until the end height, voting with the remaining duration of the lock
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VeLock {
+    pub amount: Uint128,
+    pub end_height: u64,
+}
+
+impl VeLock {
+    /// Voting power at the height, decaying linearly to none at the end of the lock
+    pub fn power_at(&self, height: u64) -> Uint128 {
+        if self.end_height <= height {
+            return Uint128::zero();
+        }
+        self.amount.multiply_ratio(
+            self.end_height - height,
+            LOCK_WEEK_BLOCKS * LOCK_POWER_WEEKS,
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct VeLockResponse {
+    pub amount: Uint128,
+    pub end_height: u64,
+    pub voting_power: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct TotalVePowerResponse {
+    pub voting_power: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -194,11 +539,22 @@ pub struct ConfigResponse {
     pub pollterra_token: String,
     pub quorum: Decimal,
     pub threshold: Decimal,
-    pub voting_period: u64,
-    pub timelock_period: u64,
-    pub expiration_period: u64,
+    pub veto_threshold: Decimal,
+    pub voting_period: Duration,
+    pub timelock_period: Duration,
+    pub expiration_period: Duration,
     pub proposal_deposit: Uint128,
-    pub snapshot_period: u64,
+    pub snapshot_period: Duration,
+    pub unbonding_period: u64,
+    pub text_poll_params: Option<PollParams>,
+    pub target_poll_params: Vec<TargetPollParamsMsg>,
+    pub deposit_period: Option<Duration>,
+    pub stake_assets: Vec<StakeAssetMsg>,
+    pub max_lock_weeks: u64,
+    pub meta_contract: Option<String>,
+    pub community_contract: Option<String>,
+    pub distributor_contract: Option<String>,
+    pub pause: PauseInfo,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -206,12 +562,16 @@ pub struct StateResponse {
     pub poll_count: u64,
     pub total_share: Uint128,
     pub total_deposit: Uint128,
+    pub pending_reward: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct VoterInfo {
     pub vote: VoteOption,
     pub balance: Uint128,
+    // cast by the delegate of the voter, replaced when the voter votes
+    #[serde(default)]
+    pub delegated: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -219,6 +579,7 @@ pub struct VotersResponseItem {
     pub voter: String,
     pub vote: VoteOption,
     pub balance: Uint128,
+    pub delegated: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -226,147 +587,311 @@ pub struct VotersResponse {
     pub voters: Vec<VotersResponseItem>,
 }
 
-pub fn bank_store(storage: &mut dyn Storage) -> Bucket<TokenManager> {
-    bucket(storage, PREFIX_BANK)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct VoterHistoryResponseItem {
+    pub poll_id: u64,
+    pub status: PollStatus,
+    pub vote: VoteOption,
+    pub balance: Uint128,
+    pub delegated: bool,
 }
 
-pub fn bank_read(storage: &dyn Storage) -> ReadonlyBucket<TokenManager> {
-    bucket_read(storage, PREFIX_BANK)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct VoterHistoryResponse {
+    pub votes: Vec<VoterHistoryResponseItem>,
 }
 
-pub fn config_store(storage: &mut dyn Storage) -> Singleton<Config> {
-    singleton(storage, KEY_CONFIG)
+/// Current votes of a poll against the requirements it must meet to pass.
+/// The quorum is the share of the voting stake which voted, the threshold the share of
+/// the yes votes and the veto the share of the NoWithVeto votes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct TallyResponse {
+    pub poll_id: u64,
+    pub status: PollStatus,
+    pub yes_votes: Uint128,
+    pub no_votes: Uint128,
+    pub abstain_votes: Uint128,
+    pub veto_votes: Uint128,
+    pub staked_amount: Uint128,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub veto: Decimal,
+    pub required_quorum: Decimal,
+    pub required_threshold: Decimal,
+    pub veto_threshold: Decimal,
+    // whether the poll would pass if it ended with these votes
+    pub passing: bool,
 }
 
-pub fn config_read(storage: &dyn Storage) -> ReadonlySingleton<Config> {
-    singleton_read(storage, KEY_CONFIG)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct LockedPoll {
+    pub poll_id: u64,
+    pub end: Expiration,
+    pub vote: VoteOption,
+    pub balance: Uint128,
 }
 
-pub fn state_store(storage: &mut dyn Storage) -> Singleton<State> {
-    singleton(storage, KEY_STATE)
+/// Polls in progress the address voted on, the largest of their balances locks the staked POL
+/// beyond the voting power of the staked assets and the lock until they end
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct LockedPollsResponse {
+    pub locked_balance: Uint128,
+    pub polls: Vec<LockedPoll>,
 }
 
-pub fn state_read(storage: &dyn Storage) -> ReadonlySingleton<State> {
-    singleton_read(storage, KEY_STATE)
-}
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct DelegationResponse {
+    pub delegate: Option<String>,
+    pub delegators: Vec<String>,
+}
+
+/// Checkpoints the share of the staker and the total share at the given height
+pub fn save_staked_share(
+    storage: &mut dyn Storage,
+    staker: &Addr,
+    share: Uint128,
+    total_share: Uint128,
+    height: u64,
+) -> StdResult<()> {
+    STAKED_SHARES.save(storage, staker, &share, height)?;
+    TOTAL_SHARE.save(storage, TOTAL_SHARE_KEY, &total_share, height)
+}
+
+/// Share of the staker at the beginning of the given height
+pub fn staked_share_at(storage: &dyn Storage, staker: &Addr, height: u64) -> StdResult<Uint128> {
+    Ok(STAKED_SHARES
+        .may_load_at_height(storage, staker, height)?
+        .unwrap_or_default())
+}
+
+/// Total share at the beginning of the given height
+pub fn total_share_at(storage: &dyn Storage, height: u64) -> StdResult<Uint128> {
+    Ok(TOTAL_SHARE
+        .may_load_at_height(storage, TOTAL_SHARE_KEY, height)?
+        .unwrap_or_default())
+}
+
+/// Checkpoints the staked amount of the asset of the staker and its total at the given height
+pub fn save_staked_asset(
+    storage: &mut dyn Storage,
+    staker: &Addr,
+    token: &Addr,
+    amount: Uint128,
+    total: Uint128,
+    height: u64,
+) -> StdResult<()> {
+    STAKED_ASSETS.save(storage, (staker, token), &amount, height)?;
+    TOTAL_ASSETS.save(storage, token, &total, height)
+}
+
+/// Staked amount of the asset of the staker at the beginning of the given height
+pub fn staked_asset_at(
+    storage: &dyn Storage,
+    staker: &Addr,
+    token: &Addr,
+    height: u64,
+) -> StdResult<Uint128> {
+    Ok(STAKED_ASSETS
+        .may_load_at_height(storage, (staker, token), height)?
+        .unwrap_or_default())
+}
+
+/// Total staked amount of the asset at the beginning of the given height
+pub fn total_asset_at(storage: &dyn Storage, token: &Addr, height: u64) -> StdResult<Uint128> {
+    Ok(TOTAL_ASSETS
+        .may_load_at_height(storage, token, height)?
+        .unwrap_or_default())
+}
+
+/// Total staked amount of the asset
+pub fn total_asset(storage: &dyn Storage, token: &Addr) -> StdResult<Uint128> {
+    Ok(TOTAL_ASSETS.may_load(storage, token)?.unwrap_or_default())
+}
+
+/// Lock of the staker, also when it has ended but is not withdrawn yet
+pub fn load_ve_lock(storage: &dyn Storage, staker: &Addr) -> StdResult<Option<VeLock>> {
+    VE_LOCKS.may_load(storage, staker)
+}
+
+/// Lock of the staker at the beginning of the given height
+pub fn ve_lock_at(storage: &dyn Storage, staker: &Addr, height: u64) -> StdResult<Option<VeLock>> {
+    VE_LOCKS.may_load_at_height(storage, staker, height)
+}
+
+/// Saves the lock of the staker at the given height, none once it is withdrawn,
+/// and checkpoints the totals of the locks voting from then on
+pub fn save_ve_lock(
+    storage: &mut dyn Storage,
+    staker: &Addr,
+    lock: Option<&VeLock>,
+    height: u64,
+) -> StdResult<()> {
+    let mut totals = VE_TOTALS
+        .may_load(storage, VE_TOTALS_KEY)?
+        .unwrap_or_default();
+    totals.end_locks(storage, height)?;
+
+    // only the locks which have not ended yet are part of the totals
+    if let Some(previous) = VE_LOCKS.may_load(storage, staker)? {
+        if previous.end_height > height {
+            totals.sub(&previous)?;
+            let ending = VE_LOCK_ENDS
+                .load(storage, U64Key::new(previous.end_height))?
+                .checked_sub(previous.amount)?;
+            if ending.is_zero() {
+                VE_LOCK_ENDS.remove(storage, U64Key::new(previous.end_height));
+            } else {
+                VE_LOCK_ENDS.save(storage, U64Key::new(previous.end_height), &ending)?;
+            }
+        }
+    }
+    match lock {
+        Some(lock) => {
+            if lock.end_height > height {
+                totals.add(lock)?;
+                VE_LOCK_ENDS.update(storage, U64Key::new(lock.end_height), |ending| {
+                    StdResult::Ok(ending.unwrap_or_default().checked_add(lock.amount)?)
+                })?;
+            }
+            VE_LOCKS.save(storage, staker, lock, height)?;
+        }
+        None => VE_LOCKS.remove(storage, staker, height)?,
+    }
 
-pub fn poll_store(storage: &mut dyn Storage) -> Bucket<Poll> {
-    bucket(storage, PREFIX_POLL)
+    VE_TOTALS.save(storage, VE_TOTALS_KEY, &totals, height)
 }
 
-pub fn poll_read(storage: &dyn Storage) -> ReadonlyBucket<Poll> {
-    bucket_read(storage, PREFIX_POLL)
+/// Voting power of the lock of the staker at the beginning of the given height
+pub fn ve_power_at(storage: &dyn Storage, staker: &Addr, height: u64) -> StdResult<Uint128> {
+    Ok(ve_lock_at(storage, staker, height)?
+        .map(|lock| lock.power_at(height))
+        .unwrap_or_default())
 }
 
-pub fn poll_voter_store(storage: &mut dyn Storage, poll_id: u64) -> Bucket<VoterInfo> {
-    Bucket::multilevel(storage, &[PREFIX_POLL_VOTER, &poll_id.to_be_bytes()])
-}
+/// Voting power of all the locks at the beginning of the given height
+pub fn total_ve_power_at(storage: &dyn Storage, height: u64) -> StdResult<Uint128> {
+    let mut totals = VE_TOTALS
+        .may_load_at_height(storage, VE_TOTALS_KEY, height)?
+        .unwrap_or_default();
+    totals.end_locks(storage, height)?;
 
-pub fn poll_voter_read(storage: &dyn Storage, poll_id: u64) -> ReadonlyBucket<VoterInfo> {
-    ReadonlyBucket::multilevel(storage, &[PREFIX_POLL_VOTER, &poll_id.to_be_bytes()])
+    let power = totals
+        .weighted_end
+        .checked_sub(totals.amount.checked_mul(height.into())?)?;
+    Ok(power.multiply_ratio(1u64, LOCK_WEEK_BLOCKS * LOCK_POWER_WEEKS))
 }
 
-pub fn poll_indexer_store<'a>(
-    storage: &'a mut dyn Storage,
-    status: &PollStatus,
-) -> Bucket<'a, bool> {
-    Bucket::multilevel(
-        storage,
-        &[PREFIX_POLL_INDEXER, status.to_string().as_bytes()],
-    )
+pub fn read_voter_history(
+    storage: &dyn Storage,
+    voter: &Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, VoterInfo)>> {
+    let range_option = id_range_option(start_after, limit, Some(OrderBy::Desc));
+
+    VOTER_HISTORY
+        .prefix(voter)
+        .range(
+            storage,
+            range_option.min,
+            range_option.max,
+            range_option.order_by,
+        )
+        .take(range_option.limit)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((u64_from_key(&k)?, v))
+        })
+        .collect()
 }
 
-pub fn read_poll_voters<'a>(
-    storage: &'a dyn Storage,
+pub fn read_poll_voters(
+    storage: &dyn Storage,
     poll_id: u64,
-    start_after: Option<CanonicalAddr>,
+    start_after: Option<String>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
-) -> StdResult<Vec<(CanonicalAddr, VoterInfo)>> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let (start, end, order_by) = match order_by {
-        Some(OrderBy::Asc) => (calc_range_start_addr(start_after), None, OrderBy::Asc),
-        _ => (None, calc_range_end_addr(start_after), OrderBy::Desc),
-    };
+) -> StdResult<Vec<(Addr, VoterInfo)>> {
+    let range_option = addr_range_option(start_after, limit, order_by);
 
-    let voters: ReadonlyBucket<'a, VoterInfo> =
-        ReadonlyBucket::multilevel(storage, &[PREFIX_POLL_VOTER, &poll_id.to_be_bytes()]);
-    voters
-        .range(start.as_deref(), end.as_deref(), order_by.into())
-        .take(limit)
+    POLL_VOTERS
+        .prefix(U64Key::new(poll_id))
+        .range(
+            storage,
+            range_option.min,
+            range_option.max,
+            range_option.order_by,
+        )
+        .take(range_option.limit)
         .map(|item| {
             let (k, v) = item?;
-            Ok((CanonicalAddr::from(k), v))
+            Ok((Addr::unchecked(String::from_utf8(k)?), v))
         })
         .collect()
 }
 
-pub fn read_polls<'a>(
-    storage: &'a dyn Storage,
+/// Polls in the order of their ids, only those of the creator or with the status when given
+pub fn read_polls(
+    storage: &dyn Storage,
     filter: Option<PollStatus>,
+    creator: Option<Addr>,
     start_after: Option<u64>,
     limit: Option<u32>,
     order_by: Option<OrderBy>,
 ) -> StdResult<Vec<Poll>> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let (start, end, order_by) = match order_by {
-        Some(OrderBy::Asc) => (calc_range_start(start_after), None, OrderBy::Asc),
-        _ => (None, calc_range_end(start_after), OrderBy::Desc),
+    let range_option = id_range_option(start_after, limit, order_by);
+
+    let prefix = match (&creator, &filter) {
+        (Some(creator), _) => polls().idx.creator.prefix(creator.clone()),
+        (None, Some(status)) => polls().idx.status.prefix(status.to_string()),
+        (None, None) => polls().prefix(()),
     };
 
-    if let Some(status) = filter {
-        let poll_indexer: ReadonlyBucket<'a, bool> = ReadonlyBucket::multilevel(
+    prefix
+        .range(
             storage,
-            &[PREFIX_POLL_INDEXER, status.to_string().as_bytes()],
-        );
-        poll_indexer
-            .range(start.as_deref(), end.as_deref(), order_by.into())
-            .take(limit)
-            .map(|item| {
-                let (k, _) = item?;
-                poll_read(storage).load(&k)
-            })
-            .collect()
-    } else {
-        let polls: ReadonlyBucket<'a, Poll> = ReadonlyBucket::new(storage, PREFIX_POLL);
-
-        polls
-            .range(start.as_deref(), end.as_deref(), order_by.into())
-            .take(limit)
-            .map(|item| {
-                let (_, v) = item?;
-                Ok(v)
-            })
-            .collect()
-    }
-}
-
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_start(start_after: Option<u64>) -> Option<Vec<u8>> {
-    start_after.map(|id| {
-        let mut v = id.to_be_bytes().to_vec();
-        v.push(1);
-        v
-    })
-}
-
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_end(start_after: Option<u64>) -> Option<Vec<u8>> {
-    start_after.map(|id| id.to_be_bytes().to_vec())
+            range_option.min,
+            range_option.max,
+            range_option.order_by,
+        )
+        .map(|item| Ok(item?.1))
+        // the polls of the creator are filtered by status as they are read
+        .filter(|poll: &StdResult<Poll>| match (poll, &filter) {
+            (Ok(poll), Some(status)) => poll.status == *status,
+            _ => true,
+        })
+        .take(range_option.limit)
+        .collect()
 }
 
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_start_addr(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
-    start_after.map(|addr| {
-        let mut v = addr.as_slice().to_vec();
-        v.push(1);
-        v
-    })
+/// Whether some polls still compare their end to the periods of the config
+pub fn has_live_polls(storage: &dyn Storage) -> StdResult<bool> {
+    for status in [
+        PollStatus::InProgress,
+        PollStatus::Passed,
+        PollStatus::Failed,
+    ]
+    .iter()
+    {
+        if polls()
+            .idx
+            .status
+            .prefix(status.to_string())
+            .range(storage, None, None, Order::Ascending)
+            .next()
+            .is_some()
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
 }
 
-// this will set the first key after the provided key, by appending a 1 byte
-fn calc_range_end_addr(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
-    start_after.map(|addr| addr.as_slice().to_vec())
+fn u64_from_key(key: &[u8]) -> StdResult<u64> {
+    let bytes: [u8; 8] = key
+        .try_into()
+        .map_err(|_| StdError::invalid_data_size(8, key.len()))?;
+    Ok(u64::from_be_bytes(bytes))
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -380,8 +905,19 @@ pub enum Cw20HookMsg {
         description: String,
         link: Option<String>,
         execute_msgs: Option<Vec<PollExecuteMsg>>,
+        // typed config changes, executed along with the execute_msgs in order
+        param_changes: Option<Vec<ParamChangeMsg>>,
     },
+    /// AddDeposit adds to the deposit of a poll in its deposit period,
+    /// the voting starts once the whole deposit is reached
+    AddDeposit { poll_id: u64 },
     /// StakeVotingTokens a user can stake their token to receive rewards
     /// or do vote on polls
     StakeVotingTokens {},
+    /// DepositReward distributes the sent tokens to the stakers by their share
+    DepositReward {},
+    /// Lock locks the sent tokens for the weeks, their voting power decays until they unlock
+    Lock { duration: u64 },
+    /// IncreaseLock adds the sent tokens to the lock of the sender until it ends
+    IncreaseLock {},
 }
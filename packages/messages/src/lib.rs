@@ -2,7 +2,9 @@ pub mod community;
 pub mod distributor;
 pub mod governance;
 pub mod meta_contract;
+pub mod migration;
 pub mod msg;
 pub mod opinion_poll;
+pub mod pause;
 pub mod prediction_poll;
 pub mod utils;
@@ -1,13 +1,25 @@
+use super::state::{PollFilter, Role};
+use crate::msg::PollMigrateMsg;
+use crate::pause::PauseOperation;
+use config::config::DepositSplit;
 use cosmwasm_std::Uint128;
 use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    pub reclaimable_threshold: Option<Uint128>,
+    pub community_contract: Option<String>,
+    pub deposit_split: Option<DepositSplit>,
+    // able to pause the contract, kept when unset
+    pub guardian: Option<String>,
+    pub governance: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
+    // granted every role
     pub admins: Vec<String>,
 }
 
@@ -30,6 +42,19 @@ pub enum ExecuteMsg {
         poll_type: String,
         winner: Option<u64>,
     },
+    FinishPolls {
+        entries: Vec<FinishPollEntry>,
+    },
+    FinishExpired {
+        limit: Option<u32>,
+    },
+    // migrates the next `limit` registered polls to `code_id` with `msg`, continuing the previous run
+    MigratePolls {
+        code_id: u64,
+        msg: PollMigrateMsg,
+        filter: Option<PollFilter>,
+        limit: Option<u32>,
+    },
     Transfer {
         recipient: String,
         amount: Uint128,
@@ -37,8 +62,46 @@ pub enum ExecuteMsg {
     UpdateConfig {
         creation_deposit: Option<Uint128>,
         reclaimable_threshold: Option<Uint128>,
-        new_admins: Option<Vec<String>>,
+        community_contract: Option<String>,
+        deposit_split: Option<DepositSplit>,
+    },
+    GrantRole {
+        address: String,
+        role: Role,
+    },
+    RevokeRole {
+        address: String,
+        role: Role,
+    },
+    Pause {
+        operations: Option<Vec<PauseOperation>>,
     },
+    Unpause {
+        operations: Option<Vec<PauseOperation>>,
+    },
+    UpdatePausers {
+        guardian: Option<String>,
+        governance: Option<String>,
+    },
+    // the meta-contract owns the polls, so it pauses them on behalf of its pausers
+    PausePolls {
+        poll_contracts: Vec<String>,
+        operations: Option<Vec<PauseOperation>>,
+    },
+    UnpausePolls {
+        poll_contracts: Vec<String>,
+        operations: Option<Vec<PauseOperation>>,
+    },
+    // hands the guardian and governance of the meta-contract down to the polls
+    UpdatePollPausers {
+        poll_contracts: Vec<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FinishPollEntry {
+    pub poll_contract: String,
+    pub winner: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -51,6 +114,10 @@ pub enum Cw20HookMsg {
         end_time: u64,
         num_side: Option<u64>,
         resolution_time: Option<u64>,
+        // wasm admin of the poll, the meta-contract by default so `MigratePolls` can upgrade it
         poll_admin: Option<String>,
+        // delegated resolvers of the poll, `resolver_threshold` of them must agree (default 1)
+        resolvers: Option<Vec<String>>,
+        resolver_threshold: Option<u32>,
     },
 }
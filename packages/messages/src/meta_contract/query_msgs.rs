@@ -1,4 +1,6 @@
-use super::state::{Config, State};
+use super::state::{Config, PollMigration, PollResolvers, Resolution, Role};
+use crate::pause::PauseInfo;
+use crate::utils::OrderBy;
 use cosmwasm_std::Addr;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -9,12 +11,48 @@ pub enum QueryMsg {
     Config {},
     State {},
     GetContracts {},
+    Roles {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order_by: Option<OrderBy>,
+    },
+    Resolution {
+        poll_contract: String,
+    },
+    PollMigration {},
 }
 
 pub type ConfigResponse = Config;
-pub type StateResponse = State;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StateResponse {
+    pub num_contract: u64,
+    pub pause: PauseInfo,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ContractsResponse {
     pub contracts: Vec<Addr>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoleInfo {
+    pub address: Addr,
+    pub roles: Vec<Role>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RolesResponse {
+    pub roles: Vec<RoleInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResolutionResponse {
+    pub resolvers: Option<PollResolvers>,
+    pub resolution: Option<Resolution>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollMigrationResponse {
+    pub migration: Option<PollMigration>,
+}
@@ -1,16 +1,19 @@
-use cosmwasm_std::{Addr, Decimal, StdResult, Storage, Uint128};
-use cw_storage_plus::{Item, Map};
+use config::config::{DepositSplit, PollType};
+use cosmwasm_std::{Addr, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map, U64Key};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
-    pub admins: Vec<Addr>,
     pub token_contract: String,
     pub creation_deposit: Uint128,
     pub reclaimable_threshold: Uint128,
     pub minimum_bet_amount: Uint128,
     pub tax_percentage: Decimal,
+    pub community_contract: Option<Addr>,
+    pub deposit_split: DepositSplit,
     // TODO : participation requirement of opinion poll
 }
 
@@ -22,9 +25,76 @@ impl Config {
     pub fn load(storage: &dyn Storage) -> StdResult<Config> {
         CONFIG.load(storage)
     }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    // updates the config and grants or revokes roles
+    ConfigAdmin,
+    // finishes polls
+    Resolver,
+    // moves the funds of the contract
+    Treasurer,
+    Pauser,
+}
+
+impl Role {
+    pub fn all() -> Vec<Role> {
+        vec![
+            Role::ConfigAdmin,
+            Role::Resolver,
+            Role::Treasurer,
+            Role::Pauser,
+        ]
+    }
 
-    pub fn is_admin(&self, address: &Addr) -> bool {
-        self.admins.contains(address)
+    pub fn is_granted(&self, storage: &dyn Storage, address: &Addr) -> StdResult<bool> {
+        Ok(ROLES
+            .may_load(storage, address)?
+            .unwrap_or_default()
+            .contains(self))
+    }
+
+    pub fn grant(&self, storage: &mut dyn Storage, address: &Addr) -> StdResult<()> {
+        let mut roles = ROLES.may_load(storage, address)?.unwrap_or_default();
+        if !roles.contains(self) {
+            roles.push(self.clone());
+            ROLES.save(storage, address, &roles)?;
+        }
+        Ok(())
+    }
+
+    pub fn revoke(&self, storage: &mut dyn Storage, address: &Addr) -> StdResult<()> {
+        let mut roles = ROLES.may_load(storage, address)?.unwrap_or_default();
+        roles.retain(|v| v != self);
+        if roles.is_empty() {
+            ROLES.remove(storage, address);
+        } else {
+            ROLES.save(storage, address, &roles)?;
+        }
+        Ok(())
+    }
+
+    // returns the number of addresses holding the role
+    pub fn count(&self, storage: &dyn Storage) -> StdResult<usize> {
+        ROLES
+            .range(storage, None, None, Order::Ascending)
+            .try_fold(0, |count, item| {
+                let (_, roles) = item?;
+                Ok(count + roles.contains(self) as usize)
+            })
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Role::ConfigAdmin => write!(f, "config_admin"),
+            Role::Resolver => write!(f, "resolver"),
+            Role::Treasurer => write!(f, "treasurer"),
+            Role::Pauser => write!(f, "pauser"),
+        }
     }
 }
 
@@ -43,6 +113,182 @@ impl State {
     }
 }
 
+// deposit of a poll creation, persisted until its instantiate reply arrives
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingCreation {
+    pub creator: Addr,
+    pub deposit_amount: Uint128,
+    pub poll_type: PollType,
+    pub end_time: u64,
+    pub resolvers: Option<PollResolvers>,
+}
+
+impl PendingCreation {
+    pub fn save(&self, storage: &mut dyn Storage, reply_id: u64) -> StdResult<()> {
+        PENDING_CREATIONS.save(storage, U64Key::new(reply_id), self)
+    }
+
+    pub fn may_load(storage: &dyn Storage, reply_id: u64) -> StdResult<Option<PendingCreation>> {
+        PENDING_CREATIONS.may_load(storage, U64Key::new(reply_id))
+    }
+
+    pub fn remove(storage: &mut dyn Storage, reply_id: u64) {
+        PENDING_CREATIONS.remove(storage, U64Key::new(reply_id))
+    }
+
+    // returns the sequence number of the next poll creation
+    pub fn next_sequence(storage: &mut dyn Storage) -> StdResult<u64> {
+        let sequence = CREATION_SEQUENCE.may_load(storage)?.unwrap_or_default() + 1;
+        CREATION_SEQUENCE.save(storage, &sequence)?;
+        Ok(sequence)
+    }
+}
+
+// resolvers delegated to finish a single poll, `threshold` of them must agree on the winner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollResolvers {
+    pub poll_type: PollType,
+    pub resolvers: Vec<Addr>,
+    pub threshold: u32,
+    pub approvals: Vec<ResolverApproval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResolverApproval {
+    pub resolver: Addr,
+    pub winner: Option<u64>,
+}
+
+impl PollResolvers {
+    pub fn save(&self, storage: &mut dyn Storage, poll_contract: &Addr) -> StdResult<()> {
+        POLL_RESOLVERS.save(storage, poll_contract, self)
+    }
+
+    pub fn may_load(
+        storage: &dyn Storage,
+        poll_contract: &Addr,
+    ) -> StdResult<Option<PollResolvers>> {
+        POLL_RESOLVERS.may_load(storage, poll_contract)
+    }
+
+    pub fn remove(storage: &mut dyn Storage, poll_contract: &Addr) {
+        POLL_RESOLVERS.remove(storage, poll_contract)
+    }
+
+    pub fn is_resolver(&self, address: &Addr) -> bool {
+        self.resolvers.contains(address)
+    }
+
+    // records the winner approved by the resolver, replacing its previous approval
+    pub fn approve(&mut self, resolver: &Addr, winner: Option<u64>) {
+        self.approvals.retain(|v| &v.resolver != resolver);
+        self.approvals.push(ResolverApproval {
+            resolver: resolver.clone(),
+            winner,
+        });
+    }
+
+    // returns the resolvers who approved the winner
+    pub fn approvers(&self, winner: Option<u64>) -> Vec<Addr> {
+        self.approvals
+            .iter()
+            .filter(|v| v.winner == winner)
+            .map(|v| v.resolver.clone())
+            .collect()
+    }
+}
+
+// who finished a poll, and with which winner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Resolution {
+    pub resolved_by: Vec<Addr>,
+    pub winner: Option<u64>,
+}
+
+impl Resolution {
+    pub fn save(&self, storage: &mut dyn Storage, poll_contract: &Addr) -> StdResult<()> {
+        RESOLUTIONS.save(storage, poll_contract, self)
+    }
+
+    pub fn may_load(storage: &dyn Storage, poll_contract: &Addr) -> StdResult<Option<Resolution>> {
+        RESOLUTIONS.may_load(storage, poll_contract)
+    }
+}
+
+// opinion poll waiting to be finished by `FinishExpired` once its end_time has passed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledFinish {
+    pub contract: Addr,
+    pub end_time: u64,
+}
+
+impl ScheduledFinish {
+    pub fn save(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        FINISH_SCHEDULE.save(storage, (U64Key::new(self.end_time), &self.contract), self)
+    }
+
+    pub fn remove(&self, storage: &mut dyn Storage) {
+        FINISH_SCHEDULE.remove(storage, (U64Key::new(self.end_time), &self.contract))
+    }
+}
+
+// expired opinion poll finished by `FinishExpired`, persisted until the reply of its FinishPoll
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingFinish {
+    pub scheduled: ScheduledFinish,
+    pub resolved_by: Addr,
+}
+
+impl PendingFinish {
+    pub fn save(&self, storage: &mut dyn Storage, reply_id: u64) -> StdResult<()> {
+        PENDING_FINISHES.save(storage, U64Key::new(reply_id), self)
+    }
+
+    pub fn may_load(storage: &dyn Storage, reply_id: u64) -> StdResult<Option<PendingFinish>> {
+        PENDING_FINISHES.may_load(storage, U64Key::new(reply_id))
+    }
+
+    pub fn remove(storage: &mut dyn Storage, reply_id: u64) {
+        PENDING_FINISHES.remove(storage, U64Key::new(reply_id))
+    }
+}
+
+// registered polls selected by `MigratePolls`, every poll when unset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PollFilter {
+    pub poll_type: Option<PollType>,
+}
+
+// progress of a `MigratePolls` run, registered polls are migrated in address order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollMigration {
+    pub code_id: u64,
+    pub filter: PollFilter,
+    pub last_contract: Option<Addr>,
+    // replies of the dispatched migrations
+    pub num_migrated: u64,
+    pub num_failed: u64,
+    pub finished: bool,
+}
+
+impl PollMigration {
+    pub fn save(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        POLL_MIGRATION.save(storage, self)
+    }
+
+    pub fn may_load(storage: &dyn Storage) -> StdResult<Option<PollMigration>> {
+        POLL_MIGRATION.may_load(storage)
+    }
+}
+
 pub const STATE: Item<State> = Item::new("state");
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const CONTRACTS: Map<&Addr, ()> = Map::new("contracts");
+pub const ROLES: Map<&Addr, Vec<Role>> = Map::new("roles");
+pub const POLL_RESOLVERS: Map<&Addr, PollResolvers> = Map::new("poll_resolvers");
+pub const RESOLUTIONS: Map<&Addr, Resolution> = Map::new("resolutions");
+pub const PENDING_CREATIONS: Map<U64Key, PendingCreation> = Map::new("pending_creations"); // reply_id: creation
+pub const CREATION_SEQUENCE: Item<u64> = Item::new("creation_sequence");
+pub const POLL_MIGRATION: Item<PollMigration> = Item::new("poll_migration");
+pub const FINISH_SCHEDULE: Map<(U64Key, &Addr), ScheduledFinish> = Map::new("finish_schedule"); // (end_time, contract): schedule
+pub const PENDING_FINISHES: Map<U64Key, PendingFinish> = Map::new("pending_finishes"); // reply_id: finish
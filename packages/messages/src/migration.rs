@@ -0,0 +1,114 @@
+use config::config::{DepositSplit, PollConfig, PollType};
+use cosmwasm_std::{Addr, Decimal, StdError, StdResult, Storage, Uint128};
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Semantic version of a contract, pre-release and build metadata are ignored
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Version {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn parse(version: &str) -> StdResult<Version> {
+        let core = version.split(['-', '+'].as_ref()).next().unwrap_or("");
+        let numbers = core
+            .split('.')
+            .map(|v| v.parse::<u64>())
+            .collect::<Result<Vec<u64>, _>>()
+            .map_err(|_| StdError::parse_err("Version", version))?;
+
+        match numbers.as_slice() {
+            [major, minor, patch] => Ok(Version::new(*major, *minor, *patch)),
+            _ => Err(StdError::parse_err("Version", version)),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Checks the stored cw2 version against the new one and stores it.
+/// Migrating from another contract or to an older version is refused.
+/// Returns the version the contract is migrated from.
+pub fn migrate_version(
+    storage: &mut dyn Storage,
+    contract_name: &str,
+    contract_version: &str,
+) -> StdResult<Version> {
+    let stored = get_contract_version(storage)?;
+    if stored.contract != contract_name {
+        return Err(StdError::generic_err(format!(
+            "Cannot migrate from {} to {}",
+            stored.contract, contract_name
+        )));
+    }
+
+    let from = Version::parse(&stored.version)?;
+    let to = Version::parse(contract_version)?;
+    if to < from {
+        return Err(StdError::generic_err(format!(
+            "Cannot downgrade from {} to {}",
+            from, to
+        )));
+    }
+
+    set_contract_version(storage, contract_name, contract_version)?;
+
+    Ok(from)
+}
+
+// PollConfig of both poll contracts before 0.2.0
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct PollConfigV0_1 {
+    owner: Addr,
+    generator: Addr,
+    token_contract: String,
+    reclaimable_threshold: Uint128,
+    poll_name: String,
+    poll_type: PollType,
+    end_time: u64,
+    num_side: u64,
+    resolution_time: u64,
+    minimum_bet_amount: Uint128,
+    tax_percentage: Decimal,
+}
+
+const POLL_CONFIG_V0_1: Item<PollConfigV0_1> = Item::new("config");
+
+/// Converts the stored config of a poll to 0.2.0, the deposit is burnt by default
+pub fn migrate_poll_config_v0_2(storage: &dyn Storage) -> StdResult<PollConfig> {
+    let legacy = POLL_CONFIG_V0_1.load(storage)?;
+
+    Ok(PollConfig {
+        owner: legacy.owner,
+        generator: legacy.generator,
+        token_contract: legacy.token_contract,
+        reclaimable_threshold: legacy.reclaimable_threshold,
+        poll_name: legacy.poll_name,
+        poll_type: legacy.poll_type,
+        end_time: legacy.end_time,
+        num_side: legacy.num_side,
+        resolution_time: legacy.resolution_time,
+        minimum_bet_amount: legacy.minimum_bet_amount,
+        tax_percentage: legacy.tax_percentage,
+        community_contract: None,
+        deposit_split: DepositSplit::burn_all(),
+    })
+}
@@ -1,4 +1,4 @@
-use config::config::PollType;
+use config::config::{DepositSplit, PollType};
 use cosmwasm_std::{Addr, Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -17,5 +17,20 @@ pub struct PollInstantiateMsg {
     pub resolution_time: Option<u64>,
     pub minimum_bet_amount: Option<Uint128>,
     pub tax_percentage: Option<Decimal>,
+    // split of the deposit when the poll doesn't reach the reclaimable threshold, burn all by default
+    pub community_contract: Option<String>,
+    pub deposit_split: Option<DepositSplit>,
+    // able to pause the poll along with its owner
+    pub guardian: Option<String>,
+    pub governance: Option<String>,
     // TODO : participation requirements for opinion poll
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PollMigrateMsg {
+    pub community_contract: Option<String>,
+    pub deposit_split: Option<DepositSplit>,
+    // able to pause the poll along with its owner, kept when unset
+    pub guardian: Option<String>,
+    pub governance: Option<String>,
+}
@@ -1,19 +1,33 @@
-use crate::msg::PollInstantiateMsg;
+use crate::msg::{PollInstantiateMsg, PollMigrateMsg};
+use crate::pause::PauseOperation;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub type MigrateMsg = PollMigrateMsg;
 
 pub type InstantiateMsg = PollInstantiateMsg;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    Vote { side: u64 },
+    Vote {
+        side: u64,
+    },
     FinishPoll {},
     // TODO : only for internal QA
     ForceFinishPoll {},
     ReclaimDeposit {},
-    TransferOwner { new_owner: String },
+    TransferOwner {
+        new_owner: String,
+    },
+    Pause {
+        operations: Option<Vec<PauseOperation>>,
+    },
+    Unpause {
+        operations: Option<Vec<PauseOperation>>,
+    },
+    UpdatePausers {
+        guardian: Option<String>,
+        governance: Option<String>,
+    },
 }
@@ -1,4 +1,5 @@
-use super::state::{Config, State};
+use super::state::Config;
+use crate::pause::PauseInfo;
 use cosmwasm_std::Uint128;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -44,4 +45,13 @@ pub struct VotePerSideResponse {
 }
 
 pub type ConfigResponse = Config;
-pub type StateResponse = State;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StateResponse {
+    pub deposit_amount: Uint128,
+    pub deposit_reclaimed: bool,
+    pub status: PollStatus,
+    pub total_amount: Uint128,
+    pub winning_side: Option<Vec<u64>>,
+    pub pause: PauseInfo,
+}
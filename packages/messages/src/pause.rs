@@ -0,0 +1,141 @@
+use cosmwasm_std::{Addr, Response, StdError, StdResult, Storage};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseOperation {
+    Betting,
+    Claiming,
+    Staking,
+    Distributing,
+    Voting,
+    Creating,
+}
+
+impl fmt::Display for PauseOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PauseOperation::Betting => write!(f, "betting"),
+            PauseOperation::Claiming => write!(f, "claiming"),
+            PauseOperation::Staking => write!(f, "staking"),
+            PauseOperation::Distributing => write!(f, "distributing"),
+            PauseOperation::Voting => write!(f, "voting"),
+            PauseOperation::Creating => write!(f, "creating"),
+        }
+    }
+}
+
+/// Emergency pause of a contract.
+/// `global` halts every guarded operation, `operations` halt only the listed ones.
+/// The guardian and governance are able to pause and unpause.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseInfo {
+    pub guardian: Option<Addr>,
+    pub governance: Option<Addr>,
+    pub global: bool,
+    pub operations: Vec<PauseOperation>,
+}
+
+impl PauseInfo {
+    pub fn save(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        PAUSE_INFO.save(storage, self)
+    }
+
+    // contracts instantiated before the pause existed are not paused
+    pub fn load(storage: &dyn Storage) -> StdResult<PauseInfo> {
+        Ok(PAUSE_INFO.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn is_pauser(&self, address: &Addr) -> bool {
+        self.guardian.as_ref() == Some(address) || self.governance.as_ref() == Some(address)
+    }
+
+    pub fn is_paused(&self, operation: &PauseOperation) -> bool {
+        self.global || self.operations.contains(operation)
+    }
+
+    pub fn assert_active(storage: &dyn Storage, operation: PauseOperation) -> StdResult<()> {
+        if PauseInfo::load(storage)?.is_paused(&operation) {
+            return Err(StdError::generic_err(format!("{} is paused", operation)));
+        }
+        Ok(())
+    }
+}
+
+/// Pauses or unpauses the operations, or the whole contract when no operation is given.
+/// The caller is expected to be authorized by the contract.
+pub fn set_paused(
+    storage: &mut dyn Storage,
+    paused: bool,
+    operations: Option<Vec<PauseOperation>>,
+) -> StdResult<Response> {
+    let mut pause_info = PauseInfo::load(storage)?;
+
+    let mut response = Response::new().add_attribute(
+        "method",
+        match paused {
+            true => "pause",
+            false => "unpause",
+        },
+    );
+
+    match operations {
+        None => pause_info.global = paused,
+        Some(operations) => {
+            response = response.add_attribute(
+                "operations",
+                operations
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+            for operation in operations {
+                pause_info.operations.retain(|v| v != &operation);
+                if paused {
+                    pause_info.operations.push(operation);
+                }
+            }
+        }
+    }
+
+    pause_info.save(storage)?;
+
+    Ok(response)
+}
+
+/// Replaces the guardian and governance allowed to pause.
+/// The caller is expected to be authorized by the contract.
+pub fn set_pausers(
+    storage: &mut dyn Storage,
+    guardian: Option<Addr>,
+    governance: Option<Addr>,
+) -> StdResult<Response> {
+    let mut pause_info = PauseInfo::load(storage)?;
+    pause_info.guardian = guardian;
+    pause_info.governance = governance;
+    pause_info.save(storage)?;
+
+    Ok(Response::new().add_attribute("method", "update_pausers"))
+}
+
+/// Sets the pausers given to a migration, the others are kept.
+pub fn migrate_pausers(
+    storage: &mut dyn Storage,
+    guardian: Option<Addr>,
+    governance: Option<Addr>,
+) -> StdResult<()> {
+    let mut pause_info = PauseInfo::load(storage)?;
+    if guardian.is_some() {
+        pause_info.guardian = guardian;
+    }
+    if governance.is_some() {
+        pause_info.governance = governance;
+    }
+    pause_info.save(storage)
+}
+
+pub const PAUSE_INFO: Item<PauseInfo> = Item::new("pause_info");
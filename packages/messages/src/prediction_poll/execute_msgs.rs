@@ -1,22 +1,42 @@
-use crate::msg::PollInstantiateMsg;
+use crate::msg::{PollInstantiateMsg, PollMigrateMsg};
+use crate::pause::PauseOperation;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub type MigrateMsg = PollMigrateMsg;
 
 pub type InstantiateMsg = PollInstantiateMsg;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    Bet { side: u64 },
-    FinishPoll { winner: u64 },
+    Bet {
+        side: u64,
+    },
+    FinishPoll {
+        winner: u64,
+    },
     // TODO : only for internal QA
-    ForceFinishPoll { winner: u64 },
+    ForceFinishPoll {
+        winner: u64,
+    },
     RevertPoll {},
     Claim {},
     ReclaimDeposit {},
-    TransferOwner { new_owner: String },
-    SetMinimumBet { amount: u128 },
+    TransferOwner {
+        new_owner: String,
+    },
+    SetMinimumBet {
+        amount: u128,
+    },
+    Pause {
+        operations: Option<Vec<PauseOperation>>,
+    },
+    Unpause {
+        operations: Option<Vec<PauseOperation>>,
+    },
+    UpdatePausers {
+        guardian: Option<String>,
+        governance: Option<String>,
+    },
 }
@@ -1,4 +1,6 @@
-use super::state::{BetStatus, Config, State};
+use super::state::{BetStatus, Config};
+use crate::pause::PauseInfo;
+use config::config::PollStatus;
 use cosmwasm_std::Uint128;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -48,4 +50,13 @@ pub struct VotePerSideResponse {
 }
 
 pub type ConfigResponse = Config;
-pub type StateResponse = State;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StateResponse {
+    pub deposit_amount: Uint128,
+    pub deposit_reclaimed: bool,
+    pub status: PollStatus,
+    pub total_amount: Uint128,
+    pub winning_side: Option<Vec<u64>>,
+    pub pause: PauseInfo,
+}
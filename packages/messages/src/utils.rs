@@ -1,5 +1,8 @@
-use cosmwasm_std::{Addr, Order, QuerierWrapper, StdResult, Uint128};
-use cw20::Cw20QueryMsg;
+use config::config::PollConfig;
+use cosmwasm_std::{
+    to_binary, Addr, CosmosMsg, Order, QuerierWrapper, Response, StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20QueryMsg};
 use cw_storage_plus::Bound;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -29,6 +32,67 @@ pub fn query_cw20_balance(
     Ok(response.balance)
 }
 
+// burns the unreclaimable deposit of a poll,
+// sending the configured shares to the community contract and the poll generator
+pub fn split_deposit(
+    response: Response,
+    config: &PollConfig,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let (mut burn_amount, mut community_amount, creator_amount) =
+        config.deposit_split.split(amount);
+
+    // without a community contract, its share is burnt as well
+    if config.community_contract.is_none() {
+        burn_amount += community_amount;
+        community_amount = Uint128::zero();
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !burn_amount.is_zero() {
+        msgs.push(cw20_execute_msg(
+            &config.token_contract,
+            &Cw20ExecuteMsg::Burn {
+                amount: burn_amount,
+            },
+        )?);
+    }
+    if let Some(community_contract) = config.community_contract.as_ref() {
+        if !community_amount.is_zero() {
+            msgs.push(cw20_execute_msg(
+                &config.token_contract,
+                &Cw20ExecuteMsg::Transfer {
+                    recipient: community_contract.to_string(),
+                    amount: community_amount,
+                },
+            )?);
+        }
+    }
+    if !creator_amount.is_zero() {
+        msgs.push(cw20_execute_msg(
+            &config.token_contract,
+            &Cw20ExecuteMsg::Transfer {
+                recipient: config.generator.to_string(),
+                amount: creator_amount,
+            },
+        )?);
+    }
+
+    Ok(response
+        .add_attribute("burn_amount", burn_amount)
+        .add_attribute("community_amount", community_amount)
+        .add_attribute("creator_amount", creator_amount)
+        .add_messages(msgs))
+}
+
+fn cw20_execute_msg(token_contract: &str, msg: &Cw20ExecuteMsg) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: token_contract.to_string(),
+        msg: to_binary(msg)?,
+        funds: vec![],
+    }))
+}
+
 pub struct RangeOption {
     pub limit: usize,
     pub min: Option<Bound>,
@@ -55,3 +119,23 @@ pub fn addr_range_option(
         order_by,
     }
 }
+
+pub fn id_range_option(
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order_by: Option<OrderBy>,
+) -> RangeOption {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(Bound::exclusive_int);
+    let (min, max, order_by) = match order_by {
+        Some(OrderBy::Asc) => (start_after, None, Order::Ascending),
+        _ => (None, start_after, Order::Descending),
+    };
+
+    RangeOption {
+        limit,
+        min,
+        max,
+        order_by,
+    }
+}